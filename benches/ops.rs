@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use hydroperx_path::{normalize_path, FlexGlob, FlexPath, FlexPathVariant};
+use std::hint::black_box;
+
+fn relative(c: &mut Criterion) {
+    let from = FlexPath::new_common("/a/b/c/d");
+    c.bench_function("relative common", |b| b.iter(|| from.relative(black_box("/a/b/x/y/z"))));
+    let from = FlexPath::new(r"C:\a\b\c\d", FlexPathVariant::Windows);
+    c.bench_function("relative windows", |b| b.iter(|| from.relative(black_box(r"C:\a\b\x\y\z"))));
+    let from = FlexPath::new(r"\\?\C:\a\b\c\d", FlexPathVariant::Windows);
+    c.bench_function("relative verbatim", |b| b.iter(|| from.relative(black_box(r"\\?\C:\a\b\x"))));
+}
+
+fn glob(c: &mut Criterion) {
+    let glob = FlexGlob::new("src/**/*.{rs,toml}", FlexPathVariant::Common).unwrap();
+    let short = FlexPath::new_common("src/lib.rs");
+    let long = FlexPath::from_n_common(["src", &"nested/".repeat(32), "mod.rs"]);
+    c.bench_function("glob match short", |b| b.iter(|| glob.is_match(black_box(&short))));
+    c.bench_function("glob match long", |b| b.iter(|| glob.is_match(black_box(&long))));
+    c.bench_function("glob compile", |b| b.iter(|| FlexGlob::new(black_box("src/**/*.{rs,toml}"), FlexPathVariant::Common)));
+}
+
+fn normalize(c: &mut Criterion) {
+    c.bench_function("normalize_path drive", |b| b.iter(|| normalize_path(black_box(r"C:/Program Files/App/../bin/"))));
+    c.bench_function("normalize_path relative", |b| b.iter(|| normalize_path(black_box("a/b/../c"))));
+}
+
+criterion_group!(benches, relative, glob, normalize);
+criterion_main!(benches);
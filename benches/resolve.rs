@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use hydroperx_path::{FlexPath, FlexPathVariant};
+use std::hint::black_box;
+
+fn long_path(segments: usize) -> String {
+    (0..segments).map(|i| if i % 7 == 6 { "..".to_owned() } else { format!("segment{i}") }).collect::<Vec<_>>().join("/")
+}
+
+fn resolve(c: &mut Criterion) {
+    let short = "a/b/../c/./d.txt";
+    let long = long_path(256);
+    c.bench_function("resolve short common", |b| b.iter(|| FlexPath::new(black_box(short), FlexPathVariant::Common)));
+    c.bench_function("resolve long common", |b| b.iter(|| FlexPath::new(black_box(&long), FlexPathVariant::Common)));
+    let long_windows = r"C:\".to_owned() + &long.replace('/', "\\");
+    c.bench_function("resolve long windows", |b| b.iter(|| FlexPath::new(black_box(&long_windows), FlexPathVariant::Windows)));
+    for prefix in [r"\\?\C:\", r"\\server\share\"] {
+        let path = prefix.to_owned() + short;
+        c.bench_function(&format!("resolve short {prefix}"), |b| b.iter(|| FlexPath::new(black_box(&path), FlexPathVariant::Windows)));
+    }
+    let base = FlexPath::new_common("/base/dir");
+    c.bench_function("resolve relative to base", |b| b.iter(|| base.resolve(black_box(short))));
+    let mut out = String::new();
+    c.bench_function("resolve into buffer", |b| b.iter(|| {
+        out.clear();
+        base.resolve_into(black_box(short), &mut out);
+    }));
+}
+
+fn resolve_n(c: &mut Criterion) {
+    let parts = ["/root", "a/b", "../c", "d/./e", "f.txt"];
+    c.bench_function("resolve_n common", |b| b.iter(|| FlexPath::from_n(black_box(parts), FlexPathVariant::Common)));
+    let parts = [r"C:\root", r"a\b", r"..\c", r"d\.\e", "f.txt"];
+    c.bench_function("resolve_n windows", |b| b.iter(|| FlexPath::from_n(black_box(parts), FlexPathVariant::Windows)));
+}
+
+criterion_group!(benches, resolve, resolve_n);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+/*!
+This module contains the discovery of ancestors containing marker
+entries, such as the root of a workspace.
+*/
+
+use super::{AscendPolicy, FlexPath};
+
+impl FlexPath {
+    /// Returns the nearest ancestor of this path, starting with the path
+    /// itself, that contains one of the `marker_names`, such as `Cargo.toml`
+    /// or `.git`, as reported by `exists`, which is given the path of each
+    /// candidate marker.
+    ///
+    /// Markers are tried in the given order within each ancestor, and the
+    /// walk stops at the root, or at the empty path for a relative path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let files = ["/work/app/Cargo.toml", "/work/.git"].map(FlexPath::new_common);
+    /// let dir = FlexPath::new_common("/work/app/src/bin");
+    /// let exists = |path: &FlexPath| files.contains(path);
+    /// assert_eq!(Some(FlexPath::new_common("/work/app")), dir.find_ancestor_containing(&["Cargo.toml"], exists));
+    /// assert_eq!(Some(FlexPath::new_common("/work")), dir.find_ancestor_containing(&[".git"], exists));
+    /// assert_eq!(None, dir.find_ancestor_containing(&[".hg"], exists));
+    /// ```
+    pub fn find_ancestor_containing<F>(&self, marker_names: &[&str], mut exists: F) -> Option<FlexPath>
+    where
+        F: FnMut(&FlexPath) -> bool,
+    {
+        self.try_find_ancestor_containing(marker_names, |path| Ok::<_, std::convert::Infallible>(exists(path))).unwrap_or_else(|never| match never {})
+    }
+
+    /// Returns the nearest ancestor of this path, starting with the path
+    /// itself, that contains one of the `marker_names` on the file system,
+    /// as in [`.find_ancestor_containing`](Self::find_ancestor_containing).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant or if
+    /// the existence of a marker can be neither confirmed nor denied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let src = FlexPath::new_native(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+    /// let root = src.discover_ancestor_containing(&["Cargo.toml"]).unwrap();
+    /// assert_eq!(Some(FlexPath::new_native(env!("CARGO_MANIFEST_DIR"))), root);
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn discover_ancestor_containing(&self, marker_names: &[&str]) -> std::io::Result<Option<FlexPath>> {
+        self.try_find_ancestor_containing(marker_names, FlexPath::exists)
+    }
+
+    fn try_find_ancestor_containing<F, E>(&self, marker_names: &[&str], mut exists: F) -> Result<Option<FlexPath>, E>
+    where
+        F: FnMut(&FlexPath) -> Result<bool, E>,
+    {
+        let mut dir = self.clone();
+        loop {
+            for marker in marker_names {
+                if exists(&dir.resolve(marker))? {
+                    return Ok(Some(dir));
+                }
+            }
+            match dir.ascend(1, AscendPolicy::Reject) {
+                Ok((parent, _)) => dir = parent,
+                Err(_) => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn markers() {
+        let windows = FlexPathVariant::Windows;
+        let mut checked = Vec::new();
+        let found = FlexPath::new(r"C:\a\b", windows).find_ancestor_containing(&["x", "y"], |path| {
+            checked.push(path.to_string());
+            false
+        });
+        assert_eq!(None, found);
+        assert_eq!(vec![r"C:\a\b\x", r"C:\a\b\y", r"C:\a\x", r"C:\a\y", r"C:\x", r"C:\y"], checked);
+        let found = FlexPath::new_common("a/b").find_ancestor_containing(&["m"], |path| path == &FlexPath::new_common("m"));
+        assert_eq!(Some(FlexPath::new_common("")), found);
+    }
+}
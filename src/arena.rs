@@ -0,0 +1,118 @@
+/*!
+This module contains the `PathArena` allocator.
+*/
+
+use super::{flexible, FlexPath, FlexPathRef, FlexPathVariant};
+use std::cell::RefCell;
+
+const CHUNK_CAPACITY: usize = 4096;
+
+/// Owns the text of many [`FlexPathRef`]s created during a batch
+/// operation, so that all of them are freed at once when the
+/// arena is dropped.
+///
+/// Paths are resolved directly into large chunks, avoiding one heap
+/// allocation per path.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPathVariant, PathArena};
+/// let arena = PathArena::new();
+/// let a = arena.alloc("a/b/../c", FlexPathVariant::Common);
+/// let b = arena.alloc_n(["x", "y"], FlexPathVariant::Windows);
+/// assert_eq!("a/c", a.to_string());
+/// assert_eq!(r"x\y", b.to_string());
+/// ```
+#[derive(Default)]
+pub struct PathArena {
+    chunks: RefCell<Vec<String>>,
+}
+
+impl PathArena {
+    /// Constructs an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `path` directly into the arena.
+    pub fn alloc(&self, path: &str, variant: FlexPathVariant) -> FlexPathRef<'_> {
+        FlexPathRef(self.alloc_with(path.len() + 1, |out| flexible::resolve_n_into([path], variant, out)), variant)
+    }
+
+    /// Resolves multiple paths as in [`FlexPath::from_n`] directly into the arena.
+    pub fn alloc_n<T: IntoIterator<Item = S>, S: AsRef<str>>(&self, paths: T, variant: FlexPathVariant) -> FlexPathRef<'_> {
+        let paths = paths.into_iter().collect::<Vec<S>>();
+        // each path adds at most its length and one separator.
+        let max_len = paths.iter().map(|path| path.as_ref().len() + 1).sum();
+        FlexPathRef(self.alloc_with(max_len, |out| flexible::resolve_n_into(paths, variant, out)), variant)
+    }
+
+    /// Copies an already resolved path into the arena.
+    pub fn alloc_path(&self, path: &FlexPath) -> FlexPathRef<'_> {
+        FlexPathRef(self.alloc_with(path.0.len(), |out| out.push_str(&path.0)), path.1)
+    }
+
+    /// Returns the number of bytes of path text stored in the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.len()).sum()
+    }
+
+    /// Appends text of at most `max_len` bytes with `write` to a chunk
+    /// with enough spare capacity, returning the text.
+    fn alloc_with(&self, max_len: usize, write: impl FnOnce(&mut String)) -> &str {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.last().is_none_or(|chunk| chunk.capacity() - chunk.len() < max_len) {
+            chunks.push(String::with_capacity(usize::max(CHUNK_CAPACITY, max_len)));
+        }
+        let chunk = chunks.last_mut().unwrap();
+        let (start, capacity) = (chunk.len(), chunk.capacity());
+        write(chunk);
+        assert!(chunk.capacity() == capacity, "hydroperx_path::PathArena wrote more than the reserved length");
+        let ptr = chunk[start..].as_ptr();
+        // SAFETY: a chunk never grows past its initial capacity, as checked
+        // above, so its buffer is never reallocated, and chunks are only
+        // dropped with the arena, which the returned lifetime is bound to.
+        // The bytes are part of a `String`, so they are valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(ptr, chunk.len() - start)) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let arena = PathArena::new();
+        let long = "x".repeat(CHUNK_CAPACITY + 1);
+        let paths: Vec<_> = (0..100).map(|i| arena.alloc(&format!("/dir/{i}"), FlexPathVariant::Common)).collect();
+        let big = arena.alloc(&long, FlexPathVariant::Common);
+        let after = arena.alloc("/after", FlexPathVariant::Common);
+        assert_eq!("/dir/42", paths[42].to_string());
+        assert_eq!(long, big.to_string());
+        assert_eq!("/after", after.to_flex_path().to_string());
+    }
+
+    #[test]
+    fn resolves_in_place() {
+        let arena = PathArena::new();
+        let windows = FlexPathVariant::Windows;
+        for path in ["C:a", "//srv/share/", r"\\?\c:\a\..\b", r"\\.\pipe\x", r"\a\..\.."] {
+            assert_eq!(FlexPath::new(path, windows).to_string(), arena.alloc(path, windows).to_string(), "{path}");
+        }
+        assert_eq!(r"D:\y", arena.alloc_n(["C:x", "..", r"D:\y"], windows).to_string());
+        assert_eq!("/b", arena.alloc_n(["a", "/b", "c/.."], FlexPathVariant::Common).to_string());
+    }
+
+    #[cfg(feature = "counters")]
+    #[test]
+    fn no_allocation_per_path() {
+        use crate::counters::count_allocations;
+        let arena = PathArena::new();
+        // the first path allocates the chunk and initializes the regex caches.
+        arena.alloc(r"C:\warm", FlexPathVariant::Windows);
+        assert_eq!(0, count_allocations(|| arena.alloc("/a/./b/../c", FlexPathVariant::Common)).1);
+        assert_eq!(0, count_allocations(|| arena.alloc(r"C:\a\..\b", FlexPathVariant::Windows)).1);
+    }
+}
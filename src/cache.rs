@@ -0,0 +1,182 @@
+/*!
+This module contains the `PathCache` memoizing path resolution.
+*/
+
+use super::{FlexPath, FlexPathVariant};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+/// Counters describing the use of a [`PathCache`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct PathCacheStats {
+    /// The number of lookups answered from the cache.
+    pub hits: u64,
+    /// The number of lookups that resolved their input.
+    pub misses: u64,
+    /// The number of entries evicted to respect the capacity.
+    pub evictions: u64,
+    /// The number of entries currently in the cache.
+    pub len: usize,
+}
+
+#[derive(Default)]
+struct State {
+    /// The entries of each variant, keyed by raw input, with the tick of
+    /// their last use.
+    entries: [HashMap<String, (FlexPath, u64)>; 2],
+    /// The entries in order of last use, from least recent.
+    order: BTreeMap<u64, (FlexPathVariant, String)>,
+    tick: u64,
+    stats: PathCacheStats,
+}
+
+fn variant_index(variant: FlexPathVariant) -> usize {
+    match variant {
+        FlexPathVariant::Common => 0,
+        FlexPathVariant::Windows => 1,
+    }
+}
+
+/// A thread-safe cache of resolved paths keyed by raw input, evicting
+/// the least recently used entry beyond a fixed capacity, for inputs that
+/// recur many times, such as paths in compiler output or logs.
+///
+/// Resolution happens outside the cache's lock, so concurrent misses do
+/// not wait for each other.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPathVariant, PathCache};
+/// let cache = PathCache::new(2);
+/// let common = FlexPathVariant::Common;
+/// assert_eq!("src/a.rs", cache.resolve("./src//a.rs", common).to_string());
+/// cache.resolve("./src//a.rs", common);
+/// cache.resolve("b.rs", common);
+/// cache.resolve("c.rs", common);
+/// let stats = cache.stats();
+/// assert_eq!((1, 3, 1, 2), (stats.hits, stats.misses, stats.evictions, stats.len));
+/// ```
+pub struct PathCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+impl PathCache {
+    /// The capacity of the cache returned by [`PathCache::global`].
+    pub const GLOBAL_CAPACITY: usize = 4096;
+
+    /// Constructs an empty cache holding at most `capacity` entries. A
+    /// cache of zero capacity resolves every input.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, state: Mutex::new(State::default()) }
+    }
+
+    /// Returns a process-wide cache of [`PathCache::GLOBAL_CAPACITY`]
+    /// entries, constructed on first use.
+    pub fn global() -> &'static PathCache {
+        static GLOBAL: OnceLock<PathCache> = OnceLock::new();
+        GLOBAL.get_or_init(|| PathCache::new(Self::GLOBAL_CAPACITY))
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // the state is consistent between statements, so a panic while
+        // holding the lock cannot corrupt it.
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Resolves `input` as [`FlexPath::new`] does, answering from the
+    /// cache when the same input was resolved with the same variant.
+    pub fn resolve(&self, input: &str, variant: FlexPathVariant) -> FlexPath {
+        {
+            let mut state = self.state();
+            state.tick += 1;
+            let tick = state.tick;
+            let State { entries, order, stats, .. } = &mut *state;
+            if let Some((path, last_use)) = entries[variant_index(variant)].get_mut(input) {
+                let key = order.remove(last_use).expect("every entry is ordered");
+                order.insert(tick, key);
+                *last_use = tick;
+                stats.hits += 1;
+                return path.clone();
+            }
+            stats.misses += 1;
+        }
+        let path = FlexPath::new(input, variant);
+        if self.capacity == 0 {
+            return path;
+        }
+        let mut state = self.state();
+        state.tick += 1;
+        let tick = state.tick;
+        let State { entries, order, stats, .. } = &mut *state;
+        // another thread may have inserted the same input meanwhile.
+        if let Some((_, last_use)) = entries[variant_index(variant)].insert(input.to_owned(), (path.clone(), tick)) {
+            order.remove(&last_use);
+        }
+        order.insert(tick, (variant, input.to_owned()));
+        while order.len() > self.capacity {
+            let (_, (variant, input)) = order.pop_first().expect("the cache is not empty");
+            entries[variant_index(variant)].remove(&input);
+            stats.evictions += 1;
+        }
+        stats.len = order.len();
+        path
+    }
+
+    /// Returns the counters of the cache.
+    pub fn stats(&self) -> PathCacheStats {
+        self.state().stats
+    }
+
+    /// Removes every entry, keeping the counters.
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.entries = Default::default();
+        state.order.clear();
+        state.stats.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn least_recently_used() {
+        let cache = PathCache::new(2);
+        let (common, windows) = (FlexPathVariant::Common, FlexPathVariant::Windows);
+        cache.resolve("C:/a", common);
+        assert_eq!(FlexPath::new("C:/a", windows), cache.resolve("C:/a", windows));
+        cache.resolve("C:/a", common);
+        // the Windows entry is now the least recently used.
+        cache.resolve("b", common);
+        cache.resolve("C:/a", common);
+        assert_eq!(PathCacheStats { hits: 2, misses: 3, evictions: 1, len: 2 }, cache.stats());
+        cache.resolve("C:/a", windows);
+        assert_eq!(PathCacheStats { hits: 2, misses: 4, evictions: 2, len: 2 }, cache.stats());
+        cache.clear();
+        assert_eq!(0, cache.stats().len);
+
+        let uncached = PathCache::new(0);
+        uncached.resolve("a", common);
+        uncached.resolve("a", common);
+        assert_eq!(PathCacheStats { hits: 0, misses: 2, evictions: 0, len: 0 }, uncached.stats());
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for i in 0..100 {
+                        assert_eq!(format!("x/{}", i % 8), PathCache::global().resolve(&format!("./x/{}", i % 8), common).to_string());
+                    }
+                });
+            }
+        });
+        assert!(PathCache::global().stats().hits >= 400 - 32);
+    }
+}
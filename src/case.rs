@@ -0,0 +1,67 @@
+/*!
+This module contains case folding shared by case-insensitive comparisons.
+*/
+
+use crate::case_folding::FOLDINGS;
+
+/// Folds the case of a string for case-insensitive comparison, using
+/// Unicode full case folding, so that `ß`, `ẞ` and `SS` all fold to `ss`.
+pub fn fold(s: &str) -> String {
+    fold_chars(s).collect()
+}
+
+/// Indicates whether two strings are equal under [`fold`], folding each
+/// character in turn rather than allocating folded copies.
+pub fn eq_folded(a: &str, b: &str) -> bool {
+    fold_chars(a).eq(fold_chars(b))
+}
+
+fn fold_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(Folded::of)
+}
+
+/// The characters a single character folds to.
+enum Folded {
+    One(Option<char>),
+    Many(std::str::Chars<'static>),
+}
+
+impl Folded {
+    fn of(ch: char) -> Self {
+        if ch.is_ascii() {
+            return Folded::One(Some(ch.to_ascii_lowercase()));
+        }
+        match FOLDINGS.binary_search_by_key(&ch, |&(from, _)| from) {
+            Ok(i) => Folded::Many(FOLDINGS[i].1.chars()),
+            Err(_) => Folded::One(Some(ch)),
+        }
+    }
+}
+
+impl Iterator for Folded {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Folded::One(ch) => ch.take(),
+            Folded::Many(chars) => chars.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_folding() {
+        assert_eq!("ss", fold("ẞ"));
+        assert_eq!("ss", fold("ß"));
+        assert_eq!("ss", fold("SS"));
+        assert_eq!("σ", fold("ς"));
+        assert_eq!("k", fold("\u{212A}"));
+        assert!(eq_folded("STRASSE", "straße"));
+        assert!(eq_folded("a.ẞ", "A.ss"));
+        assert!(!eq_folded("a", "b"));
+    }
+}
@@ -0,0 +1,92 @@
+/*!
+This module contains the `PrefixClassifier` type.
+*/
+
+use super::{cmp, trie, FlexPath, PathCmpOptions};
+
+/// Classifies paths by the longest of a set of prefixes they fall under,
+/// such as ignored or protected roots, in a single pass over the path's
+/// segments rather than one `starts_with` test per prefix.
+///
+/// Prefixes match whole segments, compared according to a chosen
+/// [`PathCmpOptions`], and only match paths of their own variant.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant, PathCmpOptions, PrefixClassifier};
+/// let windows = FlexPathVariant::Windows;
+/// let mut roots = PrefixClassifier::new(PathCmpOptions::for_variant(windows));
+/// roots.insert(FlexPath::new(r"C:\Windows", windows), "protected");
+/// roots.insert(FlexPath::new(r"C:\Windows\Temp", windows), "scratch");
+/// assert_eq!(Some(&"protected"), roots.classify(&FlexPath::new(r"c:\windows\system32", windows)).map(|(_, v)| v));
+/// assert_eq!(Some(&"scratch"), roots.classify(&FlexPath::new(r"C:\Windows\Temp\x", windows)).map(|(_, v)| v));
+/// assert!(!roots.starts_with_any(&FlexPath::new(r"C:\WindowsApps", windows)));
+/// ```
+pub struct PrefixClassifier<T> {
+    root: trie::Node<T>,
+    options: PathCmpOptions,
+    len: usize,
+}
+
+impl<T> PrefixClassifier<T> {
+    /// Constructs an empty classifier comparing prefixes according to `options`.
+    pub fn new(options: PathCmpOptions) -> Self {
+        Self { root: trie::Node::default(), options, len: 0 }
+    }
+
+    fn keys(&self, path: &FlexPath) -> Vec<String> {
+        trie::node_keys(&FlexPath::from_resolved(cmp::key(path, self.options), path.1))
+    }
+
+    /// Returns the number of prefixes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the classifier has no prefixes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a prefix with its label, returning the previous label of an
+    /// equivalent prefix.
+    pub fn insert(&mut self, prefix: FlexPath, label: T) -> Option<T> {
+        let keys = self.keys(&prefix);
+        let r = self.root.insert(&keys, prefix, label).map(|(_, label)| label);
+        if r.is_none() {
+            self.len += 1;
+        }
+        r
+    }
+
+    /// Returns the longest prefix that `path` is equal to or under,
+    /// along with its label.
+    pub fn classify(&self, path: &FlexPath) -> Option<(&FlexPath, &T)> {
+        self.root.best_match(&self.keys(path)).map(|(prefix, label)| (prefix, label))
+    }
+
+    /// Indicates whether `path` is equal to or under any prefix.
+    pub fn starts_with_any(&self, path: &FlexPath) -> bool {
+        self.classify(path).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn variants_and_case() {
+        let mut roots = PrefixClassifier::new(PathCmpOptions::default());
+        roots.insert(FlexPath::new_common("/a"), 1);
+        roots.insert(FlexPath::new("/a/b", FlexPathVariant::Windows), 2);
+        assert_eq!(2, roots.len());
+        assert_eq!(Some(&1), roots.classify(&FlexPath::new_common("/a/b/c")).map(|(_, v)| v));
+        assert_eq!(Some(&2), roots.classify(&FlexPath::new(r"\a\b\c", FlexPathVariant::Windows)).map(|(_, v)| v));
+        assert!(!roots.starts_with_any(&FlexPath::new_common("/A")));
+        assert!(!roots.starts_with_any(&FlexPath::new_common("a")));
+        assert_eq!(Some(1), roots.insert(FlexPath::new_common("/a/"), 3));
+    }
+}
@@ -0,0 +1,41 @@
+/*!
+This module contains options for comparing paths.
+*/
+
+use super::{case, flexible, FlexPath, FlexPathVariant};
+
+/// Options controlling how two paths are compared.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct PathCmpOptions {
+    /// Indicates whether letter case is significant. When `false`,
+    /// paths are compared case-insensitively, as in the Windows operating
+    /// system.
+    ///
+    /// Case is ignored with Unicode full case folding, so that `ß`, `ẞ`
+    /// and `SS` all match. This may differ from the case table of a
+    /// particular file system. Every case-insensitive comparison in this
+    /// crate works this way.
+    pub case_sensitive: bool,
+}
+
+impl Default for PathCmpOptions {
+    fn default() -> Self {
+        Self { case_sensitive: true }
+    }
+}
+
+impl PathCmpOptions {
+    /// Options matching the conventions of a variant's platform:
+    /// case-insensitive for `Windows` and case-sensitive for `Common`.
+    pub const fn for_variant(variant: FlexPathVariant) -> Self {
+        Self { case_sensitive: !matches!(variant, FlexPathVariant::Windows) }
+    }
+}
+
+/// Returns the text by which a path is compared under `options`. As in
+/// [`FlexPath::relative_with`], the root is canonicalized first, so that
+/// drive letters are compared case-insensitively regardless of `options`.
+pub fn key(path: &FlexPath, options: PathCmpOptions) -> String {
+    let text = flexible::canonical(&path.0, path.1);
+    if options.case_sensitive { text } else { case::fold(&text) }
+}
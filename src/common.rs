@@ -3,12 +3,26 @@ Work with generic file paths. This module only considers an _absolute path_ to b
 that starts with a path separator.
 */
 
-use super::STARTS_WITH_PATH_SEPARATOR;
-use lazy_regex::*;
+use super::{case, PathCmpOptions, STARTS_WITH_PATH_SEPARATOR};
 
-static PATH_SEPARATOR: Lazy<Regex> = lazy_regex!(r"[/\\]");
+/// Splits a path at forward and backward slashes, scanning bytes with
+/// `memchr` rather than a regular expression.
+pub fn split_separators(path: &str) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+    let mut ends = memchr::memchr2_iter(b'/', b'\\', path.as_bytes()).chain(std::iter::once(path.len()));
+    std::iter::from_fn(move || {
+        let end = ends.next()?;
+        let segment = &path[start..end];
+        start = end + 1;
+        Some(segment)
+    })
+}
+
+fn starts_with_separator(path: &str) -> bool {
+    path.starts_with(['/', '\\'])
+}
 
-pub fn relative(from_path: &str, to_path: &str) -> String {
+pub fn relative(from_path: &str, to_path: &str, options: PathCmpOptions) -> String {
     assert!(
         [from_path.to_owned(), to_path.to_owned()].iter().all(|path| STARTS_WITH_PATH_SEPARATOR.is_match(path)),
         "hydroperx_path::relative() requires absolute paths as arguments"
@@ -16,8 +30,8 @@ pub fn relative(from_path: &str, to_path: &str) -> String {
 
     let mut r = Vec::<String>::new();
 
-    let mut from_parts: Vec<String> = PATH_SEPARATOR.split(resolve_one(from_path).as_ref()).map(|s| s.to_owned()).collect();
-    let mut to_parts: Vec<String> = PATH_SEPARATOR.split(resolve_one(to_path).as_ref()).map(|s| s.to_owned()).collect();
+    let mut from_parts: Vec<String> = split_separators(&resolve_one(from_path)).map(|s| s.to_owned()).collect();
+    let mut to_parts: Vec<String> = split_separators(&resolve_one(to_path)).map(|s| s.to_owned()).collect();
 
     // given each path is absolute, each one can contain an empty
     // initial second part. in that case, remove the empty string,
@@ -35,7 +49,8 @@ pub fn relative(from_path: &str, to_path: &str) -> String {
     let mut common_indices = Vec::<usize>::new();
 
     for i in 0..usize::min(from_parts.len(), to_parts.len()) {
-        if from_parts[i] != to_parts[i] {
+        let same = if options.case_sensitive { from_parts[i] == to_parts[i] } else { case::fold(&from_parts[i]) == case::fold(&to_parts[i]) };
+        if !same {
             break;
         }
         common_indices.push(i);
@@ -53,57 +68,61 @@ pub fn relative(from_path: &str, to_path: &str) -> String {
     if r.ends_with('/') { r[..r.len() - 1].to_owned() } else { r }
 }
 
-#[allow(unused)]
-pub fn resolve_n<'a, T: IntoIterator<Item = &'a str>>(paths: T) -> String {
-    let paths = paths.into_iter().collect::<Vec<&'a str>>();
-    if paths.is_empty() {
-        return "".to_owned();
+/// Resolves multiple paths in order, each relative to the ones before,
+/// in time linear in their total length: resolution starts from the
+/// last absolute path, and every segment is pushed or popped once.
+pub fn resolve_n<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T) -> String {
+    let paths = paths.into_iter().collect::<Vec<S>>();
+    let start = paths.iter().rposition(|path| starts_with_separator(path.as_ref())).unwrap_or(0);
+    let mut r = String::new();
+    if paths.get(start).is_some_and(|path| starts_with_separator(path.as_ref())) {
+        r.push('/');
     }
-    if paths.len() == 1 {
-        return resolve_one(paths[0]);
+    let root_len = r.len();
+    for path in &paths[start..] {
+        push_segments(path.as_ref(), root_len, &mut r);
     }
-    let initial_path = resolve(paths[0], paths[1]);
-    paths[2..].iter().fold(initial_path, |a, b| resolve(&a, b))
+    r
 }
 
 pub fn resolve(path1: &str, path2: &str) -> String {
-    if STARTS_WITH_PATH_SEPARATOR.is_match(path2) {
-        return resolve_one(path2);
-    }
-    let starts_with_slash = STARTS_WITH_PATH_SEPARATOR.is_match(path1);
-    let mut r: String;
-    let path1_resolved = resolve_one_without_starting_sep(path1);
-    if path2.is_empty() {
-        r = path1_resolved;
-    }
-    else {
-        let paths_combination = path1_resolved + "/" + path2;
-        r = resolve_one_without_starting_sep(paths_combination.as_ref());
-    }
-    if starts_with_slash {
-        r = "/".to_owned() + &r;
-    }
+    let mut r = String::new();
+    resolve_into(path1, path2, &mut r);
     r
 }
 
-pub fn resolve_one(path: &str) -> String {
-    let starts_with_slash = STARTS_WITH_PATH_SEPARATOR.is_match(path);
-    let r = resolve_one_without_starting_sep(path);
-    if starts_with_slash { "/".to_owned() + &r } else { r }
+/// Appends the resolution of `path2` relative to `path1` to `out`,
+/// without allocating intermediate segment lists.
+pub fn resolve_into(path1: &str, path2: &str, out: &mut String) {
+    let (path1, path2) = if starts_with_separator(path2) { (path2, "") } else { (path1, path2) };
+    if starts_with_separator(path1) {
+        out.push('/');
+    }
+    let start = out.len();
+    push_segments(path1, start, out);
+    push_segments(path2, start, out);
 }
 
-fn resolve_one_without_starting_sep(path: &str) -> String {
-    let mut r = Vec::<String>::new();
-    for p in PATH_SEPARATOR.split(path) {
-        if p == "." {
-            continue;
-        } else if p == ".." {
-            if !r.is_empty() {
-                r.remove(r.len() - 1);
-            }
-        } else if !p.is_empty() {
-            r.push(p.to_owned());
+/// Appends the segments of `path` to the resolved segments in
+/// `out[start..]`, applying `.` and `..` segments.
+pub fn push_segments(path: &str, start: usize, out: &mut String) {
+    for segment in split_separators(path) {
+        match segment {
+            "" | "." => {},
+            ".." => {
+                let end = out[start..].rfind('/').map_or(start, |i| start + i);
+                out.truncate(end);
+            },
+            _ => {
+                if out.len() > start {
+                    out.push('/');
+                }
+                out.push_str(segment);
+            },
         }
     }
-    r.join("/")
+}
+
+pub fn resolve_one(path: &str) -> String {
+    resolve(path, "")
 }
\ No newline at end of file
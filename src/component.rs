@@ -0,0 +1,97 @@
+/*!
+This module contains the bridging of `FlexPath` to the component model
+of `std::path`.
+*/
+
+use super::{flexible, FlexPath, FlexPathError, FlexPathVariant};
+use std::{borrow::Cow, ffi::OsStr, path::{Component, Path}};
+
+/// The components of a native path, returned by
+/// [`FlexPath::std_components`], which yields them as
+/// `std::path::Component` values borrowing from the path.
+///
+/// The root is parsed by the standard library, so that on Windows it
+/// yields a `Prefix` component of the right kind, such as a disk, a UNC
+/// share or a verbatim prefix.
+#[derive(Clone, Debug)]
+pub struct StdComponents<'a> {
+    /// The root in the native form, only owned when a verbatim root has
+    /// to be converted to backslashes.
+    root: Cow<'a, str>,
+    rest: &'a str,
+}
+
+impl StdComponents<'_> {
+    /// Iterates over the components.
+    pub fn iter(&self) -> impl Iterator<Item = Component<'_>> {
+        let segments = self.rest.split('/').filter(|s| !s.is_empty()).map(|s| match s {
+            ".." => Component::ParentDir,
+            _ => Component::Normal(OsStr::new(s)),
+        });
+        Path::new(self.root.as_ref()).components().chain(segments)
+    }
+}
+
+impl<'a> IntoIterator for &'a StdComponents<'_> {
+    type Item = Component<'a>;
+    type IntoIter = Box<dyn Iterator<Item = Component<'a>> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl FlexPath {
+    /// Returns the components of the path as `Path::components` would
+    /// for [`.try_to_native_path`](Self::try_to_native_path), without
+    /// converting the path, so that code written against the standard
+    /// component model can consume it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if the variant of the path
+    /// is not the native variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// use std::path::Component;
+    /// let path = FlexPath::new_native("a/../b/c");
+    /// let components = path.std_components().unwrap();
+    /// let names: Vec<Component> = components.iter().collect();
+    /// assert_eq!(vec![Component::Normal("b".as_ref()), Component::Normal("c".as_ref())], names);
+    /// ```
+    pub fn std_components(&self) -> Result<StdComponents<'_>, FlexPathError> {
+        if self.1 != FlexPathVariant::NATIVE {
+            return Err(FlexPathError::VariantMismatch { expected: FlexPathVariant::NATIVE, found: self.1 });
+        }
+        let root_len = flexible::root_len(&self.0, self.1);
+        let root = &self.0[..root_len];
+        // separators within a verbatim root, as in `\\?\UNC/srv/share/`,
+        // must be backslashes for the standard library to parse it.
+        let root = if self.1 == FlexPathVariant::Windows && root.starts_with(r"\\?") && root.contains('/') {
+            Cow::Owned(root.replace('/', "\\"))
+        } else {
+            Cow::Borrowed(root)
+        };
+        Ok(StdComponents { root, rest: &self.0[root_len..] })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn native_components() {
+        for path in ["", "/", "a/b", "/a/../b/./c/", "../x", "//host/a"] {
+            let path = FlexPath::new_native(path);
+            assert!(path.std_components().unwrap().iter().eq(path.to_path_buf().components()), "{path}");
+        }
+        let foreign = if FlexPathVariant::NATIVE == FlexPathVariant::Windows { FlexPathVariant::Common } else { FlexPathVariant::Windows };
+        assert!(FlexPath::new("a", foreign).std_components().is_err());
+        let path = FlexPath::new_native("/a");
+        assert_eq!(2, (&path.std_components().unwrap()).into_iter().count());
+    }
+}
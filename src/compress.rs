@@ -0,0 +1,135 @@
+/*!
+This module contains the prefix-compressed encoding of path lists.
+*/
+
+use super::{FlexPath, FlexPathError, FlexPathVariant, ResolveOptions};
+
+/// Encodes paths with prefix compression, as used by archive directories
+/// and search indexes: each path is stored as the length of the prefix it
+/// shares with the previous path, followed by the rest of its text.
+/// Sorting the paths beforehand, such as by [`FlexPath::sort_key`],
+/// maximizes the shared prefixes.
+///
+/// The encoding starts with a tag byte for the variant of the paths,
+/// 0 for `Common` and 1 for `Windows`, followed by one record per path:
+/// the shared length in bytes and the length of the rest in bytes, each an
+/// unsigned LEB128 number, and the rest of the path in UTF-8, with
+/// forward slashes after any Windows prefix. An empty list is encoded as
+/// no bytes at all.
+///
+/// # Errors
+///
+/// Returns [`FlexPathError::VariantMismatch`] if the paths do not all have
+/// the variant of the first path.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{decode_prefix_compressed, encode_prefix_compressed, FlexPath};
+/// let paths = ["/src/lib.rs", "/src/list.rs", "/src/main.rs"].map(FlexPath::new_common);
+/// let encoded = encode_prefix_compressed(&paths).unwrap();
+/// assert_eq!(b"\0\0\x0b/src/lib.rs\x07\x05st.rs\x05\x07main.rs", &encoded[..]);
+/// assert_eq!(paths.to_vec(), decode_prefix_compressed(&encoded).unwrap());
+/// ```
+pub fn encode_prefix_compressed<'a, I: IntoIterator<Item = &'a FlexPath>>(paths: I) -> Result<Vec<u8>, FlexPathError> {
+    let mut r = Vec::new();
+    let mut previous: Option<&FlexPath> = None;
+    for path in paths {
+        let shared = match previous {
+            None => {
+                r.push(path.1 as u8);
+                0
+            },
+            Some(previous) if previous.1 != path.1 => {
+                return Err(FlexPathError::VariantMismatch { expected: previous.1, found: path.1 });
+            },
+            Some(previous) => previous.0.bytes().zip(path.0.bytes()).take_while(|(a, b)| a == b).count(),
+        };
+        let rest = &path.0.as_bytes()[shared..];
+        write_number(&mut r, shared);
+        write_number(&mut r, rest.len());
+        r.extend_from_slice(rest);
+        previous = Some(path);
+    }
+    Ok(r)
+}
+
+/// Decodes paths encoded by [`encode_prefix_compressed`], resolving each
+/// of them again, so that malformed input cannot produce an unresolved path.
+///
+/// # Errors
+///
+/// Returns [`FlexPathError::InvalidEncoding`] if the input is truncated,
+/// has an unknown variant tag, refers to more of the previous path than
+/// there is, or does not decode to UTF-8.
+pub fn decode_prefix_compressed(bytes: &[u8]) -> Result<Vec<FlexPath>, FlexPathError> {
+    let invalid = |reason| FlexPathError::InvalidEncoding { reason };
+    let Some((&tag, mut bytes)) = bytes.split_first() else {
+        return Ok(Vec::new());
+    };
+    let variant = match tag {
+        0 => FlexPathVariant::Common,
+        1 => FlexPathVariant::Windows,
+        _ => return Err(invalid("unknown variant tag")),
+    };
+    let options = ResolveOptions { preserve_double_root: true, preserve_current_dir: true };
+    let mut r = Vec::new();
+    let mut text = Vec::new();
+    while !bytes.is_empty() {
+        let shared = read_number(&mut bytes).ok_or(invalid("truncated length"))?;
+        let len = read_number(&mut bytes).ok_or(invalid("truncated length"))?;
+        if shared > text.len() {
+            return Err(invalid("shared prefix longer than the previous path"));
+        }
+        if len > bytes.len() {
+            return Err(invalid("truncated path"));
+        }
+        text.truncate(shared);
+        text.extend_from_slice(&bytes[..len]);
+        bytes = &bytes[len..];
+        let path = std::str::from_utf8(&text).map_err(|_| invalid("path is not valid UTF-8"))?;
+        r.push(FlexPath::new_with(path, variant, options));
+    }
+    Ok(r)
+}
+
+fn write_number(out: &mut Vec<u8>, mut n: usize) {
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_number(bytes: &mut &[u8]) -> Option<usize> {
+    let mut r: usize = 0;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        r |= usize::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(r);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let windows = FlexPathVariant::Windows;
+        let long = "x".repeat(300);
+        let paths = [r"\\srv\share\é", r"\\srv\share\ê", &format!("C:/{long}"), &format!("C:/{long}/a"), ""].map(|p| FlexPath::new(p, windows));
+        let encoded = encode_prefix_compressed(&paths).unwrap();
+        assert_eq!(paths.to_vec(), decode_prefix_compressed(&encoded).unwrap());
+        assert!(decode_prefix_compressed(&encoded[..encoded.len() - 1]).is_err());
+        assert!(decode_prefix_compressed(&[0, 5, 0]).is_err());
+        assert!(decode_prefix_compressed(&[2]).is_err());
+        assert!(decode_prefix_compressed(&[0, 0, 1, b'/', 0, 1, 0xff]).is_err());
+        assert!(encode_prefix_compressed(&[FlexPath::new_common("a"), FlexPath::new("a", windows)]).is_err());
+        assert_eq!(Vec::<FlexPath>::new(), decode_prefix_compressed(&encode_prefix_compressed([]).unwrap()).unwrap());
+    }
+}
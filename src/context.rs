@@ -0,0 +1,133 @@
+/*!
+This module contains the `ResolveContext` type, which makes paths
+absolute against an explicit current directory.
+*/
+
+use super::{flexible, FlexPath, FlexPathVariant};
+use lazy_regex::*;
+use std::collections::BTreeMap;
+
+/// An explicit model of the current directory, used to make paths
+/// absolute without consulting the process environment.
+///
+/// For the Windows variant, a current directory may additionally be
+/// given per drive, which drive-relative paths such as `D:a` resolve
+/// against. A drive with no such directory resolves against its root.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveContext};
+/// let windows = FlexPathVariant::Windows;
+/// let ctx = ResolveContext::new(FlexPath::new(r"C:\work", windows))
+///     .with_drive_cwd('d', FlexPath::new(r"D:\data", windows));
+/// assert_eq!(FlexPath::new(r"C:\work\a", windows), ctx.resolve("a"));
+/// assert_eq!(FlexPath::new(r"C:\a", windows), ctx.resolve(r"\a"));
+/// assert_eq!(FlexPath::new(r"D:\data\a", windows), ctx.resolve("D:a"));
+/// assert_eq!(FlexPath::new(r"E:\a", windows), ctx.resolve("e:a"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolveContext {
+    cwd: FlexPath,
+    drive_cwds: BTreeMap<char, FlexPath>,
+}
+
+impl ResolveContext {
+    /// Constructs a context whose current directory is `cwd`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cwd` is not absolute.
+    pub fn new(cwd: FlexPath) -> Self {
+        assert!(cwd.is_absolute(), "The argument to hydroperx_path::ResolveContext::new() must be an absolute path.");
+        Self { cwd, drive_cwds: BTreeMap::new() }
+    }
+
+    /// Constructs a context from the current directory of the process.
+    #[cfg(feature = "fs")]
+    pub fn from_env() -> std::io::Result<Self> {
+        Ok(Self::new(crate::current_dir()?))
+    }
+
+    /// Sets the current directory of a Windows drive, used by
+    /// drive-relative paths such as `D:a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cwd` is not a Windows path on the given drive.
+    pub fn with_drive_cwd(mut self, drive: char, cwd: FlexPath) -> Self {
+        let drive = drive.to_ascii_uppercase();
+        assert!(
+            cwd.1 == FlexPathVariant::Windows && drive_of(&cwd.0) == Some(drive),
+            "The argument to hydroperx_path::ResolveContext::with_drive_cwd() must be a Windows path on the given drive."
+        );
+        self.drive_cwds.insert(drive, cwd);
+        self
+    }
+
+    /// Returns the current directory.
+    pub fn cwd(&self) -> &FlexPath {
+        &self.cwd
+    }
+
+    /// Returns the current directory of a Windows drive: the one given to
+    /// [`.with_drive_cwd`](Self::with_drive_cwd), or else the current
+    /// directory if it is on that drive.
+    pub fn drive_cwd(&self, drive: char) -> Option<&FlexPath> {
+        let drive = drive.to_ascii_uppercase();
+        self.drive_cwds.get(&drive).or_else(|| (drive_of(&self.cwd.0) == Some(drive)).then_some(&self.cwd))
+    }
+
+    /// Resolves `path` into an absolute path based on the variant of
+    /// the current directory.
+    ///
+    /// Relative paths resolve against the current directory. For the
+    /// Windows variant, paths rooted without a drive, such as `\a`, resolve
+    /// against the root of the current directory, and drive-relative paths
+    /// resolve against the current directory of their drive.
+    pub fn resolve(&self, path: &str) -> FlexPath {
+        let variant = self.cwd.1;
+        if variant == FlexPathVariant::Windows {
+            if let Some((_, drive, rest)) = regex_captures!(r"^([A-Za-z]):([^\\/].*|)$", path) {
+                let drive = drive.chars().next().unwrap();
+                return match self.drive_cwd(drive) {
+                    Some(cwd) => cwd.resolve(rest),
+                    None => FlexPath::from_n([&format!("{}:/", drive.to_ascii_uppercase()), rest], variant),
+                };
+            }
+            if regex_is_match!(r"^[\\/]([^\\/]|$)", path) {
+                let root = &self.cwd.0[..flexible::root_len(&self.cwd.0, variant)];
+                return FlexPath::new(&(root.trim_end_matches('/').to_owned() + path), variant);
+            }
+        }
+        self.cwd.resolve(path)
+    }
+}
+
+fn drive_of(path: &str) -> Option<char> {
+    regex_captures!(r"^(?:[\\/][\\/]\?[\\/])?([A-Za-z]):", path).map(|(_, drive)| drive.chars().next().unwrap().to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn roots() {
+        let windows = FlexPathVariant::Windows;
+        let ctx = ResolveContext::new(FlexPath::new(r"\\srv\share\dir", windows));
+        assert_eq!(FlexPath::new(r"\\srv\share\a", windows), ctx.resolve(r"\a"));
+        assert_eq!(FlexPath::new(r"C:\a", windows), ctx.resolve("C:a"));
+        assert_eq!(FlexPath::new(r"D:\b", windows), ctx.resolve(r"D:\b"));
+
+        let ctx = ctx.with_drive_cwd('d', FlexPath::new(r"D:\data", windows));
+        assert_eq!(FlexPath::new(r"D:\data\a", windows), FlexPath::new_in("D:a", &ctx));
+        assert_eq!(FlexPath::new(r"D:\data", windows), FlexPath::new_in("d:", &ctx));
+        assert_eq!(FlexPath::new(r"D:\a", windows), FlexPath::new_in(r"D:\a", &ctx));
+        assert_eq!(FlexPath::new(r"D:\a", windows), FlexPath::new("D:a", windows).resolve_in(&ctx));
+
+        let ctx = ResolveContext::new(FlexPath::new_common("/home/user"));
+        assert_eq!(FlexPath::new_common("/home/user/C:a"), ctx.resolve("C:a"));
+        assert_eq!(FlexPath::new_common("/a"), ctx.resolve("/a"));
+    }
+}
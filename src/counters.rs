@@ -0,0 +1,95 @@
+/*!
+This module contains allocation counters for guarding performance,
+available with the `counters` feature.
+
+A library cannot install a global allocator by itself. A test or
+benchmark binary installs [`CountingAllocator`], after which
+[`count_allocations`] reports how many allocations an operation performs.
+
+# Example
+
+```
+use hydroperx_path::counters::{count_allocations, CountingAllocator};
+use hydroperx_path::FlexPath;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+let base = FlexPath::new_common("/a/b");
+let mut out = String::with_capacity(64);
+let ((), allocations) = count_allocations(|| base.resolve_into("../c", &mut out));
+assert_eq!(0, allocations);
+```
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A global allocator that delegates to the system allocator, counting
+/// allocations and reallocations per thread.
+pub struct CountingAllocator;
+
+fn count() {
+    // the counter may already be destroyed while a thread exits.
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Runs `f`, returning its result along with the number of allocations
+/// and reallocations it performed on the current thread.
+///
+/// The count is always zero unless [`CountingAllocator`] is installed
+/// as the global allocator.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(|allocations| allocations.get());
+    let r = f();
+    (r, ALLOCATIONS.with(|allocations| allocations.get()) - before)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{FlexGlob, FlexPath, FlexPathVariant};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn allocations_per_operation() {
+        let base = FlexPath::new_common("/a/b");
+        let mut out = String::with_capacity(256);
+        assert_eq!(0, count_allocations(|| base.resolve_into("../c/./d/../e", &mut out)).1);
+        assert_eq!(1, count_allocations(|| base.resolve("c/d")).1);
+        assert_eq!(1, count_allocations(|| FlexPath::new_common("a/b/../c")).1);
+
+        let glob = FlexGlob::new("src/**/*.rs", FlexPathVariant::Common).unwrap();
+        let path = FlexPath::new_common("src/a/b.rs");
+        // the first match initializes the regex caches.
+        glob.is_match(&path);
+        assert!(count_allocations(|| glob.is_match(&path)).1 <= 1);
+    }
+}
@@ -0,0 +1,146 @@
+/*!
+This module contains the `Debug` implementation of `FlexPath`, whose
+alternate form is a structured, platform-independent description
+suitable for snapshot tests.
+*/
+
+use super::{flexible, FlexPath, FlexPathVariant};
+use std::fmt;
+
+/// The kind of the root of a path, as shown by the alternate `Debug` form.
+// the fields are only read by the derived `Debug` implementation.
+#[allow(dead_code)]
+#[derive(Debug)]
+enum Prefix<'a> {
+    /// A relative path.
+    None,
+    /// A single separator, as in `/a`, or `\a` rooted at the current
+    /// drive for the `Windows` variant.
+    Root,
+    /// Two separators kept by the `Common` variant, as in `//host/a`.
+    DoubleRoot,
+    /// A drive letter, as in `C:\a`.
+    Disk(String),
+    /// A server and share, as in `\\server\share\a`.
+    Unc { server: &'a str, share: &'a str },
+    /// A verbatim drive letter, as in `\\?\C:\a`.
+    VerbatimDisk(String),
+    /// A verbatim server and share, as in `\\?\UNC\server\share\a`.
+    VerbatimUnc { server: &'a str, share: &'a str },
+    /// Another verbatim prefix, as in `\\?\GLOBALROOT\a`, whose
+    /// segments start after `\\?\`.
+    Verbatim,
+    /// The device namespace, as in `\\.\pipe\a`, whose segments start
+    /// after `\\.\`.
+    DeviceNs,
+}
+
+fn server_and_share(rest: &str) -> (&str, &str) {
+    let mut parts = rest.split('/');
+    (parts.next().unwrap_or(""), parts.next().unwrap_or(""))
+}
+
+fn prefix(root: &str, variant: FlexPathVariant) -> Prefix<'_> {
+    let disk = |text: &str| (text.len() == 2 && text.ends_with(':')).then(|| text.to_uppercase());
+    if root.is_empty() {
+        return Prefix::None;
+    }
+    if variant == FlexPathVariant::Common {
+        return if root == "/" { Prefix::Root } else { Prefix::DoubleRoot };
+    }
+    let root = root.trim_end_matches('/');
+    if let Some(rest) = root.strip_prefix(r"\\?\") {
+        if let Some(drive) = disk(rest) {
+            return Prefix::VerbatimDisk(drive);
+        }
+        if rest.get(..3).is_some_and(|unc| unc.eq_ignore_ascii_case("unc")) && matches!(rest.as_bytes().get(3), None | Some(b'/')) {
+            let (server, share) = server_and_share(rest.get(4..).unwrap_or(""));
+            return Prefix::VerbatimUnc { server, share };
+        }
+        return Prefix::Verbatim;
+    }
+    if root == r"\\.\" {
+        return Prefix::DeviceNs;
+    }
+    if let Some(rest) = root.strip_prefix(r"\\") {
+        let (server, share) = server_and_share(rest);
+        return Prefix::Unc { server, share };
+    }
+    disk(root).map_or(Prefix::Root, Prefix::Disk)
+}
+
+/// Formats the path as `FlexPath("text", Variant)`, or with the alternate
+/// flag (`{:#?}`) as a structure listing the variant, the kind of prefix
+/// and the segments, which is the same on every platform and so suits
+/// snapshot tests.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant};
+/// let path = FlexPath::new(r"\\?\unc\srv\share\a\b", FlexPathVariant::Windows);
+/// assert_eq!(r#"FlexPath("\\\\?\\unc/srv/share/a/b", Windows)"#, format!("{path:?}"));
+/// assert_eq!(r#"FlexPath {
+///     variant: Windows,
+///     prefix: VerbatimUnc {
+///         server: "srv",
+///         share: "share",
+///     },
+///     segments: [
+///         "a",
+///         "b",
+///     ],
+/// }"#, format!("{path:#?}"));
+/// ```
+impl fmt::Debug for FlexPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return f.debug_tuple("FlexPath").field(&self.0).field(&self.1).finish();
+        }
+        let root_len = flexible::root_len(&self.0, self.1);
+        let segments: Vec<&str> = self.0[root_len..].split('/').filter(|s| !s.is_empty()).collect();
+        f.debug_struct("FlexPath")
+            .field("variant", &self.1)
+            .field("prefix", &prefix(&self.0[..root_len], self.1))
+            .field("segments", &segments)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefixes() {
+        let windows = FlexPathVariant::Windows;
+        let describe = |path: &str, variant| {
+            let path = FlexPath::new(path, variant);
+            let root_len = flexible::root_len(&path.0, path.1);
+            format!("{:?}", prefix(&path.0[..root_len], variant))
+        };
+        assert_eq!("None", describe("a/b", windows));
+        assert_eq!("Root", describe(r"\a", windows));
+        assert_eq!("Root", describe("/a", FlexPathVariant::Common));
+        assert_eq!(r#"Disk("C:")"#, describe(r"c:\a", windows));
+        assert_eq!(r#"Unc { server: "srv", share: "share" }"#, describe(r"\\srv\share\a", windows));
+        assert_eq!(r#"Unc { server: "srv", share: "" }"#, describe(r"\\srv", windows));
+        assert_eq!(r#"VerbatimDisk("C:")"#, describe(r"\\?\c:", windows));
+        assert_eq!("Verbatim", describe(r"\\?\GLOBALROOT\x", windows));
+        assert_eq!("Verbatim", describe(r"\\?\uncle\x", windows));
+        assert_eq!("DeviceNs", describe(r"\\.\pipe\x", windows));
+
+        let options = crate::ResolveOptions { preserve_double_root: true, ..Default::default() };
+        let path = FlexPath::new_with("//host/a", FlexPathVariant::Common, options);
+        let expected = r#"FlexPath {
+    variant: Common,
+    prefix: DoubleRoot,
+    segments: [
+        "host",
+        "a",
+    ],
+}"#;
+        assert_eq!(expected, format!("{path:#?}"));
+        assert_eq!(r#"FlexPath("//host/a", Common)"#, format!("{path:?}"));
+    }
+}
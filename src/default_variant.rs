@@ -0,0 +1,81 @@
+/*!
+This module contains the configurable default variant used by
+`FromStr`, `Default` and the `*_native` constructors.
+*/
+
+use super::FlexPathVariant;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const UNSET: u8 = 0;
+
+static PROCESS_DEFAULT: AtomicU8 = AtomicU8::new(UNSET);
+
+thread_local! {
+    static SCOPED_DEFAULT: Cell<Option<FlexPathVariant>> = const { Cell::new(None) };
+}
+
+fn encode(variant: FlexPathVariant) -> u8 {
+    match variant {
+        FlexPathVariant::Common => 1,
+        FlexPathVariant::Windows => 2,
+    }
+}
+
+pub fn get() -> FlexPathVariant {
+    if let Some(variant) = SCOPED_DEFAULT.with(|scoped| scoped.get()) {
+        return variant;
+    }
+    match PROCESS_DEFAULT.load(Ordering::Relaxed) {
+        1 => FlexPathVariant::Common,
+        2 => FlexPathVariant::Windows,
+        _ => FlexPathVariant::NATIVE,
+    }
+}
+
+pub fn set(variant: FlexPathVariant) {
+    PROCESS_DEFAULT.store(encode(variant), Ordering::Relaxed);
+}
+
+pub fn scoped(variant: FlexPathVariant) -> DefaultVariantGuard {
+    let previous = SCOPED_DEFAULT.with(|scoped| scoped.replace(Some(variant)));
+    DefaultVariantGuard { previous, _not_send: PhantomData }
+}
+
+/// Overrides the default variant for the current thread until dropped,
+/// as returned by [`FlexPath::scoped_default_variant`](crate::FlexPath::scoped_default_variant).
+///
+/// Guards may be nested; dropping one restores the override that was
+/// in effect when it was created.
+#[must_use = "the override ends when the guard is dropped"]
+pub struct DefaultVariantGuard {
+    previous: Option<FlexPathVariant>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for DefaultVariantGuard {
+    fn drop(&mut self) {
+        SCOPED_DEFAULT.with(|scoped| scoped.set(self.previous));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_guards() {
+        let outer = scoped(FlexPathVariant::Windows);
+        assert_eq!(FlexPathVariant::Windows, get());
+        {
+            let _inner = scoped(FlexPathVariant::Common);
+            assert_eq!(FlexPathVariant::Common, get());
+        }
+        assert_eq!(FlexPathVariant::Windows, get());
+        assert_eq!(crate::FlexPath::new(r"C:\a", FlexPathVariant::Windows), crate::FlexPath::new_native(r"C:\a"));
+        assert_eq!(FlexPathVariant::Windows, crate::FlexPath::from_n_native(["a", "b"]).variant());
+        drop(outer);
+        assert_eq!(None, SCOPED_DEFAULT.with(|scoped| scoped.get()));
+    }
+}
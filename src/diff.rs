@@ -0,0 +1,107 @@
+/*!
+This module contains path-list diffing.
+*/
+
+use super::{case, cmp, FlexPath, FlexPathVariant, PathCmpOptions};
+use std::collections::{HashMap, HashSet};
+
+/// Options for [`diff_paths`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct DiffOptions {
+    /// How paths of both lists are compared.
+    pub cmp: PathCmpOptions,
+    /// Indicates whether a removed and an added path sharing a base name
+    /// are reported as a move. A pair is only reported when the base name
+    /// is unique among both the removed and the added paths.
+    pub detect_moves: bool,
+}
+
+/// The result of [`diff_paths`], each list keeping the order of the input.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PathDiff {
+    /// Paths only in the new list.
+    pub added: Vec<FlexPath>,
+    /// Paths only in the old list.
+    pub removed: Vec<FlexPath>,
+    /// Paths detected as moved, as `(old, new)` pairs.
+    pub moved: Vec<(FlexPath, FlexPath)>,
+}
+
+/// Compares an old and a new list of paths, reporting which paths were
+/// added, removed and, optionally, moved.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{diff_paths, DiffOptions, FlexPath};
+/// let old = ["a/x.txt", "a/y.txt", "b"].map(FlexPath::new_common);
+/// let new = ["c/x.txt", "b", "d"].map(FlexPath::new_common);
+/// let diff = diff_paths(old, new, DiffOptions { detect_moves: true, ..Default::default() });
+/// assert_eq!(vec![FlexPath::new_common("d")], diff.added);
+/// assert_eq!(vec![FlexPath::new_common("a/y.txt")], diff.removed);
+/// assert_eq!(vec![(FlexPath::new_common("a/x.txt"), FlexPath::new_common("c/x.txt"))], diff.moved);
+/// ```
+pub fn diff_paths<I, J>(old: I, new: J, options: DiffOptions) -> PathDiff
+    where I: IntoIterator<Item = FlexPath>, J: IntoIterator<Item = FlexPath>
+{
+    let key = |path: &FlexPath| -> (FlexPathVariant, String) { (path.variant(), cmp::key(path, options.cmp)) };
+    let old: Vec<FlexPath> = old.into_iter().collect();
+    let new: Vec<FlexPath> = new.into_iter().collect();
+    let old_keys: HashSet<_> = old.iter().map(key).collect();
+    let new_keys: HashSet<_> = new.iter().map(key).collect();
+    let mut removed: Vec<FlexPath> = old.into_iter().filter(|path| !new_keys.contains(&key(path))).collect();
+    let mut added: Vec<FlexPath> = new.into_iter().filter(|path| !old_keys.contains(&key(path))).collect();
+    let mut moved = Vec::new();
+
+    if options.detect_moves {
+        let name = |path: &FlexPath| {
+            let name = path.base_name();
+            if options.cmp.case_sensitive { name } else { case::fold(&name) }
+        };
+        let count = |paths: &[FlexPath]| {
+            let mut r = HashMap::<String, usize>::new();
+            for path in paths {
+                *r.entry(name(path)).or_default() += 1;
+            }
+            r
+        };
+        let removed_names = count(&removed);
+        let added_names = count(&added);
+        let unique = |n: &String| removed_names.get(n) == Some(&1) && added_names.get(n) == Some(&1);
+        let mut targets: HashMap<String, FlexPath> = HashMap::new();
+        added.retain(|path| {
+            let n = name(path);
+            if unique(&n) {
+                targets.insert(n, path.clone());
+                return false;
+            }
+            true
+        });
+        removed.retain(|path| match targets.remove(&name(path)) {
+            Some(target) => {
+                moved.push((path.clone(), target));
+                false
+            },
+            None => true,
+        });
+    }
+
+    PathDiff { added, removed, moved }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn case_and_ambiguous_moves() {
+        let windows = FlexPathVariant::Windows;
+        let old = [r"C:\A.txt", r"C:\x\n.txt", r"C:\y\n.txt"].map(|p| FlexPath::new(p, windows));
+        let new = [r"c:\a.TXT", r"C:\z\n.txt"].map(|p| FlexPath::new(p, windows));
+        let options = DiffOptions { cmp: PathCmpOptions::for_variant(windows), detect_moves: true };
+        let diff = diff_paths(old, new, options);
+        assert!(diff.moved.is_empty());
+        assert_eq!(1, diff.added.len());
+        assert_eq!(2, diff.removed.len());
+    }
+}
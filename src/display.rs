@@ -0,0 +1,133 @@
+/*!
+This module contains the `RelativeDisplay` formatter.
+*/
+
+use super::{case, flexible, FlexPath, FlexPathVariant, PathCmpOptions};
+use std::fmt;
+
+/// A formatter of a path relative to a base directory, returned by
+/// [`FlexPath::relative_display`].
+///
+/// The relative form is computed at format time without allocating.
+#[derive(Clone, Copy, Debug)]
+pub struct RelativeDisplay<'a> {
+    path: &'a FlexPath,
+    base: &'a FlexPath,
+    options: PathCmpOptions,
+}
+
+impl FlexPath {
+    /// Returns a formatter of the path relative to `base` if it is `base`
+    /// itself or under it, and of the whole path otherwise, comparing
+    /// letter case according to the conventions of the variant. The path
+    /// itself formats as `.`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let root = FlexPath::new(r"C:\work", FlexPathVariant::Windows);
+    /// let file = FlexPath::new(r"c:\Work\src\main.rs", FlexPathVariant::Windows);
+    /// assert_eq!(r"src\main.rs", format!("{}", file.relative_display(&root)));
+    /// assert_eq!(".", root.relative_display(&root).to_string());
+    /// let other = FlexPath::new(r"D:\lib.rs", FlexPathVariant::Windows);
+    /// assert_eq!(r"D:\lib.rs", other.relative_display(&root).to_string());
+    /// ```
+    pub fn relative_display<'a>(&'a self, base: &'a FlexPath) -> RelativeDisplay<'a> {
+        self.relative_display_with(base, PathCmpOptions::for_variant(self.1))
+    }
+
+    /// Returns a formatter of the path relative to `base` like
+    /// [`.relative_display`](Self::relative_display), comparing letter
+    /// case according to `options`.
+    pub fn relative_display_with<'a>(&'a self, base: &'a FlexPath, options: PathCmpOptions) -> RelativeDisplay<'a> {
+        RelativeDisplay { path: self, base, options }
+    }
+}
+
+impl<'a> RelativeDisplay<'a> {
+    /// Returns the text of the path after `base`, or `None` if the path
+    /// is not `base` itself or under it.
+    fn relative_text(&self) -> Option<&'a str> {
+        let (path, base) = (self.path, self.base);
+        if path.1 != base.1 {
+            return None;
+        }
+        let eq = |a: &str, b: &str| if self.options.case_sensitive { a == b } else { case::eq_folded(a, b) };
+        let path_root_len = flexible::root_len(&path.0, path.1);
+        let base_root_len = flexible::root_len(&base.0, base.1);
+        // drive letters never differ by case.
+        let root_eq = if path.1 == FlexPathVariant::Windows { case::eq_folded } else { eq_exact };
+        if !root_eq(&path.0[..path_root_len], &base.0[..base_root_len]) {
+            return None;
+        }
+        let mut rest = &path.0[path_root_len..];
+        for base_segment in base.0[base_root_len..].split('/').filter(|s| !s.is_empty()) {
+            let (segment, after) = rest.split_once('/').unwrap_or((rest, ""));
+            if segment.is_empty() || !eq(segment, base_segment) {
+                return None;
+            }
+            rest = after;
+        }
+        Some(rest)
+    }
+}
+
+fn eq_exact(a: &str, b: &str) -> bool {
+    a == b
+}
+
+impl fmt::Display for RelativeDisplay<'_> {
+    /// Formats the path with the separators of its variant, as the
+    /// `Display` implementation of `FlexPath` does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self.relative_text() {
+            Some("") => return f.write_str("."),
+            Some(rest) => rest,
+            None => &self.path.0,
+        };
+        if self.path.1 != FlexPathVariant::Windows {
+            return f.write_str(text);
+        }
+        for (i, piece) in text.split('/').enumerate() {
+            if i != 0 {
+                f.write_str("\\")?;
+            }
+            f.write_str(piece)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ResolveOptions;
+
+    #[test]
+    fn relative_forms() {
+        let base = FlexPath::new_common("/work/app");
+        for (path, expected) in [
+            ("/work/app/src/lib.rs", "src/lib.rs"),
+            ("/work/app", "."),
+            ("/work/application/a", "/work/application/a"),
+            ("/work", "/work"),
+            ("/WORK/app/a", "/WORK/app/a"),
+            ("work/app/a", "work/app/a"),
+        ] {
+            assert_eq!(expected, FlexPath::new_common(path).relative_display(&base).to_string(), "{path}");
+        }
+        assert_eq!("a", FlexPath::new_common("a").relative_display(&FlexPath::new_common("")).to_string());
+        let options = ResolveOptions { preserve_double_root: true, ..Default::default() };
+        let double = FlexPath::new_with("//a", FlexPathVariant::Common, options);
+        assert_eq!("b", FlexPath::new_with("//a/b", FlexPathVariant::Common, options).relative_display(&double).to_string());
+        assert_eq!("/a/b", FlexPath::new_common("/a/b").relative_display(&double).to_string());
+
+        let windows = FlexPathVariant::Windows;
+        let base = FlexPath::new(r"\\srv\share\Dir", windows);
+        assert_eq!(r"x\y", FlexPath::new(r"\\SRV\share\dir\x\y", windows).relative_display(&base).to_string());
+        let exact = PathCmpOptions { case_sensitive: true };
+        assert_eq!(r"\\srv\share\dir\x", FlexPath::new(r"\\srv\share\dir\x", windows).relative_display_with(&base, exact).to_string());
+        assert_eq!("a", FlexPath::new("a", windows).relative_display(&FlexPath::new_common("")).to_string());
+    }
+}
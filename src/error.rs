@@ -0,0 +1,147 @@
+/*!
+This module contains the error type of fallible operations.
+*/
+
+use super::FlexPathVariant;
+#[cfg(any(feature = "fs", feature = "os-str"))]
+use std::ffi::OsStr;
+
+/// An error produced by a fallible path operation.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum FlexPathError {
+    /// A glob pattern is malformed.
+    InvalidGlob {
+        /// The pattern as given.
+        pattern: String,
+        /// Describes what is wrong with the pattern.
+        reason: &'static str,
+    },
+    /// A path's variant differs from the variant an operation requires.
+    VariantMismatch {
+        /// The required variant.
+        expected: FlexPathVariant,
+        /// The path's variant.
+        found: FlexPathVariant,
+    },
+    /// A path cannot be used as a root of the required variant.
+    InvalidRoot {
+        /// The root as given.
+        root: String,
+        /// The required variant.
+        variant: FlexPathVariant,
+    },
+    /// A segment cannot be appended literally.
+    InvalidSegment {
+        /// The segment as given.
+        segment: String,
+        /// Describes why the segment is not literal.
+        reason: &'static str,
+    },
+    /// A path was required to be absolute, but is relative.
+    RelativePath {
+        /// The path as given.
+        path: String,
+    },
+    /// A path was required to be relative, but is absolute.
+    AbsolutePath {
+        /// The path as given.
+        path: String,
+    },
+    /// A path cannot be written to a path list without being misread.
+    InvalidListEntry {
+        /// The path as formatted.
+        path: String,
+        /// Describes why the path cannot be listed.
+        reason: &'static str,
+    },
+    /// A path was asked to ascend more levels than it has segments.
+    AboveRoot {
+        /// The path as given.
+        path: String,
+        /// The number of levels requested.
+        levels: usize,
+    },
+    /// Encoded data is malformed.
+    InvalidEncoding {
+        /// Describes what is wrong with the data.
+        reason: &'static str,
+    },
+    /// A file name extension is empty, contains a path separator or has
+    /// an empty dot-separated part.
+    InvalidExtension {
+        /// The extension as given.
+        extension: String,
+        /// Describes what is wrong with the extension.
+        reason: &'static str,
+    },
+    /// A path from the operating system is not valid UTF-8 and cannot be
+    /// represented without loss.
+    NonUtf8Path {
+        /// The path in the platform's encoding of `OsStr`: raw bytes on
+        /// Unix and WTF-8 on Windows.
+        bytes: Vec<u8>,
+        /// The length of the longest valid UTF-8 prefix of `bytes`.
+        valid_up_to: usize,
+    },
+    /// Two different paths would be renamed to the same path.
+    RenameConflict {
+        /// The shared target path.
+        target: String,
+        /// The two source paths, in input order.
+        sources: [String; 2],
+    },
+    /// Input is longer than the [`InputLimits`](crate::InputLimits) allow.
+    InputTooLong {
+        /// The length of the input in bytes.
+        len: usize,
+        /// The maximum length.
+        max: usize,
+    },
+    /// Input has more segments than the [`InputLimits`](crate::InputLimits) allow.
+    TooManySegments {
+        /// The number of segments of the input.
+        count: usize,
+        /// The maximum number of segments.
+        max: usize,
+    },
+    /// A saved mount table is malformed.
+    InvalidMountTable {
+        /// Describes what is wrong with the table.
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for FlexPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidGlob { pattern, reason } => write!(f, "invalid glob pattern {pattern:?}: {reason}"),
+            Self::VariantMismatch { expected, found } => write!(f, "expected a path of the {expected:?} variant, found {found:?}"),
+            Self::InvalidRoot { root, variant } => write!(f, "{root:?} is not a root of the {variant:?} variant"),
+            Self::InvalidSegment { segment, reason } => write!(f, "invalid path segment {segment:?}: {reason}"),
+            Self::RelativePath { path } => write!(f, "expected an absolute path, found {path:?}"),
+            Self::AbsolutePath { path } => write!(f, "expected a relative path, found {path:?}"),
+            Self::InvalidListEntry { path, reason } => write!(f, "cannot list path {path:?}: {reason}"),
+            Self::AboveRoot { path, levels } => write!(f, "cannot ascend {levels} levels from {path:?}"),
+            Self::InvalidEncoding { reason } => write!(f, "invalid encoding: {reason}"),
+            Self::InvalidExtension { extension, reason } => write!(f, "invalid extension {extension:?}: {reason}"),
+            Self::NonUtf8Path { bytes, valid_up_to } => write!(f, "path {:?} is not valid UTF-8 after {valid_up_to} bytes", String::from_utf8_lossy(bytes)),
+            Self::RenameConflict { target, sources: [a, b] } => write!(f, "both {a:?} and {b:?} would be renamed to {target:?}"),
+            Self::InputTooLong { len, max } => write!(f, "input of {len} bytes exceeds the limit of {max} bytes"),
+            Self::TooManySegments { count, max } => write!(f, "input of {count} segments exceeds the limit of {max} segments"),
+            Self::InvalidMountTable { reason } => write!(f, "invalid mount table: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FlexPathError {}
+
+impl FlexPathError {
+    /// Describes an `OsStr` that is not valid UTF-8.
+    #[cfg(any(feature = "fs", feature = "os-str"))]
+    pub(crate) fn non_utf8(path: &OsStr) -> Self {
+        let bytes = path.as_encoded_bytes().to_vec();
+        let valid_up_to = std::str::from_utf8(&bytes).err().map_or(bytes.len(), |error| error.valid_up_to());
+        Self::NonUtf8Path { bytes, valid_up_to }
+    }
+}
@@ -0,0 +1,464 @@
+/*!
+This module contains the validated `Extension` type and the
+`ExtensionMap` and `ExtensionSet` lookup tables.
+*/
+
+use super::{case, full_extension, ExtensionOptions, FlexPath, FlexPathError};
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Constructs an [`Extension`] from a string literal at compile time,
+/// failing the build if the extension is invalid.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{extension, FlexPath};
+/// let path = FlexPath::new_common("a/b.txt");
+/// assert_eq!("a/b.md", path.with_extension(&extension!("md")).to_string());
+/// assert!(path.has_extension(extension!(".TXT")));
+/// ```
+///
+/// An invalid extension does not compile:
+///
+/// ```compile_fail
+/// let _ = hydroperx_path::extension!("a/b");
+/// ```
+#[macro_export]
+macro_rules! extension {
+    ($extension:literal) => {{
+        const EXTENSION: $crate::Extension = $crate::Extension::from_static($extension);
+        EXTENSION
+    }};
+}
+
+/// A validated file name extension, such as `txt` or `tar.gz`.
+///
+/// An extension may be given with or without its leading dot, is never
+/// empty, contains no path separator and has no empty dot-separated part.
+/// Use the [`extension!`] macro to validate a literal at compile time.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{Extension, FlexPathError};
+/// assert_eq!("tar.gz", Extension::new(".tar.gz").unwrap().as_str());
+/// assert!(matches!(Extension::new("a..b"), Err(FlexPathError::InvalidExtension { .. })));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Extension(Cow<'static, str>);
+
+impl PartialEq for Extension {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Extension {}
+
+impl std::hash::Hash for Extension {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+const fn extension_error(extension: &str) -> Option<&'static str> {
+    let bytes = extension.as_bytes();
+    let mut i = if !bytes.is_empty() && bytes[0] == b'.' { 1 } else { 0 };
+    if i == bytes.len() {
+        return Some("extension is empty");
+    }
+    let mut part_start = i;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' | b'\\' => return Some("extension contains a path separator"),
+            b'.' if i == part_start => return Some("extension has an empty part"),
+            b'.' => part_start = i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    if part_start == bytes.len() {
+        return Some("extension has an empty part");
+    }
+    None
+}
+
+impl Extension {
+    /// Validates an extension, given with or without its leading dot.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidExtension`] if the extension is
+    /// empty, contains a path separator or has an empty dot-separated part.
+    pub fn new(extension: &str) -> Result<Extension, FlexPathError> {
+        match extension_error(extension) {
+            Some(reason) => Err(FlexPathError::InvalidExtension { extension: extension.to_owned(), reason }),
+            None => Ok(Extension(Cow::Owned(extension.strip_prefix('.').unwrap_or(extension).to_owned()))),
+        }
+    }
+
+    /// Validates a static extension in a constant context; the
+    /// [`extension!`] macro is the usual way to call this.
+    ///
+    /// # Panics
+    ///
+    /// Panics, failing compilation in a constant context, if the
+    /// extension is invalid as described in [`Extension::new`].
+    pub const fn from_static(extension: &'static str) -> Extension {
+        if extension_error(extension).is_some() {
+            panic!("The argument to hydroperx_path::Extension::from_static() must be a valid extension");
+        }
+        Extension(Cow::Borrowed(extension))
+    }
+
+    /// Returns the extension without its leading dot.
+    pub fn as_str(&self) -> &str {
+        self.0.strip_prefix('.').unwrap_or(&self.0)
+    }
+}
+
+impl AsRef<str> for Extension {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for Extension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Extension {
+    type Err = FlexPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+/// A map from file name extensions to values, such as MIME types or
+/// asset loaders, where a compound extension like `.tar.gz` takes
+/// precedence over its last part `.gz`.
+///
+/// Extensions are recognized and compared according to a chosen
+/// [`ExtensionOptions`], case-insensitively by default.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{ExtensionMap, FlexPath};
+/// let mut types = ExtensionMap::new();
+/// types.insert(".gz", "application/gzip");
+/// types.insert(".tar.gz", "application/x-gtar");
+/// assert_eq!(Some(&"application/x-gtar"), FlexPath::new_common("a.tar.GZ").lookup_extension(&types));
+/// assert_eq!(Some(&"application/gzip"), FlexPath::new_common("a.svg.gz").lookup_extension(&types));
+/// assert_eq!(None, FlexPath::new_common("a.zip").lookup_extension(&types));
+/// ```
+pub struct ExtensionMap<T> {
+    entries: HashMap<String, (String, T)>,
+    options: ExtensionOptions,
+}
+
+impl<T> Default for ExtensionMap<T> {
+    fn default() -> Self {
+        Self::with_options(ExtensionOptions::default())
+    }
+}
+
+impl<T> ExtensionMap<T> {
+    /// Constructs an empty map with the default [`ExtensionOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs an empty map recognizing extensions according to `options`.
+    pub fn with_options(options: ExtensionOptions) -> Self {
+        Self { entries: HashMap::new(), options }
+    }
+
+    /// Returns the options of the map.
+    pub fn options(&self) -> ExtensionOptions {
+        self.options
+    }
+
+    fn key(&self, extension: &str) -> String {
+        extension_key(extension, self.options)
+    }
+
+    /// Returns the number of extensions in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indicates whether the map contains no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Maps an extension, given with or without its leading dot, to a
+    /// value, returning the previous value of an equivalent extension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension is empty or contains a path separator.
+    pub fn insert(&mut self, extension: &str, value: T) -> Option<T> {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        assert!(
+            !extension.is_empty() && !extension.contains(['/', '\\']),
+            "The argument to hydroperx_path::ExtensionMap::insert() must be a non-empty extension without path separators; got {}",
+            extension
+        );
+        self.entries.insert(self.key(extension), (extension.to_owned(), value)).map(|(_, v)| v)
+    }
+
+    /// Removes an extension, returning its value.
+    pub fn remove(&mut self, extension: &str) -> Option<T> {
+        let key = self.key(extension.strip_prefix('.').unwrap_or(extension));
+        self.entries.remove(&key).map(|(_, v)| v)
+    }
+
+    /// Returns the longest extension of the path's file name that is in
+    /// the map, without its leading dot and as it was inserted, along
+    /// with its value.
+    pub fn lookup(&self, path: &FlexPath) -> Option<(&str, &T)> {
+        let extension = self.key(full_extension(path.file_name()?, self.options)?);
+        longest_suffix(&extension, |rest| self.entries.get(rest)).map(|(extension, value)| (extension.as_str(), value))
+    }
+
+    /// Iterates over the extensions and their values in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.entries.values().map(|(e, v)| (e.as_str(), v))
+    }
+}
+
+impl<'a, T> FromIterator<(&'a str, T)> for ExtensionMap<T> {
+    fn from_iter<I: IntoIterator<Item = (&'a str, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (extension, value) in iter {
+            map.insert(extension, value);
+        }
+        map
+    }
+}
+
+fn extension_key(extension: &str, options: ExtensionOptions) -> String {
+    if options.case_sensitive { extension.to_owned() } else { case::fold(extension) }
+}
+
+/// Finds the longest dot-separated suffix of a compound extension for
+/// which `get` returns a value.
+fn longest_suffix<T>(extension: &str, mut get: impl FnMut(&str) -> Option<T>) -> Option<T> {
+    let mut rest = extension;
+    loop {
+        if let Some(value) = get(rest) {
+            return Some(value);
+        }
+        rest = &rest[rest.find('.')? + 1..];
+    }
+}
+
+/// A set of file name extensions built once for fast membership checks
+/// over many paths, where a path matches if its compound extension ends
+/// with any extension in the set.
+///
+/// Extensions are folded once on insertion according to a chosen
+/// [`ExtensionOptions`], case-insensitively by default, so that checking
+/// a path folds only its own extension and performs one hash lookup per
+/// dot-separated part, longest first.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{ExtensionSet, FlexPath};
+/// let archives: ExtensionSet = [".zip", "gz", "tar.gz"].into_iter().collect();
+/// assert!(FlexPath::new_common("a/b.TAR.GZ").matches_extension_set(&archives));
+/// assert_eq!(Some("tar.gz"), archives.lookup(&FlexPath::new_common("b.tar.gz")));
+/// assert!(!FlexPath::new_common("a/b.txt").matches_extension_set(&archives));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExtensionSet {
+    entries: HashMap<String, String>,
+    options: ExtensionOptions,
+}
+
+impl Default for ExtensionSet {
+    fn default() -> Self {
+        Self::with_options(ExtensionOptions::default())
+    }
+}
+
+impl ExtensionSet {
+    /// Constructs an empty set with the default [`ExtensionOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs an empty set recognizing extensions according to `options`.
+    pub fn with_options(options: ExtensionOptions) -> Self {
+        Self { entries: HashMap::new(), options }
+    }
+
+    /// Returns the options of the set.
+    pub fn options(&self) -> ExtensionOptions {
+        self.options
+    }
+
+    /// Returns the number of extensions in the set.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indicates whether the set contains no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds an extension, given with or without its leading dot or as an
+    /// [`Extension`], returning whether no equivalent extension was present.
+    /// An equivalent extension already present is kept as it was inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension is empty or contains a path separator.
+    pub fn insert(&mut self, extension: impl AsRef<str>) -> bool {
+        let extension = extension.as_ref();
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        assert!(
+            !extension.is_empty() && !extension.contains(['/', '\\']),
+            "The argument to hydroperx_path::ExtensionSet::insert() must be a non-empty extension without path separators; got {}",
+            extension
+        );
+        match self.entries.entry(extension_key(extension, self.options)) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(extension.to_owned());
+                true
+            }
+        }
+    }
+
+    /// Removes an extension, returning whether it was present.
+    pub fn remove(&mut self, extension: impl AsRef<str>) -> bool {
+        let extension = extension.as_ref();
+        self.entries.remove(&extension_key(extension.strip_prefix('.').unwrap_or(extension), self.options)).is_some()
+    }
+
+    /// Indicates whether an extension equivalent to the given one is in
+    /// the set.
+    pub fn contains(&self, extension: impl AsRef<str>) -> bool {
+        let extension = extension.as_ref();
+        self.entries.contains_key(&extension_key(extension.strip_prefix('.').unwrap_or(extension), self.options))
+    }
+
+    /// Returns the longest extension of the path's file name that is in
+    /// the set, without its leading dot and as it was inserted.
+    pub fn lookup(&self, path: &FlexPath) -> Option<&str> {
+        let extension = extension_key(full_extension(path.file_name()?, self.options)?, self.options);
+        longest_suffix(&extension, |rest| self.entries.get(rest)).map(|extension| extension.as_str())
+    }
+
+    /// Iterates over the extensions in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().map(|e| e.as_str())
+    }
+}
+
+impl<E: AsRef<str>> FromIterator<E> for ExtensionSet {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<E: AsRef<str>> Extend<E> for ExtensionSet {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for extension in iter {
+            self.insert(extension);
+        }
+    }
+}
+
+impl FlexPath {
+    /// Indicates whether the compound extension of the file name ends with
+    /// any extension in `set`, as in [`ExtensionSet::lookup`].
+    pub fn matches_extension_set(&self, set: &ExtensionSet) -> bool {
+        set.lookup(self).is_some()
+    }
+
+    /// Changes the extension of the file name to a validated extension,
+    /// as in [`FlexPath::change_extension`].
+    pub fn with_extension(&self, extension: &Extension) -> FlexPath {
+        self.change_extension(extension)
+    }
+
+    /// Returns the value of the longest extension of the file name that
+    /// is in `map`, as in [`ExtensionMap::lookup`].
+    pub fn lookup_extension<'a, T>(&self, map: &'a ExtensionMap<T>) -> Option<&'a T> {
+        map.lookup(self).map(|(_, v)| v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn compound_and_case() {
+        let types: ExtensionMap<u32> = [("gz", 1), (".tar.gz", 2), ("JS", 3)].into_iter().collect();
+        assert_eq!(Some(("tar.gz", &2)), types.lookup(&FlexPath::new_common("a.b.tar.gz")));
+        assert_eq!(Some(("JS", &3)), types.lookup(&FlexPath::new_common("app.min.js")));
+        assert_eq!(None, types.lookup(&FlexPath::new_common(".gz")));
+        assert_eq!(None, types.lookup(&FlexPath::new_common("gz")));
+        assert_eq!(None, types.lookup(&FlexPath::new(r"\\srv\share.gz", FlexPathVariant::Windows)));
+
+        let mut exact = ExtensionMap::with_options(ExtensionOptions { case_sensitive: true, ..Default::default() });
+        exact.insert("C", "c++");
+        assert_eq!(None, FlexPath::new_common("a.c").lookup_extension(&exact));
+        assert_eq!(Some(&"c++"), FlexPath::new_common("a.C").lookup_extension(&exact));
+        assert_eq!(Some("c++"), exact.remove(".C"));
+        assert!(exact.is_empty());
+    }
+
+    #[test]
+    fn validated_extension() {
+        const MD: Extension = Extension::from_static(".md");
+        assert_eq!("md", MD.as_str());
+        assert_eq!(MD, Extension::new("md").unwrap());
+        for invalid in ["", ".", "..", "a.", ".a.", "a..b", "a/b", "a\\b"] {
+            assert!(Extension::new(invalid).is_err(), "{invalid:?}");
+        }
+        let path = FlexPath::new_common("a/b.tar.gz");
+        assert_eq!("a/b.md", path.with_extension(&MD).to_string());
+        assert!(path.has_extension(Extension::new("gz").unwrap()));
+    }
+
+    #[test]
+    fn extension_set() {
+        let mut set: ExtensionSet = ["gz", ".Tar.gz", "JS"].into_iter().collect();
+        assert_eq!(3, set.len());
+        assert!(!set.insert(".TAR.GZ"));
+        assert!(set.contains("js"));
+        assert_eq!(Some("Tar.gz"), set.lookup(&FlexPath::new_common("a.b.tar.gz")));
+        assert_eq!(Some("gz"), set.lookup(&FlexPath::new_common("a.svg.gz")));
+        assert_eq!(Some("JS"), set.lookup(&FlexPath::new_common("app.min.js")));
+        assert_eq!(None, set.lookup(&FlexPath::new_common(".gz")));
+        assert_eq!(None, set.lookup(&FlexPath::new_common("a.x/b")));
+        assert_eq!(None, set.lookup(&FlexPath::new(r"\\srv\share.gz", FlexPathVariant::Windows)));
+        for name in ["a.gz", "a.b.tar.gz", "a.JS", "a.svg", ".gz", "b.tar"] {
+            let path = FlexPath::new_common(name);
+            assert_eq!(path.has_extensions(set.iter()), path.matches_extension_set(&set), "{name}");
+        }
+        assert!(set.remove(Extension::new("gz").unwrap()));
+        assert!(!FlexPath::new_common("a.svg.gz").matches_extension_set(&set));
+
+        let mut exact = ExtensionSet::with_options(ExtensionOptions { case_sensitive: true, ..Default::default() });
+        exact.extend(["C"]);
+        assert!(!FlexPath::new_common("a.c").matches_extension_set(&exact));
+        assert!(FlexPath::new_common("a.C").matches_extension_set(&exact));
+    }
+}
@@ -0,0 +1,201 @@
+/*!
+This module contains the `PathFilter` combinator.
+*/
+
+use super::{trie, ExtensionSet, FlexGlob, FlexPath};
+use std::sync::Arc;
+
+#[derive(Clone)]
+enum Node {
+    All,
+    Nothing,
+    Glob(FlexGlob),
+    Under(Arc<trie::Node<()>>),
+    Extensions(Arc<ExtensionSet>),
+    Predicate(Arc<dyn Fn(&FlexPath) -> bool + Send + Sync>),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+}
+
+impl Node {
+    fn is_match(&self, path: &FlexPath) -> bool {
+        match self {
+            Node::All => true,
+            Node::Nothing => false,
+            Node::Glob(glob) => glob.is_match(path),
+            Node::Under(root) => root.best_match(&trie::node_keys(path)).is_some(),
+            Node::Extensions(set) => path.matches_extension_set(set),
+            Node::Predicate(predicate) => predicate(path),
+            Node::And(nodes) => nodes.iter().all(|node| node.is_match(path)),
+            Node::Or(nodes) => nodes.iter().any(|node| node.is_match(path)),
+            Node::Not(node) => !node.is_match(path),
+        }
+    }
+
+    /// Estimates the cost of matching, so that cheaper operands of a
+    /// conjunction or disjunction run first and may short-circuit the rest.
+    fn cost(&self) -> usize {
+        match self {
+            Node::All | Node::Nothing => 0,
+            Node::Extensions(_) => 1,
+            Node::Under(_) => 2,
+            Node::Glob(_) => 3,
+            Node::Predicate(_) => 4,
+            Node::And(nodes) | Node::Or(nodes) => nodes.iter().map(Node::cost).sum(),
+            Node::Not(node) => node.cost(),
+        }
+    }
+}
+
+/// A declarative filter over paths, composed of globs, directory
+/// prefixes, extension sets and custom predicates with [`.and`],
+/// [`.or`] and `!`, such as the ignore rules of a walker, watcher or
+/// packager.
+///
+/// Composition flattens nested conjunctions and disjunctions, folds the
+/// constant filters [`PathFilter::all`] and [`PathFilter::nothing`], and
+/// orders operands so that cheap checks short-circuit expensive ones.
+/// Filters are cheap to clone and can be shared across threads.
+///
+/// [`.and`]: PathFilter::and
+/// [`.or`]: PathFilter::or
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{ExtensionSet, FlexGlob, FlexPath, FlexPathVariant, PathFilter};
+/// let common = FlexPathVariant::Common;
+/// let sources = PathFilter::extensions(["rs", "toml"].into_iter().collect::<ExtensionSet>());
+/// let ignored = PathFilter::under([FlexPath::new_common("target")])
+///     .or(PathFilter::glob(FlexGlob::new("**/.*", common).unwrap()));
+/// let filter = sources.and(!ignored);
+/// assert!(filter.is_match(&FlexPath::new_common("src/lib.rs")));
+/// assert!(!filter.is_match(&FlexPath::new_common("target/debug/build.rs")));
+/// assert!(!filter.is_match(&FlexPath::new_common("src/.hidden.rs")));
+/// assert!(!filter.is_match(&FlexPath::new_common("README.md")));
+/// ```
+#[derive(Clone)]
+pub struct PathFilter(Node);
+
+impl PathFilter {
+    /// A filter matching every path.
+    pub fn all() -> Self {
+        Self(Node::All)
+    }
+
+    /// A filter matching no path.
+    pub fn nothing() -> Self {
+        Self(Node::Nothing)
+    }
+
+    /// A filter matching paths that match a glob pattern.
+    pub fn glob(glob: FlexGlob) -> Self {
+        Self(Node::Glob(glob))
+    }
+
+    /// A filter matching paths that are any of `dirs` or under one of
+    /// them, comparing whole segments exactly.
+    pub fn under<I: IntoIterator<Item = FlexPath>>(dirs: I) -> Self {
+        let mut root = trie::Node::default();
+        for dir in dirs {
+            root.insert(&trie::node_keys(&dir), dir, ());
+        }
+        Self(Node::Under(Arc::new(root)))
+    }
+
+    /// A filter matching paths whose extension matches an
+    /// [`ExtensionSet`], as in [`FlexPath::matches_extension_set`].
+    pub fn extensions(set: ExtensionSet) -> Self {
+        Self(Node::Extensions(Arc::new(set)))
+    }
+
+    /// A filter matching paths for which `predicate` returns `true`.
+    pub fn predicate<F: Fn(&FlexPath) -> bool + Send + Sync + 'static>(predicate: F) -> Self {
+        Self(Node::Predicate(Arc::new(predicate)))
+    }
+
+    /// A filter matching paths that match both filters.
+    pub fn and(self, other: PathFilter) -> Self {
+        let mut nodes = Vec::new();
+        for node in [self.0, other.0] {
+            match node {
+                Node::Nothing => return Self::nothing(),
+                Node::All => {},
+                Node::And(inner) => nodes.extend(inner),
+                node => nodes.push(node),
+            }
+        }
+        Self::compose(nodes, Node::All, Node::And)
+    }
+
+    /// A filter matching paths that match either filter.
+    pub fn or(self, other: PathFilter) -> Self {
+        let mut nodes = Vec::new();
+        for node in [self.0, other.0] {
+            match node {
+                Node::All => return Self::all(),
+                Node::Nothing => {},
+                Node::Or(inner) => nodes.extend(inner),
+                node => nodes.push(node),
+            }
+        }
+        Self::compose(nodes, Node::Nothing, Node::Or)
+    }
+
+    fn compose(mut nodes: Vec<Node>, empty: Node, combine: fn(Vec<Node>) -> Node) -> Self {
+        nodes.sort_by_key(Node::cost);
+        Self(match nodes.len() {
+            0 => empty,
+            1 => nodes.pop().unwrap(),
+            _ => combine(nodes),
+        })
+    }
+
+    /// Indicates whether the filter matches a path.
+    pub fn is_match(&self, path: &FlexPath) -> bool {
+        self.0.is_match(path)
+    }
+}
+
+/// Negates a filter.
+impl std::ops::Not for PathFilter {
+    type Output = PathFilter;
+
+    fn not(self) -> PathFilter {
+        Self(match self.0 {
+            Node::All => Node::Nothing,
+            Node::Nothing => Node::All,
+            Node::Not(node) => *node,
+            node => Node::Not(Box::new(node)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn composition() {
+        let windows = FlexPathVariant::Windows;
+        let logs = PathFilter::glob(FlexGlob::new(r"C:\logs\**", windows).unwrap());
+        let big = PathFilter::predicate(|path| path.base_name().len() > 5);
+        let filter = logs.clone().and(big.clone()).or(PathFilter::under([FlexPath::new(r"C:\keep", windows)]));
+        assert!(filter.is_match(&FlexPath::new(r"c:\LOGS\server.log", windows)));
+        assert!(!filter.is_match(&FlexPath::new(r"C:\logs\a.log", windows)));
+        assert!(filter.is_match(&FlexPath::new(r"C:\keep\a", windows)));
+        assert!(filter.is_match(&FlexPath::new(r"C:\keep", windows)));
+        assert!(!filter.is_match(&FlexPath::new(r"C:\keeper", windows)));
+        assert!(!PathFilter::under([FlexPath::new_common("/a")]).is_match(&FlexPath::new("/a/b", windows)));
+
+        assert!(matches!(logs.clone().and(PathFilter::all()).0, Node::Glob(_)));
+        assert!(matches!(logs.clone().or(PathFilter::all()).0, Node::All));
+        assert!(matches!(big.clone().and(logs.clone()).and(PathFilter::nothing()).0, Node::Nothing));
+        assert!(matches!((!!logs.clone()).0, Node::Glob(_)));
+        let Node::And(nodes) = big.and(logs.clone()).and(PathFilter::extensions(["log"].into_iter().collect())).0 else { panic!() };
+        assert!(matches!(nodes[..], [Node::Extensions(_), Node::Glob(_), Node::Predicate(_)]));
+        assert!(!PathFilter::nothing().is_match(&FlexPath::new_common("a")));
+    }
+}
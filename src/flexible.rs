@@ -4,22 +4,26 @@ handling paths with a `FlexPathVariant` variant.
 */
 
 use super::{
+    case,
     STARTS_WITH_PATH_SEPARATOR,
-    FlexPathVariant
+    FlexPathVariant,
+    PathCmpOptions,
 };
 use lazy_regex::*;
 
 static STARTS_WITH_WINDOWS_PATH_PREFIX: Lazy<Regex> = lazy_regex!(r#"(?x)
     ^ (
-        ([\\/][\\/]\?\\([A-Za-z]\:)?)  | # extended-length prefix
-        ([\\/][\\/])                   | # UNC prefix
-        ([A-Za-z]\:)                     # drive prefix
+        ([\\/][\\/]\?[\\/]([A-Za-z]\:)?)  | # extended-length prefix
+        ([\\/][\\/]\.[\\/])                | # device namespace prefix
+        ([\\/][\\/])                      | # UNC prefix
+        ([A-Za-z]\:)                        # drive prefix
     )
 "#);
 
 static STARTS_WITH_WINDOWS_PATH_PREFIX_OR_SLASH: Lazy<Regex> = lazy_regex!(r#"(?x)
     ^ (
         ([\\/][\\/]\?[\\/]([A-Za-z]\:)?)  | # extended-length prefix
+        ([\\/][\\/]\.[\\/])                | # device namespace prefix
         ([\\/][\\/])                      | # UNC prefix
         ([A-Za-z]\:)                      | # drive prefix
         [\/\\] ([^/\\] | $)                 # slash
@@ -27,9 +31,104 @@ static STARTS_WITH_WINDOWS_PATH_PREFIX_OR_SLASH: Lazy<Regex> = lazy_regex!(r#"(?
 "#);
 
 static UNC_OR_EXT_PREFIX: Lazy<Regex> = lazy_regex!(r#"(?x)
-    ^[\\/][\\/](?:\?[\\/])?$
+    ^[\\/][\\/](?:[?.][\\/])?$
 "#);
 
+static WINDOWS_ROOT: Lazy<Regex> = lazy_regex!(r#"(?xi)
+    ^ (
+        [\\/][\\/]\?[\\/][a-z]\:/?                    | # extended-length drive root
+        [\\/][\\/]\?[\\/]unc/[^/]+(/[^/]+)?/?        | # extended-length UNC root
+        [\\/][\\/]\?[\\/]                             | # other extended-length root
+        [\\/][\\/]\.[\\/]                             | # device namespace root
+        [\\/][\\/][^/\\]+(/[^/]+)?/?                  | # UNC host and share
+        [\\/][\\/]                                    | # bare UNC prefix
+        [a-z]\:/?                                      | # drive root
+        /                                                # slash
+    )
+"#);
+
+/// Returns the length of the root of a resolved path, including
+/// its trailing separator if any, or zero for a relative path.
+pub fn root_len(path: &str, manipulation: FlexPathVariant) -> usize {
+    match manipulation {
+        // a resolved path only starts with two slashes if it keeps a double root.
+        FlexPathVariant::Common => path.bytes().take_while(|&b| b == b'/').count(),
+        FlexPathVariant::Windows => WINDOWS_ROOT.find(path).map_or(0, |m| m.end()),
+    }
+}
+
+/// Canonicalizes a root returned by [`root_len`], using forward slashes,
+/// an uppercase drive letter and an uppercase verbatim `UNC` marker.
+pub fn canonical_root(root: &str) -> String {
+    let root = root.replace('\\', "/");
+    if let Some((_, verbatim, drive, rest)) = regex_captures!(r"^(//\?/)?([a-z])(:.*)$", &root) {
+        return verbatim.to_owned() + &drive.to_uppercase() + rest;
+    }
+    if regex_is_match!(r"(?i)^//\?/unc(/|$)", &root) {
+        return "//?/UNC".to_owned() + &root[7..];
+    }
+    root
+}
+
+/// Returns a resolved path with its root canonicalized as by
+/// [`canonical_root`], delimiting every segment with a forward slash.
+pub fn canonical(path: &str, manipulation: FlexPathVariant) -> String {
+    let root_len = root_len(path, manipulation);
+    canonical_root(&path[..root_len]) + &path[root_len..]
+}
+
+/// Converts a resolved verbatim path into the drive or UNC form,
+/// unless the result would not refer to the same file.
+pub fn simplify_verbatim(path: &str, manipulation: FlexPathVariant) -> String {
+    const MAX_PATH: usize = 260;
+    if manipulation != FlexPathVariant::Windows || path.len() >= MAX_PATH {
+        return path.to_owned();
+    }
+    let simplified = if let Some((_, drive)) = regex_captures!(r"^[\\/][\\/]\?[\\/]([A-Za-z]:)", path) {
+        drive.to_owned() + &path[6..]
+    } else if regex_is_match!(r"^[\\/][\\/]\?[\\/][Uu][Nn][Cc]/", path) {
+        r"\\".to_owned() + &path[8..]
+    } else {
+        return path.to_owned();
+    };
+    let needs_verbatim = simplified.split('/').any(|s| s.ends_with(['.', ' ']) && s != "." && s != "..");
+    if needs_verbatim { path.to_owned() } else { simplified }
+}
+
+/// Returns the length of the Windows prefix of an unresolved path, such
+/// as `C:`, `\\` or `\\?\C:`, or zero if it has none or is of the
+/// `Common` variant.
+pub fn prefix_len(path: &str, manipulation: FlexPathVariant) -> usize {
+    match manipulation {
+        FlexPathVariant::Common => 0,
+        FlexPathVariant::Windows => STARTS_WITH_WINDOWS_PATH_PREFIX.find(path).map_or(0, |m| m.end()),
+    }
+}
+
+/// Canonicalizes a prefix returned by [`prefix_len`] the way resolution does.
+pub fn canonical_prefix(prefix: &str) -> String {
+    let mut r = String::with_capacity(prefix.len());
+    push_canonical_prefix(prefix, &mut r);
+    r
+}
+
+fn push_canonical_prefix(prefix: &str, out: &mut String) {
+    // verbatim and device namespace prefixes are canonicalized with
+    // backward slashes, as the forward-slash forms are not verbatim to
+    // Windows APIs, and so are UNC prefixes, so that `//srv/share` and
+    // `\\srv\share` are equal.
+    if prefix.starts_with(['/', '\\']) && prefix[2..].starts_with(['?', '.']) {
+        out.push_str(r"\\");
+        out.push_str(&prefix[2..3]);
+        out.push('\\');
+        out.push_str(&prefix[4..]);
+    } else if prefix.starts_with(['/', '\\']) {
+        out.push_str(r"\\");
+    } else {
+        out.push_str(prefix);
+    }
+}
+
 pub fn resolve(path1: &str, path2: &str, manipulation: FlexPathVariant) -> String {
     match manipulation {
         FlexPathVariant::Common => {
@@ -41,10 +140,10 @@ pub fn resolve(path1: &str, path2: &str, manipulation: FlexPathVariant) -> Strin
             if prefixed.is_empty() {
                 return crate::common::resolve(path1, path2);
             }
-            let prefix = STARTS_WITH_WINDOWS_PATH_PREFIX.find(prefixed.last().unwrap().as_ref()).map(|m| m.as_str().to_owned()).unwrap();
+            let prefix = canonical_prefix(STARTS_WITH_WINDOWS_PATH_PREFIX.find(prefixed.last().unwrap().as_ref()).unwrap().as_str());
             let paths: Vec<String> = paths.iter().map(|path| STARTS_WITH_WINDOWS_PATH_PREFIX.replace(path.as_ref(), |_: &Captures| "/").into_owned()).collect();
             let r = crate::common::resolve(&paths[0], &paths[1]);
-            if UNC_OR_EXT_PREFIX.is_match(&prefix.as_str()) {
+            if UNC_OR_EXT_PREFIX.is_match(prefix.as_str()) {
                 return prefix + &r[1..];
             }
             prefix + &r
@@ -52,20 +151,62 @@ pub fn resolve(path1: &str, path2: &str, manipulation: FlexPathVariant) -> Strin
     }
 }
 
-pub fn resolve_n<'a, T: IntoIterator<Item = &'a str>>(paths: T, manipulation: FlexPathVariant) -> String {
-    let paths = paths.into_iter().collect::<Vec<&'a str>>();
-    if paths.is_empty() {
-        return "".to_owned();
+/// Resolves multiple paths in order, each relative to the ones before,
+/// in time linear in their total length. The result is that of folding
+/// the paths with [`resolve`].
+pub fn resolve_n<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T, manipulation: FlexPathVariant) -> String {
+    let paths = paths.into_iter().collect::<Vec<S>>();
+    let paths = paths.iter().map(|path| path.as_ref()).collect::<Vec<&str>>();
+    if manipulation == FlexPathVariant::Common {
+        return crate::common::resolve_n(paths);
+    }
+    // the prefix of the last prefixed path wins, and every prefix
+    // otherwise acts as a root.
+    let Some(prefix) = paths.iter().rev().find_map(|path| STARTS_WITH_WINDOWS_PATH_PREFIX.find(path)) else {
+        return crate::common::resolve_n(paths);
+    };
+    let prefix = canonical_prefix(prefix.as_str());
+    let r = crate::common::resolve_n(paths.iter().map(|path| STARTS_WITH_WINDOWS_PATH_PREFIX.replace(path, "/")));
+    if UNC_OR_EXT_PREFIX.is_match(prefix.as_str()) {
+        return prefix + &r[1..];
+    }
+    prefix + &r
+}
+
+/// Appends the result of [`resolve_n`] to `out` without allocating,
+/// other than to grow `out`.
+pub fn resolve_n_into<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T, manipulation: FlexPathVariant, out: &mut String) {
+    let base = out.len();
+    // a prefix restarts resolution and is kept by later rooted paths;
+    // segments are resolved after `start`.
+    let mut prefix_end = base;
+    let mut start = base;
+    for path in paths {
+        let path = path.as_ref();
+        let prefix = if manipulation == FlexPathVariant::Windows { STARTS_WITH_WINDOWS_PATH_PREFIX.find(path) } else { None };
+        let rest = match prefix {
+            Some(prefix) => {
+                out.truncate(base);
+                push_canonical_prefix(prefix.as_str(), out);
+                prefix_end = out.len();
+                &path[prefix.end()..]
+            },
+            None => path,
+        };
+        if prefix.is_some() || rest.starts_with(['/', '\\']) {
+            out.truncate(prefix_end);
+            out.push('/');
+            start = out.len();
+        }
+        crate::common::push_segments(rest, start, out);
     }
-    if paths.len() == 1 {
-        return resolve(paths[0], "", manipulation);
+    if UNC_OR_EXT_PREFIX.is_match(&out[base..prefix_end]) {
+        out.remove(prefix_end);
     }
-    let initial_path = resolve(paths[0], paths[1], manipulation);
-    paths[2..].iter().fold(initial_path, |a, b| resolve(&a, b, manipulation))
 }
 
 pub fn resolve_one(path: &str, manipulation: FlexPathVariant) -> String {
-    resolve_n([path], manipulation)
+    resolve(path, "", manipulation)
 }
 
 pub fn is_absolute(path: &str, manipulation: FlexPathVariant) -> bool {
@@ -75,28 +216,44 @@ pub fn is_absolute(path: &str, manipulation: FlexPathVariant) -> bool {
     }
 }
 
-pub fn relative(from_path: &str, to_path: &str, manipulation: FlexPathVariant) -> String {
+pub fn relative(from_path: &str, to_path: &str, manipulation: FlexPathVariant, options: PathCmpOptions) -> String {
     match manipulation {
         FlexPathVariant::Common =>
-            crate::common::relative(from_path, to_path),
+            crate::common::relative(from_path, to_path, options),
         FlexPathVariant::Windows => {
             assert!(
                 [from_path.to_owned(), to_path.to_owned()].iter().all(|path| is_absolute(path, manipulation)),
                 "hydroperx_path::argumented::relative() requires absolute paths as arguments"
             );
-            let mut paths = [from_path, to_path].map(|s| s.to_owned());
-            let prefixes: Vec<String> = paths.iter().map(|path| STARTS_WITH_WINDOWS_PATH_PREFIX_OR_SLASH.find(path.as_ref()).unwrap().as_str().into()).collect();
-            let prefix = prefixes[0].clone();
-            if prefix != prefixes[1] {
-                return resolve_one(to_path, manipulation);
+            let mut paths = [from_path, to_path].map(|path| resolve_one(path, manipulation));
+            let prefixes = paths.clone().map(|path| relative_prefix(&path).to_owned());
+            // drive letters never differ by case, and UNC hosts and shares
+            // are compared according to the options, as they are segments
+            // that cannot be traversed with `..`.
+            let key = |prefix: &str| {
+                let root = canonical_root(prefix);
+                if options.case_sensitive { root } else { case::fold(&root) }
+            };
+            if key(&prefixes[0]) != key(&prefixes[1]) {
+                return paths[1].clone();
             }
-            for path in &mut paths {
+            for (path, prefix) in paths.iter_mut().zip(&prefixes) {
                 *path = path[prefix.len()..].to_owned();
                 if !STARTS_WITH_PATH_SEPARATOR.is_match(path.as_ref()) {
                     *path = "/".to_owned() + path.as_ref();
                 }
             }
-            crate::common::relative(paths[0].as_ref(), paths[1].as_ref())
+            crate::common::relative(paths[0].as_ref(), paths[1].as_ref(), options)
         },
     }
-}
\ No newline at end of file
+}
+
+/// Returns the prefix of a resolved absolute path that [`relative`]
+/// cannot traverse: the drive, the host and share of a UNC path or the
+/// verbatim prefix, or nothing for a path rooted without a drive.
+fn relative_prefix(path: &str) -> &str {
+    if regex_is_match!(r"(?i)^\\\\(\?\\unc(/|$)|[^?.])", path) {
+        return WINDOWS_ROOT.find(path).map_or(path, |m| m.as_str().trim_end_matches('/'));
+    }
+    STARTS_WITH_WINDOWS_PATH_PREFIX.find(path).map_or("", |m| m.as_str())
+}
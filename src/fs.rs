@@ -0,0 +1,446 @@
+/*!
+This module contains the `FlexPath` methods that access the file system,
+available with the `fs` feature.
+
+These methods only accept paths of the native variant, returning an
+error of kind `Unsupported` otherwise.
+*/
+
+use super::{flexible, FlexGlob, FlexPath, FlexPathError, FlexPathVariant, VerbatimPolicy};
+use std::{fs, io, path::{Path, PathBuf}};
+
+impl FlexPath {
+    /// Converts the path for use with `std::fs`, failing if its variant
+    /// is not the native variant.
+    pub(crate) fn native_path(&self) -> io::Result<PathBuf> {
+        let path = self.try_to_native_path().map_err(|error| io::Error::new(io::ErrorKind::Unsupported, error))?;
+        // The empty path refers to the current directory.
+        Ok(if self.0.is_empty() { PathBuf::from(".") } else { path })
+    }
+
+    /// Converts a path returned by the operating system into a native `FlexPath`.
+    fn from_native_path_buf(path: PathBuf) -> io::Result<FlexPath> {
+        match path.to_str() {
+            Some(path) => Ok(FlexPath::new(path, FlexPathVariant::NATIVE)),
+            None => Err(io::Error::new(io::ErrorKind::InvalidData, FlexPathError::non_utf8(path.as_os_str()))),
+        }
+    }
+
+    /// Returns the canonical absolute form of the path from the operating
+    /// system, with all intermediate components normalized and symbolic
+    /// links resolved.
+    ///
+    /// In the Windows operating system, the result of the system is in
+    /// the verbatim (`\\?\`) form, which is kept or simplified according
+    /// to `policy`. Other platforms ignore `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant,
+    /// if the path does not exist or if the result is not valid UTF-8.
+    pub fn canonicalize(&self, policy: VerbatimPolicy) -> io::Result<FlexPath> {
+        let r = Self::from_native_path_buf(fs::canonicalize(self.native_path()?)?)?;
+        Ok(match policy {
+            VerbatimPolicy::Keep => r,
+            VerbatimPolicy::Simplify => FlexPath::from_resolved(flexible::simplify_verbatim(&r.0, r.1), r.1),
+        })
+    }
+
+    /// Indicates whether this path and `other` refer to the same file.
+    ///
+    /// When both paths exist, this compares their identity from the file
+    /// system after following symbolic links: the device and inode numbers
+    /// on Unix and the canonical paths elsewhere. Otherwise, it compares the
+    /// paths textually after resolving them against the current directory,
+    /// as by [`.normal_key`](Self::normal_key).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either path's variant is not the native variant
+    /// or if the metadata of either path cannot be read for a reason other
+    /// than its absence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let manifest = FlexPath::new_native(env!("CARGO_MANIFEST_DIR"));
+    /// assert!(manifest.resolve("Cargo.toml").is_same_file_as(&manifest.resolve("src/../Cargo.toml")).unwrap());
+    /// assert!(!manifest.resolve("Cargo.toml").is_same_file_as(&manifest.resolve("src")).unwrap());
+    /// ```
+    pub fn is_same_file_as(&self, other: &FlexPath) -> io::Result<bool> {
+        let (path, other_path) = (self.native_path()?, other.native_path()?);
+        match (fs::metadata(&path), fs::metadata(&other_path)) {
+            (Ok(metadata), Ok(other_metadata)) => same_file(&path, &metadata, &other_path, &other_metadata),
+            (Err(error), _) | (_, Err(error)) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => {
+                let cwd = current_dir()?;
+                let key = |path: &FlexPath| path.absolutize(&cwd).map(|path| path.normal_key()).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error));
+                Ok(key(self)? == key(other)?)
+            },
+        }
+    }
+
+    /// Returns an iterator over the entries of the directory, each joined
+    /// textually to this path and keeping its variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant or if
+    /// the directory cannot be read. The iterator yields an error for entries
+    /// that cannot be read or whose names are not valid UTF-8.
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        Ok(ReadDir { parent: self.clone(), inner: fs::read_dir(self.native_path()?)?, relative: false })
+    }
+
+    /// Returns a recursive, depth-first walk over this path and every
+    /// entry under it, visiting the entries of each directory in name order.
+    /// Symbolic links are not followed.
+    ///
+    /// Errors, including a non-native variant, are yielded by the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexGlob, FlexPath, FlexPathVariant};
+    /// let root = FlexPath::new_native(env!("CARGO_MANIFEST_DIR"));
+    /// let target = FlexGlob::new("**/target", FlexPathVariant::native()).unwrap();
+    /// let sources = root.walk().prune(target).filter_map(Result::ok).filter(|entry| entry.path().has_extension("rs"));
+    /// assert!(sources.into_iter().any(|entry| entry.path().base_name() == "lib.rs"));
+    /// ```
+    pub fn walk(&self) -> Walk {
+        let root = self.native_path().and_then(|path| {
+            Ok(WalkEntry { path: self.clone(), depth: 0, file_type: fs::symlink_metadata(path)?.file_type() })
+        });
+        Walk { pending: vec![root], max_depth: usize::MAX, prune: Vec::new(), filter: None }
+    }
+
+    /// Indicates whether the path points at an existing entity,
+    /// following symbolic links.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant or if
+    /// the existence can be neither confirmed nor denied, as in `Path::try_exists`.
+    pub fn exists(&self) -> io::Result<bool> {
+        self.native_path()?.try_exists()
+    }
+
+    /// Indicates whether the path points at an existing directory,
+    /// following symbolic links.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant.
+    pub fn is_dir(&self) -> io::Result<bool> {
+        Ok(self.native_path()?.is_dir())
+    }
+
+    /// Indicates whether the path points at an existing regular file,
+    /// following symbolic links.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant.
+    pub fn is_file(&self) -> io::Result<bool> {
+        Ok(self.native_path()?.is_file())
+    }
+
+    /// Queries the metadata of the file, following symbolic links.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant
+    /// or if the metadata cannot be read.
+    pub fn metadata(&self) -> io::Result<fs::Metadata> {
+        fs::metadata(self.native_path()?)
+    }
+
+    /// Indicates whether the file is hidden, checking the hidden attribute
+    /// in the Windows operating system and the naming convention elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant
+    /// or if the file metadata cannot be read.
+    pub fn is_hidden_on_disk(&self) -> io::Result<bool> {
+        let path = self.native_path()?;
+        #[cfg(target_os = "windows")] {
+            use std::os::windows::fs::MetadataExt;
+            const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+            Ok(fs::metadata(path)?.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+        }
+        #[cfg(not(target_os = "windows"))] {
+            fs::symlink_metadata(path)?;
+            Ok(self.is_hidden())
+        }
+    }
+}
+
+/// An iterator over the entries of a directory, returned by [`FlexPath::read_dir`].
+pub struct ReadDir {
+    parent: FlexPath,
+    inner: fs::ReadDir,
+    relative: bool,
+}
+
+impl ReadDir {
+    /// Yields only the entry names as relative paths instead of
+    /// joining them to the directory.
+    pub fn relative_names(mut self) -> Self {
+        self.relative = true;
+        self
+    }
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<FlexPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = match self.inner.next()? {
+            Ok(entry) => entry,
+            Err(error) => return Some(Err(error)),
+        };
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            return Some(Err(io::Error::new(io::ErrorKind::InvalidData, FlexPathError::non_utf8(&name))));
+        };
+        Some(Ok(if self.relative {
+            FlexPath::from_resolved(name, self.parent.1)
+        } else {
+            self.parent.join_segments(&[name])
+        }))
+    }
+}
+
+/// Compares the identity of two existing files.
+#[cfg(unix)]
+fn same_file(_: &Path, metadata: &fs::Metadata, _: &Path, other_metadata: &fs::Metadata) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(metadata.dev() == other_metadata.dev() && metadata.ino() == other_metadata.ino())
+}
+
+/// Compares the identity of two existing files.
+#[cfg(not(unix))]
+fn same_file(path: &Path, _: &fs::Metadata, other_path: &Path, _: &fs::Metadata) -> io::Result<bool> {
+    Ok(fs::canonicalize(path)? == fs::canonicalize(other_path)?)
+}
+
+/// Returns the current working directory of the process as a native path.
+///
+/// # Errors
+///
+/// Returns an error if the current directory cannot be determined
+/// or is not valid UTF-8.
+pub fn current_dir() -> io::Result<FlexPath> {
+    FlexPath::from_native_path_buf(std::env::current_dir()?)
+}
+
+/// Returns the home directory of the current user as a native path,
+/// taken from the `USERPROFILE` environment variable in the Windows
+/// operating system and from `HOME` elsewhere.
+///
+/// Returns `None` if the variable is unset, empty or not valid UTF-8.
+pub fn home_dir() -> Option<FlexPath> {
+    let variable = if FlexPathVariant::NATIVE == FlexPathVariant::Windows { "USERPROFILE" } else { "HOME" };
+    std::env::var(variable).ok().filter(|home| !home.is_empty()).map(|home| FlexPath::new(&home, FlexPathVariant::NATIVE))
+}
+
+/// Expands a glob pattern of the native variant against the file system,
+/// yielding matching paths in name order.
+///
+/// Only the directory formed by the leading segments without wildcards
+/// is walked, and only as deep as the pattern requires. A relative
+/// pattern is expanded under the current directory and yields relative paths.
+///
+/// # Errors
+///
+/// Returns an error if the pattern is malformed. The iterator yields an
+/// error for entries that cannot be read.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::FlexPath;
+/// let root = FlexPath::new_native(env!("CARGO_MANIFEST_DIR"));
+/// let pattern = root.to_string() + "/src/*.rs";
+/// let sources: Vec<FlexPath> = hydroperx_path::glob(&pattern).unwrap().map(Result::unwrap).collect();
+/// assert!(sources.contains(&root.resolve("src/lib.rs")));
+/// ```
+pub fn glob(pattern: &str) -> Result<Glob, FlexPathError> {
+    let glob = FlexGlob::new(pattern, FlexPathVariant::NATIVE)?;
+    let (base, depth) = glob.literal_base();
+    let mut walk = FlexPath::new(&base, FlexPathVariant::NATIVE).walk();
+    if let Some(depth) = depth {
+        walk = walk.max_depth(depth);
+    }
+    Ok(Glob { walk, glob })
+}
+
+/// An iterator over the paths matching a pattern, returned by [`glob`].
+pub struct Glob {
+    walk: Walk,
+    glob: FlexGlob,
+}
+
+impl Iterator for Glob {
+    type Item = io::Result<FlexPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.walk.next()? {
+                Ok(entry) if self.glob.is_match(&entry.path) => return Some(Ok(entry.path)),
+                Ok(_) => {},
+                // A missing base directory has no matches.
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {},
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// An entry yielded by [`Walk`].
+#[derive(Clone, Debug)]
+pub struct WalkEntry {
+    path: FlexPath,
+    depth: usize,
+    file_type: fs::FileType,
+}
+
+impl WalkEntry {
+    /// Returns the path of the entry, joined to the walk root.
+    pub fn path(&self) -> &FlexPath {
+        &self.path
+    }
+
+    /// Returns the depth of the entry, the walk root having depth zero.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of the entry, not following symbolic links.
+    pub fn file_type(&self) -> fs::FileType {
+        self.file_type
+    }
+}
+
+/// A recursive directory walk, returned by [`FlexPath::walk`].
+pub struct Walk {
+    pending: Vec<io::Result<WalkEntry>>,
+    max_depth: usize,
+    prune: Vec<FlexGlob>,
+    filter: Option<EntryFilter>,
+}
+
+type EntryFilter = Box<dyn FnMut(&WalkEntry) -> bool>;
+
+impl Walk {
+    /// Does not descend into entries deeper than `depth`.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Skips entries whose path matches `glob`, along with everything under them.
+    pub fn prune(mut self, glob: FlexGlob) -> Self {
+        self.prune.push(glob);
+        self
+    }
+
+    /// Skips entries for which `predicate` returns `false`, along with
+    /// everything under them.
+    pub fn filter_entry<F: FnMut(&WalkEntry) -> bool + 'static>(mut self, predicate: F) -> Self {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+
+    fn push_children(&mut self, parent: &WalkEntry) {
+        let children = match parent.path.read_dir() {
+            Ok(children) => children,
+            Err(error) => return self.pending.push(Err(error)),
+        };
+        let (mut entries, errors): (Vec<_>, Vec<_>) = children.map(|child| {
+            let path = child?;
+            let file_type = fs::symlink_metadata(path.to_path_buf())?.file_type();
+            Ok(WalkEntry { path, depth: parent.depth + 1, file_type })
+        }).partition(|child| child.is_ok());
+        // Entries are popped from the end, so they are pushed in reverse order.
+        entries.sort_by(|a: &io::Result<WalkEntry>, b| b.as_ref().unwrap().path.cmp(&a.as_ref().unwrap().path));
+        self.pending.extend(errors);
+        self.pending.extend(entries);
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.pending.pop()? {
+                Ok(entry) => entry,
+                Err(error) => return Some(Err(error)),
+            };
+            if self.prune.iter().any(|glob| glob.is_match(&entry.path)) {
+                continue;
+            }
+            if self.filter.as_mut().is_some_and(|filter| !filter(&entry)) {
+                continue;
+            }
+            if entry.file_type.is_dir() && entry.depth < self.max_depth {
+                self.push_children(&entry);
+            }
+            return Some(Ok(entry));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn existence() {
+        let manifest = FlexPath::new_native(env!("CARGO_MANIFEST_DIR"));
+        assert!(current_dir().unwrap().is_absolute());
+        assert!(home_dir().is_none_or(|home| home.is_absolute()));
+        assert!(manifest.is_dir().unwrap());
+        assert!(manifest.resolve("Cargo.toml").is_file().unwrap());
+        assert!(manifest.resolve("Cargo.toml").metadata().unwrap().len() > 0);
+        assert!(!manifest.resolve("missing").exists().unwrap());
+
+        let canonical = manifest.resolve("src/../Cargo.toml").canonicalize(VerbatimPolicy::Simplify).unwrap();
+        assert!(canonical.is_absolute());
+        assert_eq!("Cargo.toml", canonical.base_name());
+
+        let children: Vec<FlexPath> = manifest.read_dir().unwrap().map(Result::unwrap).collect();
+        assert!(children.contains(&manifest.resolve("src")));
+        let names: Vec<FlexPath> = manifest.read_dir().unwrap().relative_names().map(Result::unwrap).collect();
+        assert!(names.contains(&FlexPath::new_native("Cargo.toml")));
+
+        let walked: Vec<WalkEntry> = manifest.resolve("src").walk().max_depth(1).map(Result::unwrap).collect();
+        assert_eq!(0, walked[0].depth());
+        assert!(walked.iter().any(|entry| entry.path() == &manifest.resolve("src/lib.rs") && entry.depth() == 1));
+        assert!(walked.windows(2).skip(1).all(|pair| pair[0].path() < pair[1].path()));
+
+        let pattern = manifest.to_string() + "/**/*.toml";
+        let matches: Vec<FlexPath> = glob(&pattern).unwrap().map(Result::unwrap).collect();
+        assert!(matches.contains(&manifest.resolve("Cargo.toml")));
+        assert_eq!(0, glob(&(manifest.to_string() + "/missing/*")).unwrap().count());
+
+        let cargo_toml = manifest.resolve("Cargo.toml");
+        assert!(cargo_toml.is_same_file_as(&FlexPath::new_native("Cargo.toml").absolutize(&manifest).unwrap()).unwrap());
+        assert!(manifest.resolve("missing/a").is_same_file_as(&manifest.resolve("missing/b/../a")).unwrap());
+        assert!(!cargo_toml.is_same_file_as(&manifest.resolve("missing")).unwrap());
+        #[cfg(unix)]
+        {
+            let dir = FlexPath::new_native(std::env::temp_dir().to_str().unwrap()).create_random_dir("hydroperx-", "").unwrap();
+            let link = dir.resolve("link");
+            std::os::unix::fs::symlink(cargo_toml.native_path().unwrap(), link.native_path().unwrap()).unwrap();
+            assert!(link.is_same_file_as(&cargo_toml).unwrap());
+            std::fs::remove_dir_all(dir.native_path().unwrap()).unwrap();
+        }
+
+        let foreign = if FlexPathVariant::NATIVE == FlexPathVariant::Windows { FlexPathVariant::Common } else { FlexPathVariant::Windows };
+        assert_eq!(io::ErrorKind::Unsupported, FlexPath::new("a", foreign).exists().unwrap_err().kind());
+    }
+}
@@ -0,0 +1,228 @@
+/*!
+This module contains the `FlexGlob` pattern matcher.
+*/
+
+use super::{FlexPath, FlexPathError, FlexPathVariant, PathCmpOptions};
+use lazy_regex::{regex, regex_find, regex_is_match, Regex, RegexBuilder};
+
+/// A glob pattern matched against whole resolved paths.
+///
+/// Syntax:
+///
+/// - `*` matches any text within a single segment.
+/// - `**` as a whole segment matches zero or more segments.
+/// - `?` matches one character other than a separator.
+/// - `[abc]`, `[a-z]` and `[!abc]` match one character of a class.
+/// - `{a,b}` matches any of the comma-separated alternatives.
+///
+/// Both forward slashes (`/`) and backslashes (`\`) are separators, as in
+/// the rest of this crate; use a class such as `[*]` to match a wildcard
+/// character literally. Each `*`, `**`, `?` and class forms a capture,
+/// numbered from 1 in the order it appears in the pattern, while
+/// alternations do not.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexGlob, FlexPath, FlexPathVariant};
+/// let glob = FlexGlob::new("src/**/*.{rs,toml}", FlexPathVariant::Common).unwrap();
+/// assert!(glob.is_match(&FlexPath::new_common("src/a/b/lib.rs")));
+/// assert!(glob.is_match(&FlexPath::new_common("src/lib.rs")));
+/// assert!(!glob.is_match(&FlexPath::new_common("src/lib.rs/x")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FlexGlob {
+    pattern: String,
+    variant: FlexPathVariant,
+    regex: Regex,
+}
+
+impl FlexGlob {
+    /// Compiles a glob pattern, comparing letter case according to
+    /// the conventions of `variant`.
+    pub fn new(pattern: &str, variant: FlexPathVariant) -> Result<Self, FlexPathError> {
+        Self::with_options(pattern, variant, PathCmpOptions::for_variant(variant))
+    }
+
+    /// Compiles a glob pattern, comparing letter case according to `options`.
+    pub fn with_options(pattern: &str, variant: FlexPathVariant, options: PathCmpOptions) -> Result<Self, FlexPathError> {
+        let source = translate(pattern)?;
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|_| FlexPathError::InvalidGlob { pattern: pattern.to_owned(), reason: "unsupported syntax" })?;
+        Ok(Self { pattern: pattern.to_owned(), variant, regex })
+    }
+
+    /// Returns the pattern as given.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the variant the pattern was compiled for.
+    pub fn variant(&self) -> FlexPathVariant {
+        self.variant
+    }
+
+    /// Indicates whether the whole path matches the pattern.
+    pub fn is_match(&self, path: &FlexPath) -> bool {
+        self.regex.is_match(&match_text(path))
+    }
+
+    /// Splits the pattern into its leading segments free of wildcards,
+    /// forming the directory under which every match lies, and the
+    /// number of segments that follow, or `None` if a `**` allows any depth.
+    #[cfg_attr(not(feature = "fs"), allow(dead_code))]
+    pub(crate) fn literal_base(&self) -> (String, Option<usize>) {
+        let normalized = normalize_pattern(&self.pattern);
+        let verbatim = regex_find!(r"^//\?(/|$)", &normalized).unwrap_or("");
+        let segments: Vec<&str> = normalized[verbatim.len()..].split('/').collect();
+        let literal = segments.iter().take_while(|s| !s.contains(['*', '?', '[', '{'])).count();
+        let base = verbatim.to_owned() + &segments[..literal].join("/");
+        let base = if base.is_empty() && normalized.starts_with('/') { "/".to_owned() } else { base };
+        let rest = &segments[literal..];
+        (base, if rest.contains(&"**") { None } else { Some(rest.len()) })
+    }
+
+    /// Matches the whole path, returning the text of each wildcard capture,
+    /// preceded by the whole path at index 0.
+    pub fn captures(&self, path: &FlexPath) -> Option<Vec<String>> {
+        let text = match_text(path);
+        let captures = self.regex.captures(&text)?;
+        Some(captures.iter().map(|c| c.map_or("", |c| c.as_str()).to_owned()).collect())
+    }
+}
+
+/// Returns the text of a resolved path that patterns match against,
+/// with every separator as a forward slash and no trailing separator.
+fn match_text(path: &FlexPath) -> String {
+    trim_trailing_separator(path.0.replace('\\', "/"))
+}
+
+fn trim_trailing_separator(mut s: String) -> String {
+    if s.len() > 1 && s.ends_with('/') {
+        s.pop();
+    }
+    s
+}
+
+/// Normalizes the separators of a pattern like [`match_text`], collapsing
+/// repeated separators except for a leading UNC prefix.
+fn normalize_pattern(pattern: &str) -> String {
+    let unc = regex_is_match!(r"^[/\\]{2}", pattern);
+    let r = regex!(r"[/\\]+").replace_all(pattern, "/");
+    trim_trailing_separator((if unc { "/" } else { "" }).to_owned() + &r)
+}
+
+fn translate(pattern: &str) -> Result<String, FlexPathError> {
+    let invalid = |reason| FlexPathError::InvalidGlob { pattern: pattern.to_owned(), reason };
+    let normalized = normalize_pattern(pattern);
+    // The `?` of a verbatim prefix is literal rather than a wildcard.
+    let verbatim = regex_find!(r"^//\?(/|$)", &normalized).unwrap_or("");
+    let chars: Vec<char> = normalized[verbatim.len()..].chars().collect();
+    let mut r = "^".to_owned() + &lazy_regex::regex::escape(verbatim);
+    let mut i = 0;
+    let mut in_alternation = false;
+    while i < chars.len() {
+        let at_segment_start = if i == 0 { verbatim.is_empty() || verbatim.ends_with('/') } else { chars[i - 1] == '/' };
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') && at_segment_start && matches!(chars.get(i + 2), None | Some('/')) => {
+                if chars.get(i + 2).is_some() {
+                    // `**/` matches zero or more leading segments.
+                    r.push_str("((?:[^/]*/)*?)");
+                    i += 3;
+                    continue;
+                }
+                if r.ends_with('/') {
+                    // `/**` matches the directory itself and anything under it.
+                    r.pop();
+                    r.push_str("((?:/.*)?)");
+                } else {
+                    r.push_str("(.*)");
+                }
+                i += 1;
+            },
+            '*' => r.push_str("([^/]*)"),
+            '?' => r.push_str("([^/])"),
+            '[' => {
+                let negated = matches!(chars.get(i + 1), Some('!' | '^'));
+                let start = if negated { i + 2 } else { i + 1 };
+                let mut j = start;
+                let mut body = String::new();
+                while j < chars.len() && (chars[j] != ']' || j == start) {
+                    if chars[j] == '/' {
+                        return Err(invalid("separator inside a character class"));
+                    }
+                    if matches!(chars[j], '\\' | '[' | ']' | '&' | '~' | '^') {
+                        body.push('\\');
+                    }
+                    body.push(chars[j]);
+                    j += 1;
+                }
+                if j == chars.len() {
+                    return Err(invalid("unclosed character class"));
+                }
+                r.push_str(&if negated { format!("([^/{body}])") } else { format!("([{body}&&[^/]])") });
+                i = j;
+            },
+            '{' if !in_alternation => {
+                r.push_str("(?:");
+                in_alternation = true;
+            },
+            ',' if in_alternation => r.push('|'),
+            '}' if in_alternation => {
+                r.push(')');
+                in_alternation = false;
+            },
+            c => r.push_str(&lazy_regex::regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        i += 1;
+    }
+    if in_alternation {
+        return Err(invalid("unclosed alternation"));
+    }
+    r.push('$');
+    Ok(r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matching() {
+        let common = FlexPathVariant::Common;
+        let windows = FlexPathVariant::Windows;
+        let glob = FlexGlob::new("/a/*/c", common).unwrap();
+        assert!(glob.is_match(&FlexPath::new_common("/a/b/c")));
+        assert!(!glob.is_match(&FlexPath::new_common("/a/b/x/c")));
+        assert_eq!(Some(vec!["/a/b/c".to_owned(), "b".to_owned()]), glob.captures(&FlexPath::new_common("/a/b/c")));
+
+        let glob = FlexGlob::new("a/**", common).unwrap();
+        assert!(glob.is_match(&FlexPath::new_common("a")));
+        assert!(glob.is_match(&FlexPath::new_common("a/b/c")));
+        assert!(!glob.is_match(&FlexPath::new_common("ab")));
+
+        let glob = FlexGlob::new("file[0-9][!a].?xt", common).unwrap();
+        assert!(glob.is_match(&FlexPath::new_common("file1b.txt")));
+        assert!(!glob.is_match(&FlexPath::new_common("file1a.txt")));
+        assert!(!glob.is_match(&FlexPath::new_common("filex1.txt")));
+
+        let glob = FlexGlob::new(r"C:\Users\*\*.TXT", windows).unwrap();
+        assert!(glob.is_match(&FlexPath::new(r"c:\users\me\notes.txt", windows)));
+        let glob = FlexGlob::new(r"\\srv\share\**\*.log", windows).unwrap();
+        assert!(glob.is_match(&FlexPath::new(r"\\srv\share\a\x.log", windows)));
+
+        let glob = FlexGlob::new(r"\\?\C:\*", windows).unwrap();
+        assert!(glob.is_match(&FlexPath::new(r"\\?\C:\x", windows)));
+        assert!(!glob.is_match(&FlexPath::new(r"\\a\C:\x", windows)));
+        assert_eq!((r"//?/C:".to_owned(), Some(1)), glob.literal_base());
+        assert_eq!(("/a".to_owned(), None), FlexGlob::new("/a/**/b", common).unwrap().literal_base());
+
+        assert!(FlexGlob::new("a[b", common).is_err());
+        assert!(FlexGlob::new("a{b", common).is_err());
+
+        let glob = FlexGlob::new("{src,lib}/*.{rs,toml}", common).unwrap();
+        assert_eq!(Some(vec!["lib/a.rs".to_owned(), "a".to_owned()]), glob.captures(&FlexPath::new_common("lib/a.rs")));
+    }
+}
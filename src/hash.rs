@@ -0,0 +1,84 @@
+/*!
+This module contains the stable hashing of paths.
+*/
+
+use super::{flexible, FlexPath, FlexPathVariant};
+use lazy_regex::regex_captures;
+
+const FNV64_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV64_PRIME: u64 = 0x100000001b3;
+const FNV128_OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV128_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+impl FlexPath {
+    /// The version of the algorithm of [`.hash_stable`](Self::hash_stable)
+    /// and [`.hash_stable_128`](Self::hash_stable_128), incremented whenever
+    /// a release changes their results.
+    pub const STABLE_HASH_VERSION: u32 = 1;
+
+    /// Returns a 64-bit hash of the path that is the same across runs,
+    /// platforms and releases of the same [`STABLE_HASH_VERSION`](Self::STABLE_HASH_VERSION),
+    /// unlike the `Hash` implementation, so that it can be persisted in
+    /// build caches and content-addressed stores.
+    ///
+    /// Version 1 is the FNV-1a hash of a tag byte, 0 for `Common` and
+    /// 1 for `Windows`, followed by the UTF-8 path with forward slashes
+    /// and an uppercase drive letter. Letter case is otherwise significant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// assert_eq!(FlexPath::new(r"c:\a", windows).hash_stable(), FlexPath::new("C:/a", windows).hash_stable());
+    /// assert_ne!(FlexPath::new("/a", windows).hash_stable(), FlexPath::new_common("/a").hash_stable());
+    /// ```
+    pub fn hash_stable(&self) -> u64 {
+        self.stable_hash_input().fold(FNV64_OFFSET, |h, b| (h ^ u64::from(b)).wrapping_mul(FNV64_PRIME))
+    }
+
+    /// Returns a 128-bit hash of the path like [`.hash_stable`](Self::hash_stable),
+    /// for stores where 64-bit collisions are a concern. Version 1 is the
+    /// 128-bit FNV-1a hash of the same bytes.
+    pub fn hash_stable_128(&self) -> u128 {
+        self.stable_hash_input().fold(FNV128_OFFSET, |h, b| (h ^ u128::from(b)).wrapping_mul(FNV128_PRIME))
+    }
+
+    fn stable_hash_input(&self) -> impl Iterator<Item = u8> {
+        let tag = match self.1 {
+            FlexPathVariant::Common => 0,
+            FlexPathVariant::Windows => 1,
+        };
+        std::iter::once(tag).chain(version_1_text(&self.0, self.1).into_bytes())
+    }
+}
+
+/// Returns the path text hashed by version 1, with forward slashes and an
+/// uppercase drive letter. This is kept apart from [`flexible::canonical`]
+/// so that changes to the canonical form do not change stable hashes.
+fn version_1_text(path: &str, manipulation: FlexPathVariant) -> String {
+    let root_len = flexible::root_len(path, manipulation);
+    let root = path[..root_len].replace('\\', "/");
+    let root = match regex_captures!(r"^(//\?/)?([a-z])(:.*)$", &root) {
+        Some((_, verbatim, drive, rest)) => verbatim.to_owned() + &drive.to_uppercase() + rest,
+        None => root,
+    };
+    root + &path[root_len..]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pinned_values() {
+        // these values must only change along with `STABLE_HASH_VERSION`.
+        assert_eq!(0x78678f66471ca052, FlexPath::new_common("/a/b").hash_stable());
+        assert_eq!(0xf2f313817e83d94f708038fbc9cb58fa, FlexPath::new_common("/a/b").hash_stable_128());
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(0x0dc546dd69681ad2, FlexPath::new("c:/x", windows).hash_stable());
+        assert_eq!(FlexPath::new(r"\\srv\share\a", windows).hash_stable(), FlexPath::new("//srv/share/a", windows).hash_stable());
+        assert_ne!(FlexPath::new("a/B", windows).hash_stable(), FlexPath::new("a/b", windows).hash_stable());
+        assert_ne!(FlexPath::new(r"\\?\unc\srv\a", windows).hash_stable(), FlexPath::new(r"\\?\UNC\srv\a", windows).hash_stable());
+    }
+}
@@ -0,0 +1,91 @@
+/*!
+This module contains the `PathInterner` and its `PathId` handles.
+*/
+
+use super::FlexPath;
+use std::{collections::HashMap, sync::Arc};
+
+/// A small handle to a path stored in a [`PathInterner`].
+///
+/// Handles from the same interner are equal exactly when their paths
+/// are equal, so they can be compared and hashed in constant time.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PathId(u32);
+
+impl PathId {
+    /// Returns the index of the handle, assigned in insertion order from zero.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Deduplicates resolved paths into [`PathId`] handles, each path
+/// being stored once.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, PathInterner};
+/// let mut interner = PathInterner::new();
+/// let a = interner.intern(FlexPath::new_common("a/b"));
+/// let b = interner.intern(FlexPath::new_common("a/./b/"));
+/// assert_eq!(a, b);
+/// assert_eq!("a/b", interner.resolve(a).to_string());
+/// ```
+#[derive(Default)]
+pub struct PathInterner {
+    paths: Vec<Arc<FlexPath>>,
+    ids: HashMap<Arc<FlexPath>, PathId>,
+}
+
+impl PathInterner {
+    /// Constructs an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of distinct paths interned.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Indicates whether no path has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the handle of `path`, interning it if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct paths are interned.
+    pub fn intern(&mut self, path: FlexPath) -> PathId {
+        if let Some(id) = self.ids.get(&path) {
+            return *id;
+        }
+        let id = PathId(u32::try_from(self.paths.len()).expect("hydroperx_path::PathInterner is full"));
+        let path = Arc::new(path);
+        self.paths.push(path.clone());
+        self.ids.insert(path, id);
+        id
+    }
+
+    /// Returns the handle of `path` if it has been interned.
+    pub fn get(&self, path: &FlexPath) -> Option<PathId> {
+        self.ids.get(path).copied()
+    }
+
+    /// Returns the path of a handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle was not produced by this interner.
+    pub fn resolve(&self, id: PathId) -> &FlexPath {
+        &self.paths[id.index()]
+    }
+
+    /// Iterates over the handles and paths in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (PathId, &FlexPath)> {
+        self.paths.iter().enumerate().map(|(i, path)| (PathId(i as u32), path.as_ref()))
+    }
+}
@@ -31,8 +31,98 @@ assert_eq!("../../c/d", FlexPath::new_common("/a/b").relative("/c/d"));
 use lazy_regex::*;
 use std::{path::{Path, PathBuf}, str::FromStr};
 
+pub(crate) mod ancestor;
+pub(crate) mod arena;
+pub(crate) mod cache;
+pub(crate) mod case;
+pub(crate) mod case_folding;
+pub(crate) mod classify;
+pub(crate) mod cmp;
 pub(crate) mod common;
+pub(crate) mod component;
+pub(crate) mod compress;
+pub(crate) mod context;
+pub(crate) mod debug;
+#[cfg(feature = "counters")]
+pub mod counters;
+pub(crate) mod default_variant;
+pub(crate) mod diff;
+pub(crate) mod display;
+pub(crate) mod error;
+pub(crate) mod extension;
+pub(crate) mod filter;
 pub(crate) mod flexible;
+#[cfg(feature = "fs")]
+pub(crate) mod fs;
+pub(crate) mod glob;
+pub(crate) mod hash;
+pub(crate) mod interner;
+pub(crate) mod limits;
+pub(crate) mod list;
+pub(crate) mod map;
+pub(crate) mod parents;
+pub(crate) mod pointer;
+pub(crate) mod mount;
+#[cfg(feature = "os-str")]
+pub(crate) mod os_str;
+pub mod raw;
+pub(crate) mod rename;
+pub(crate) mod report;
+pub(crate) mod rewrite;
+pub(crate) mod segments;
+pub(crate) mod set;
+pub(crate) mod storage;
+pub(crate) mod suffix;
+pub(crate) mod temp;
+pub(crate) mod tree;
+pub(crate) mod trie;
+pub(crate) mod typed;
+pub(crate) mod url;
+pub(crate) mod wasi;
+#[cfg(feature = "fs")]
+pub(crate) mod watch;
+pub(crate) mod which;
+#[cfg(feature = "unicode")]
+pub(crate) mod width;
+
+pub use arena::PathArena;
+pub use cache::{PathCache, PathCacheStats};
+pub use classify::PrefixClassifier;
+pub use cmp::PathCmpOptions;
+pub use component::StdComponents;
+pub use compress::{decode_prefix_compressed, encode_prefix_compressed};
+pub use context::ResolveContext;
+pub use default_variant::DefaultVariantGuard;
+pub use diff::{diff_paths, DiffOptions, PathDiff};
+pub use display::RelativeDisplay;
+pub use error::FlexPathError;
+pub use extension::{Extension, ExtensionMap, ExtensionSet};
+pub use filter::PathFilter;
+#[cfg(feature = "fs")]
+pub use fs::{current_dir, glob, home_dir, Glob, ReadDir, Walk, WalkEntry};
+pub use glob::FlexGlob;
+pub use interner::{PathId, PathInterner};
+pub use limits::{InputLimits, LimitViolation, PathLimits};
+pub use list::{format_path_list, join_path_list, parse_path_list, path_list_separator, read_path_list, split_path_list, write_path_list, ListDelimiter};
+pub use map::PathMap;
+pub use parents::{dir_file_pairs, group_by_parent, DirFilePairs, NameOrder};
+pub use mount::MountTable;
+pub use report::{InputEdit, ResolveEdit, ResolveEditKind, ResolveReport, SpannedPath, SpannedSegment};
+pub use rename::plan_renames;
+pub use rewrite::RewriteRules;
+pub use segments::Segments;
+pub use set::{dedup_paths, find_case_collisions, PathSet};
+pub use suffix::SuffixIndex;
+pub use tree::{PathTree, PathTreeNode};
+pub use trie::PathTrie;
+pub use typed::{AbsoluteFlexPath, RelativeFlexPath};
+pub use url::UrlPathOptions;
+pub use wasi::WasiPreopens;
+#[cfg(feature = "fs")]
+pub use watch::WatchRoot;
+pub use which::executable_candidates;
+#[cfg(feature = "fs")]
+pub use which::find_in_path;
 
 /// Indicates if special absolute paths are considered.
 ///
@@ -41,7 +131,7 @@ pub(crate) mod flexible;
 /// 
 /// * `Common`
 /// * `Windows`
-#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum FlexPathVariant {
     /// Indicates that the path is manipulated in a Unix common way, resulting into forward slashes.
     Common,
@@ -63,46 +153,280 @@ impl FlexPathVariant {
     pub const fn native() -> Self {
         Self::NATIVE
     }
+
+    /// The separator with which paths of this variant are formatted:
+    /// a backward slash (`\`) for `Windows` and a forward slash (`/`) otherwise.
+    pub const fn separator(self) -> char {
+        separator_for(self)
+    }
+}
+
+/// The separator with which paths of the native variant are formatted.
+pub const SEPARATOR: char = separator_for(FlexPathVariant::NATIVE);
+
+/// Returns the separator with which paths of `variant` are formatted.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{separator_for, FlexPathVariant};
+/// assert_eq!(format!("a{}b", separator_for(FlexPathVariant::Windows)), r"a\b");
+/// assert_eq!('/', FlexPathVariant::Common.separator());
+/// ```
+pub const fn separator_for(variant: FlexPathVariant) -> char {
+    match variant {
+        FlexPathVariant::Common => '/',
+        FlexPathVariant::Windows => '\\',
+    }
+}
+
+/// Options controlling how the extensions of a file name are recognized.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct ExtensionOptions {
+    /// Indicates whether leading dots of a file name start an extension, as
+    /// in `.gitignore` having the extension `gitignore`. This is `false`
+    /// by default, matching `std::path`; set it to keep the behavior
+    /// of earlier versions of this crate.
+    pub leading_dot_is_extension: bool,
+    /// Indicates whether extensions are compared case-sensitively, as on
+    /// case-sensitive file systems where `.C` and `.c` differ. This is `false`
+    /// by default, in which case both sides are compared with Unicode case folding.
+    pub case_sensitive: bool,
+}
+
+/// Options controlling how [`FlexPath::new_with`] resolves a path.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct ResolveOptions {
+    /// Indicates whether the `Common` variant keeps the root of a path
+    /// starting with exactly two separators, as in `//host/a`, rather than
+    /// collapsing it into `/host/a`. POSIX leaves the meaning of such a root
+    /// to the implementation, and systems such as Cygwin treat it as a
+    /// network path. This is `false` by default. The `Windows` variant
+    /// ignores it, always taking such a path as UNC.
+    pub preserve_double_root: bool,
+    /// Indicates whether a non-empty relative path that resolves to the
+    /// current directory, such as `.` or `a/..`, is kept as `.` rather than
+    /// becoming the empty path. This is `false` by default. Either way, the
+    /// empty path stays empty, and `.` is dropped when resolving other
+    /// paths against it.
+    pub preserve_current_dir: bool,
+}
+
+/// Options controlling how [`normalize_path_with`] normalizes a path.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct NormalizeOptions {
+    /// Indicates whether dots and spaces are stripped from the end of each
+    /// segment, as Win32 APIs silently do for paths that are not in the
+    /// verbatim form, so that the normalized verbatim path names the same
+    /// file. This is `false` by default, in which case such segments are
+    /// only reported.
+    pub strip_trailing_dots_and_spaces: bool,
+}
+
+/// A segment found by [`normalize_path_with`] ending with dots or spaces.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TrailingCharsSegment {
+    /// The index of the segment after the root.
+    pub index: usize,
+    /// The segment as it was before any stripping.
+    pub segment: String,
+}
+
+/// The segments [`normalize_path_with`] found ending with dots or spaces,
+/// in order, which were stripped if [`NormalizeOptions::strip_trailing_dots_and_spaces`]
+/// was set.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct NormalizeReport {
+    /// The segments ending with dots or spaces.
+    pub trailing_segments: Vec<TrailingCharsSegment>,
+}
+
+/// Indicates whether a Windows path in the verbatim (`\\?\`) form
+/// is kept in that form or simplified.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum VerbatimPolicy {
+    /// Keeps the verbatim form.
+    Keep,
+    /// Converts `\\?\C:\` and `\\?\UNC\` prefixes into the equivalent
+    /// drive and UNC forms, unless the path needs the verbatim form, such
+    /// as when it is too long or has a segment ending with a dot or a space.
+    #[default]
+    Simplify,
+}
+
+/// Indicates how [`FlexPath::with_dedup_suffix`] numbers a file name,
+/// placing the number between the stem and the extension.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum DedupPattern {
+    /// Produces names like `name (1).ext`.
+    #[default]
+    Parenthesized,
+    /// Produces names like `name-1.ext`.
+    Hyphenated,
+}
+
+/// Indicates how [`FlexPath::push_segment`] handles a segment that would
+/// not be taken literally, such as one containing a separator or `..`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum SegmentPolicy {
+    /// Fails with [`FlexPathError::InvalidSegment`].
+    #[default]
+    Reject,
+    /// Percent-encodes the offending characters, along with any `%`,
+    /// so that distinct segments remain distinct.
+    Escape,
+}
+
+/// Indicates how [`FlexPath::ascend`] handles a request to ascend more
+/// levels than the path has segments.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum AscendPolicy {
+    /// Stops at the root, or at the empty path for a relative path.
+    #[default]
+    Clamp,
+    /// Fails with [`FlexPathError::AboveRoot`].
+    Reject,
 }
 
 /// The `FlexPath` structure represents an always-resolved textual file path based
 /// on a [_FlexPathVariant_].
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct FlexPath(String, FlexPathVariant);
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlexPath(storage::Storage, FlexPathVariant);
 
 impl FlexPath {
     /// Constructs a `FlexPath` with a given `variant`. This method
     /// will resolve the specified path.
     pub fn new(path: &str, variant: FlexPathVariant) -> Self {
-        Self(flexible::resolve_one(path, variant), variant)
+        Self::from_resolved(flexible::resolve_one(path, variant), variant)
+    }
+
+    /// Constructs a `FlexPath` with a given `variant`, resolving the
+    /// specified path according to `options`.
+    ///
+    /// A path keeping a double root, as checked by
+    /// [`.has_double_root`](Self::has_double_root), keeps it when relative
+    /// paths are resolved against it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveOptions};
+    /// let options = ResolveOptions { preserve_double_root: true, ..Default::default() };
+    /// let path = FlexPath::new_with("//host/a/../b", FlexPathVariant::Common, options);
+    /// assert_eq!("//host/b", path.to_string());
+    /// assert_eq!("//host/b/c", path.resolve("c").to_string());
+    /// assert_eq!("/a", FlexPath::new_with("///a", FlexPathVariant::Common, options).to_string());
+    /// assert_eq!("/host", FlexPath::new_common("//host").to_string());
+    ///
+    /// let options = ResolveOptions { preserve_current_dir: true, ..Default::default() };
+    /// assert_eq!(".", FlexPath::new_with("./a/..", FlexPathVariant::Common, options).to_string());
+    /// assert_eq!("", FlexPath::new_common(".").to_string());
+    /// ```
+    pub fn new_with(path: &str, variant: FlexPathVariant, options: ResolveOptions) -> Self {
+        let r = flexible::resolve_one(path, variant);
+        if options.preserve_current_dir && r.is_empty() && !path.is_empty() {
+            return Self::from_resolved(".", variant);
+        }
+        if options.preserve_double_root && variant == FlexPathVariant::Common && starts_with_double_root(path) {
+            return Self::from_resolved("/".to_owned() + &r, variant);
+        }
+        Self::from_resolved(r, variant)
     }
 
     /// Constructs a `FlexPath` whose variant is `Common`. This method
     /// will resolve the specified path.
     pub fn new_common(path: &str) -> Self {
-        Self(flexible::resolve_one(path, FlexPathVariant::Common), FlexPathVariant::Common)
+        Self::from_resolved(flexible::resolve_one(path, FlexPathVariant::Common), FlexPathVariant::Common)
     }
 
-    /// Constructs a `FlexPath` whose variant is chosen according to the target platform.
-    /// This method will resolve the specified path.
+    /// Constructs a `FlexPath` whose variant is the [default variant](FlexPath::default_variant),
+    /// which is chosen according to the target platform unless configured
+    /// otherwise. This method will resolve the specified path.
     pub fn new_native(path: &str) -> Self {
-        Self(flexible::resolve_one(path, FlexPathVariant::NATIVE), FlexPathVariant::NATIVE)
+        Self::new(path, Self::default_variant())
     }
 
     /// Constructs a `FlexPath` from multiple paths and a given `variant`.
-    pub fn from_n<'a, T: IntoIterator<Item = &'a str>>(paths: T, variant: FlexPathVariant) -> Self {
-        Self(flexible::resolve_n(paths, variant), variant)
+    /// The paths may be of any type implementing `AsRef<str>`, including
+    /// `String` and `Cow<str>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let segments = vec!["a".to_owned(), "b".to_owned()];
+    /// assert_eq!("a/b", FlexPath::from_n(&segments, FlexPathVariant::Common).to_string());
+    /// let bases = ["/x".to_owned(), "y".to_owned()];
+    /// assert_eq!("/x/y", FlexPath::from_n_common(bases).to_string());
+    /// let parts = [std::borrow::Cow::Borrowed("c"), std::borrow::Cow::Owned("d".to_owned())];
+    /// assert_eq!("/x/c/d", FlexPath::new_common("/x").resolve_n(parts).to_string());
+    /// ```
+    pub fn from_n<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T, variant: FlexPathVariant) -> Self {
+        Self::from_resolved(flexible::resolve_n(paths, variant), variant)
     }
 
     /// Constructs a `FlexPath` from multiple paths and a `Common` variant.
-    pub fn from_n_common<'a, T: IntoIterator<Item = &'a str>>(paths: T) -> Self {
+    pub fn from_n_common<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T) -> Self {
         Self::from_n(paths, FlexPathVariant::Common)
     }
 
-    /// Constructs a `FlexPath` from multiple paths and a variant based on
-    /// the target platform.
-    pub fn from_n_native<'a, T: IntoIterator<Item = &'a str>>(paths: T) -> Self {
-        Self::from_n(paths, FlexPathVariant::NATIVE)
+    /// Constructs a `FlexPath` from multiple paths and the
+    /// [default variant](FlexPath::default_variant), which is based on
+    /// the target platform unless configured otherwise.
+    pub fn from_n_native<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T) -> Self {
+        Self::from_n(paths, Self::default_variant())
+    }
+
+    /// Returns the default variant, used by the `FromStr` and `Default`
+    /// implementations and by the `*_native` constructors.
+    ///
+    /// This is the innermost override from [`FlexPath::scoped_default_variant`]
+    /// on the current thread, if any, or else the variant given to
+    /// [`FlexPath::set_default_variant`], or else the native variant.
+    pub fn default_variant() -> FlexPathVariant {
+        default_variant::get()
+    }
+
+    /// Sets the default variant for the whole process.
+    ///
+    /// This is useful for tools that manipulate paths of another platform,
+    /// such as when cross-compiling for Windows. Constructors with an
+    /// explicit variant are not affected, while the `*_native` ones follow
+    /// the default variant. Paths returned by the operating system, as by
+    /// the `fs` feature, keep the variant of the target platform.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// FlexPath::set_default_variant(FlexPathVariant::Windows);
+    /// let path: FlexPath = r"C:\a\..\b".parse().unwrap();
+    /// assert_eq!(FlexPath::new("C:/b", FlexPathVariant::Windows), path);
+    /// ```
+    pub fn set_default_variant(variant: FlexPathVariant) {
+        default_variant::set(variant);
+    }
+
+    /// Overrides the default variant on the current thread until the
+    /// returned guard is dropped, taking precedence over
+    /// [`FlexPath::set_default_variant`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// {
+    ///     let _guard = FlexPath::scoped_default_variant(FlexPathVariant::Windows);
+    ///     assert_eq!(FlexPathVariant::Windows, FlexPath::default().variant());
+    /// }
+    /// ```
+    pub fn scoped_default_variant(variant: FlexPathVariant) -> DefaultVariantGuard {
+        default_variant::scoped(variant)
+    }
+
+    /// Wraps text that is already resolved for `variant`.
+    pub(crate) fn from_resolved(text: impl Into<storage::Storage>, variant: FlexPathVariant) -> Self {
+        Self(text.into(), variant)
     }
 
     /// Returns the variant this `FlexPath` object is based on.
@@ -124,14 +448,175 @@ impl FlexPath {
     /// - If any path is absolute, this function returns an absolute path.
     /// - Any empty segment and trailing path separators, such as in `a/b/` and `a//b` are eliminated.
     pub fn resolve(&self, path2: &str) -> FlexPath {
-        FlexPath(flexible::resolve(&self.0, path2, self.1), self.1)
+        self.keep_double_root(flexible::resolve(&self.0, path2, self.1), path2)
+    }
+
+    /// Resolves another `FlexPath` relative to this path, as by
+    /// [`.resolve`](Self::resolve), without formatting it first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path2` has another variant than this path; see
+    /// [`.try_resolve_path`](Self::try_resolve_path) for a fallible version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// let base = FlexPath::new(r"C:\a", windows);
+    /// assert_eq!(FlexPath::new(r"C:\a\b", windows), base.resolve_path(&FlexPath::new("b", windows)));
+    /// assert_eq!(FlexPath::new(r"D:\b", windows), base.resolve_path(&FlexPath::new(r"D:\b", windows)));
+    /// ```
+    pub fn resolve_path(&self, path2: &FlexPath) -> FlexPath {
+        assert!(self.1 == path2.1, "The argument to hydroperx_path::FlexPath::resolve_path() must have the same variant as the path.");
+        if path2.has_double_root() {
+            return path2.clone();
+        }
+        self.resolve(&path2.0)
+    }
+
+    /// Resolves another `FlexPath` relative to this path like
+    /// [`.resolve_path`](Self::resolve_path), failing rather than mixing
+    /// variants.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if `path2` has another
+    /// variant than this path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathError, FlexPathVariant};
+    /// let base = FlexPath::new_common("/a");
+    /// assert_eq!(FlexPath::new_common("/a/b"), base.try_resolve_path(&FlexPath::new_common("b")).unwrap());
+    /// let windows = FlexPath::new("b", FlexPathVariant::Windows);
+    /// assert!(matches!(base.try_resolve_path(&windows), Err(FlexPathError::VariantMismatch { .. })));
+    /// ```
+    pub fn try_resolve_path(&self, path2: &FlexPath) -> Result<FlexPath, FlexPathError> {
+        if self.1 != path2.1 {
+            return Err(FlexPathError::VariantMismatch { expected: self.1, found: path2.1 });
+        }
+        Ok(self.resolve_path(path2))
+    }
+
+    /// Wraps the resolution of `path2` relative to this path, restoring the
+    /// double root of this path if `path2` is relative.
+    fn keep_double_root(&self, r: String, path2: &str) -> FlexPath {
+        if self.has_double_root() && !flexible::is_absolute(path2, self.1) {
+            return FlexPath::from_resolved("/".to_owned() + &r, self.1);
+        }
+        FlexPath::from_resolved(r, self.1)
+    }
+
+    /// Appends the resolution of `path2` relative to this path to `out`,
+    /// formatted as by the `Display` implementation.
+    ///
+    /// This is equivalent to formatting [`.resolve`] into `out`, but for
+    /// the `Common` variant it does not allocate beyond growing `out`,
+    /// so that a single buffer can be reused when generating many paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let base = FlexPath::new_common("archive/root");
+    /// let mut name = String::new();
+    /// for entry in ["a/../b.txt", "c/./d.txt"] {
+    ///     name.clear();
+    ///     base.resolve_into(entry, &mut name);
+    ///     assert_eq!(base.resolve(entry).to_string(), name);
+    /// }
+    /// ```
+    pub fn resolve_into(&self, path2: &str, out: &mut String) {
+        match self.1 {
+            FlexPathVariant::Common => {
+                if self.has_double_root() && !flexible::is_absolute(path2, self.1) {
+                    out.push('/');
+                }
+                common::resolve_into(&self.0, path2, out);
+            },
+            FlexPathVariant::Windows => {
+                let r = flexible::resolve(&self.0, path2, self.1);
+                out.extend(r.chars().map(|ch| if ch == '/' { '\\' } else { ch }));
+            },
+        }
+    }
+
+    /// Resolves this path into an absolute path against the current
+    /// directory of `ctx`. See [`ResolveContext::resolve`].
+    ///
+    /// Constructing a path already makes a drive-relative path such as
+    /// `D:a` absolute on its drive root, so the per-drive current directories
+    /// of `ctx` do not apply here; use [`FlexPath::new_in`] to construct a
+    /// path from text against `ctx` instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variant of this path differs from that of the context.
+    pub fn resolve_in(&self, ctx: &ResolveContext) -> FlexPath {
+        assert!(self.1 == ctx.cwd().1, "The argument to hydroperx_path::FlexPath::resolve_in() must have the same variant as the path.");
+        ctx.resolve(&self.0)
+    }
+
+    /// Constructs a path of the variant of `ctx` from text, making it
+    /// absolute against the current directory of `ctx`, as by
+    /// [`ResolveContext::resolve`]. Unlike [`.resolve_in`], drive-relative
+    /// paths such as `D:a` resolve against the current directory of
+    /// their drive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveContext};
+    /// let windows = FlexPathVariant::Windows;
+    /// let ctx = ResolveContext::new(FlexPath::new(r"C:\work", windows))
+    ///     .with_drive_cwd('D', FlexPath::new(r"D:\data", windows));
+    /// assert_eq!(FlexPath::new(r"D:\data\a", windows), FlexPath::new_in("D:a", &ctx));
+    /// assert_eq!(FlexPath::new(r"D:\a", windows), FlexPath::new("D:a", windows).resolve_in(&ctx));
+    /// ```
+    pub fn new_in(path: &str, ctx: &ResolveContext) -> FlexPath {
+        ctx.resolve(path)
+    }
+
+    /// Makes this path absolute against `base`, returning it unchanged
+    /// if it is already absolute.
+    ///
+    /// Unlike [`normalize_path`], this neither consults the current
+    /// directory of the process nor converts the path into the verbatim
+    /// form or lowercase.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::RelativePath`] if `base` is relative and
+    /// [`FlexPathError::VariantMismatch`] if it has another variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathError};
+    /// let base = FlexPath::new_common("/home/user");
+    /// assert_eq!(FlexPath::new_common("/home/user/a"), FlexPath::new_common("a").absolutize(&base).unwrap());
+    /// assert_eq!(FlexPath::new_common("/etc"), FlexPath::new_common("/etc").absolutize(&base).unwrap());
+    /// assert!(matches!(FlexPath::new_common("a").absolutize(&FlexPath::new_common("b")), Err(FlexPathError::RelativePath { .. })));
+    /// ```
+    pub fn absolutize(&self, base: &FlexPath) -> Result<FlexPath, FlexPathError> {
+        if base.1 != self.1 {
+            return Err(FlexPathError::VariantMismatch { expected: self.1, found: base.1 });
+        }
+        if !base.is_absolute() {
+            return Err(FlexPathError::RelativePath { path: base.to_string() });
+        }
+        Ok(self.resolve_in(&ResolveContext::new(base.clone())))
     }
 
     /// Resolves multiple paths relative to this path. The
     /// behavior is similiar to [`.resolve`]. If the given
     /// set has no items, an empty string is returned.
-    pub fn resolve_n<'a, T: IntoIterator<Item = &'a str>>(&self, paths: T) -> FlexPath {
-        FlexPath(flexible::resolve(&self.0, &flexible::resolve_n(paths, self.1), self.1), self.1)
+    pub fn resolve_n<T: IntoIterator<Item = S>, S: AsRef<str>>(&self, paths: T) -> FlexPath {
+        let path2 = flexible::resolve_n(paths, self.1);
+        self.keep_double_root(flexible::resolve(&self.0, &path2, self.1), &path2)
     }
 
     /**
@@ -140,10 +625,12 @@ impl FlexPath {
     # Behavior:
 
     - If the paths refer to the same path, this function returns
-    an empty string.
+      an empty string.
     - The function ensures that both paths are absolute and resolves
-    any `..` and `.` segments inside.
-    - If both paths have different prefix, `to_path` is returned.
+      any `..` and `.` segments inside.
+    - If both paths have different prefix, `to_path` is returned. For
+      UNC paths, the prefix includes the host and share, as `..` cannot
+      leave a share, so that `\\srv\a` and `\\srv\b` have different prefixes.
 
     # Panics
 
@@ -160,122 +647,1262 @@ impl FlexPath {
     ```
     */
     pub fn relative(&self, to_path: &str) -> String {
-        flexible::relative(&self.0, to_path, self.1)
+        flexible::relative(&self.0, to_path, self.1, PathCmpOptions::default())
+    }
+
+    /// Finds the relative path from this path to `to_path` like
+    /// [`.relative`], returning it as a `FlexPath` of the same variant,
+    /// so that it is formatted with the variant's separators.
+    ///
+    /// Leading `..` segments are kept, although constructing the same
+    /// relative path with [`FlexPath::new`] would eliminate them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let from = FlexPath::new(r"C:\a\b", FlexPathVariant::Windows);
+    /// assert_eq!(r"..\c\d", from.relative_as_path(r"C:\a\c\d").to_string());
+    /// ```
+    pub fn relative_as_path(&self, to_path: &str) -> FlexPath {
+        FlexPath::from_resolved(self.relative(to_path), self.1)
+    }
+
+    /// Finds the relative path from this path to `to_path` like
+    /// [`.relative`], comparing segments according to `options`.
+    ///
+    /// Drive letters are compared case-insensitively regardless of
+    /// `options`; with case-insensitive options, so are UNC host names
+    /// and all other segments, while the result keeps the letter case
+    /// of `to_path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, PathCmpOptions};
+    /// let windows = FlexPathVariant::Windows;
+    /// let options = PathCmpOptions::for_variant(windows);
+    /// assert_eq!("../Bar", FlexPath::new(r"C:\Foo\a", windows).relative_with(r"c:\foo\Bar", options));
+    /// assert_eq!("b", FlexPath::new(r"\\SRV\share", windows).relative_with(r"\\srv\Share\b", options));
+    /// ```
+    pub fn relative_with(&self, to_path: &str, options: PathCmpOptions) -> String {
+        flexible::relative(&self.0, to_path, self.1, options)
+    }
+
+    /// Finds the relative path from this path to `to_path` like
+    /// [`.relative_with`], unless it would climb more than
+    /// `max_parent_levels` directories, in which case the resolved
+    /// absolute `to_path` is returned, as a link generator would prefer
+    /// over a long chain of `..` segments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if given paths are not absolute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, PathCmpOptions};
+    /// let page = FlexPath::new_common("/docs/guide/intro/setup");
+    /// let options = PathCmpOptions::default();
+    /// assert_eq!("../../api", page.relative_within("/docs/guide/api", 2, options));
+    /// assert_eq!("/docs/api", page.relative_within("/docs/api", 2, options));
+    /// assert_eq!("a", page.relative_within("/docs/guide/intro/setup/a", 0, options));
+    /// ```
+    pub fn relative_within(&self, to_path: &str, max_parent_levels: usize, options: PathCmpOptions) -> String {
+        let r = flexible::relative(&self.0, to_path, self.1, options);
+        if r.split('/').take_while(|s| *s == "..").nth(max_parent_levels).is_some() {
+            return flexible::resolve_one(to_path, self.1);
+        }
+        r
+    }
+
+    /// Changes the extension of a path and returns a new string.
+    /// This method adds any lacking dot (`.`) prefix automatically to the
+    /// `extension` argument, which may also be an [`Extension`].
+    ///
+    /// This method allows multiple dots per extension. If that is not
+    /// desired, use [`.change_last_extension`].
+    ///
+    /// # Example
+    /// 
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("a.y", FlexPath::new_common("a.x").change_extension(".y").to_string());
+    /// assert_eq!("a.z", FlexPath::new_common("a.x.y").change_extension(".z").to_string());
+    /// assert_eq!("a.z.w", FlexPath::new_common("a.x.y").change_extension(".z.w").to_string());
+    /// ```
+    ///
+    pub fn change_extension(&self, extension: impl AsRef<str>) -> FlexPath {
+        self.change_extension_with(extension, ExtensionOptions::default())
+    }
+
+    /// Changes the extension of a path like [`.change_extension`],
+    /// recognizing extensions according to `options`.
+    pub fn change_extension_with(&self, extension: impl AsRef<str>, options: ExtensionOptions) -> FlexPath {
+        Self::from_resolved(change_extension(&self.0, extension.as_ref(), options), self.1)
+    }
+
+    /// Changes only the last extension of a path and returns a new string.
+    /// This method adds any lacking dot (`.`) prefix automatically to the
+    /// `extension` argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension contains more than one dot.
+    ///
+    pub fn change_last_extension(&self, extension: &str) -> FlexPath {
+        Self::from_resolved(change_last_extension(&self.0, extension), self.1)
+    }
+
+    /// Appends an extension to the path, keeping any existing extension,
+    /// and returns a new path. This method adds any lacking dot (`.`)
+    /// prefix automatically to the `extension` argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension is empty or contains a path separator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("backup.sql.gz", FlexPath::new_common("backup.sql").add_extension("gz").to_string());
+    /// ```
+    pub fn add_extension(&self, extension: &str) -> FlexPath {
+        Self::from_resolved(add_extension(&self.0, extension), self.1)
+    }
+
+    /// Removes the last extension of the file name and returns a new path.
+    /// A path without a file name, such as a root, is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("foo/a.tar", FlexPath::new_common("foo/a.tar.gz").without_extension().to_string());
+    /// ```
+    pub fn without_extension(&self) -> FlexPath {
+        if self.file_name().is_none() {
+            return self.clone();
+        }
+        let ext_len = last_extension(&self.0, ExtensionOptions::default()).map_or(0, |ext| ext.len() + 1);
+        Self::from_resolved(self.0[..self.0.len() - ext_len].to_owned(), self.1)
+    }
+
+    /// Removes the compound extension of the file name and returns a new path.
+    /// A path without a file name, such as a root, is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("foo/a", FlexPath::new_common("foo/a.tar.gz").remove_extension().to_string());
+    /// ```
+    pub fn remove_extension(&self) -> FlexPath {
+        if self.file_name().is_none() {
+            return self.clone();
+        }
+        let ext_len = full_extension(&self.0, ExtensionOptions::default()).map_or(0, |ext| ext.len() + 1);
+        Self::from_resolved(self.0[..self.0.len() - ext_len].to_owned(), self.1)
+    }
+
+    /// Checks if a file path has a specific extension, ignoring case.
+    /// This method adds any lacking dot (`.`) prefix automatically to the
+    /// `extension` argument, which may also be an [`Extension`].
+    pub fn has_extension(&self, extension: impl AsRef<str>) -> bool {
+        self.has_extension_with(extension, ExtensionOptions::default())
+    }
+
+    /// Checks if a file path has a specific extension like [`.has_extension`],
+    /// recognizing extensions according to `options`.
+    pub fn has_extension_with(&self, extension: impl AsRef<str>, options: ExtensionOptions) -> bool {
+        has_extension(&self.0, extension.as_ref(), options)
+    }
+
+    /// Checks if a file path has any of multiple specific extensions.
+    /// This method adds any lacking dot (`.`) prefix automatically to each
+    /// extension argument.
+    ///
+    /// To check many paths against the same extensions, build an
+    /// [`ExtensionSet`] once and use [`.matches_extension_set`] instead.
+    ///
+    /// [`.matches_extension_set`]: FlexPath::matches_extension_set
+    pub fn has_extensions<'a, T: IntoIterator<Item = &'a str>>(&self, extensions: T) -> bool {
+        has_extensions(&self.0, extensions)
+    }
+
+    /// Indicates whether the path is a Windows path in the verbatim
+    /// (`\\?\`) form, which the Windows operating system does not normalize.
+    ///
+    /// Verbatim prefixes written with forward slashes, as in `//?/C:/a`,
+    /// are canonicalized into the `\\?\C:\a` form.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new("//?/C:/foo", FlexPathVariant::Windows);
+    /// assert!(path.is_verbatim());
+    /// assert_eq!(r"\\?\C:\foo", path.to_string());
+    /// assert!(!FlexPath::new_common("//?/C:/foo").is_verbatim());
+    /// ```
+    pub fn is_verbatim(&self) -> bool {
+        self.1 == FlexPathVariant::Windows && self.0.starts_with(r"\\?\")
+    }
+
+    /// Indicates whether the path is a Windows UNC path, naming a share
+    /// on a server, as in `\\server\share\a` or `\\?\UNC\server\share\a`.
+    ///
+    /// UNC prefixes written with forward slashes, as in `//server/share`,
+    /// are common in cross-platform configuration files and are
+    /// canonicalized into the `\\server\share` form. For the `Common`
+    /// variant, a leading double slash is an ordinary separator, so that
+    /// `//server/share` is `/server/share` and never a UNC path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// let path = FlexPath::new("//server/share/x", windows);
+    /// assert!(path.is_unc());
+    /// assert_eq!(FlexPath::new(r"\\server\share\x", windows), path);
+    /// assert!(FlexPath::new(r"\\?\UNC\server\share", windows).is_unc());
+    /// assert!(!FlexPath::new(r"\\?\C:\x", windows).is_unc());
+    /// assert!(!FlexPath::new(r"\\.\pipe\x", windows).is_unc());
+    /// assert!(!FlexPath::new_common("//server/share").is_unc());
+    /// ```
+    pub fn is_unc(&self) -> bool {
+        self.1 == FlexPathVariant::Windows
+            && (regex_is_match!(r"^\\\\[^?.]", &self.0) || regex_is_match!(r"(?i)^\\\\\?\\unc(/|$)", &self.0))
+    }
+
+    /// Indicates whether the path resolves against the current directory,
+    /// that is, whether it is relative, including the empty path and `.`.
+    /// A `Windows` path rooted without a drive, such as `\a`, is not, as it
+    /// resolves against the root of the current drive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveOptions};
+    /// let options = ResolveOptions { preserve_current_dir: true, ..Default::default() };
+    /// assert!(FlexPath::new_with(".", FlexPathVariant::Common, options).is_cwd_relative());
+    /// assert!(FlexPath::new_common("a/b").is_cwd_relative());
+    /// assert!(!FlexPath::new(r"\a", FlexPathVariant::Windows).is_cwd_relative());
+    /// ```
+    pub fn is_cwd_relative(&self) -> bool {
+        !self.is_absolute()
+    }
+
+    /// Indicates whether the path is of the `Common` variant and rooted at
+    /// exactly two separators, as constructed by [`FlexPath::new_with`] with
+    /// [`ResolveOptions::preserve_double_root`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveOptions};
+    /// let options = ResolveOptions { preserve_double_root: true, ..Default::default() };
+    /// assert!(FlexPath::new_with("//a", FlexPathVariant::Common, options).has_double_root());
+    /// assert!(!FlexPath::new_common("//a").has_double_root());
+    /// assert!(!FlexPath::new_with("//a/b", FlexPathVariant::Windows, options).has_double_root());
+    /// ```
+    pub fn has_double_root(&self) -> bool {
+        self.1 == FlexPathVariant::Common && self.0.starts_with("//")
+    }
+
+    /// Converts a verbatim path into the equivalent drive or UNC form,
+    /// as in `\\?\C:\a` into `C:\a` and `\\?\UNC\srv\share` into
+    /// `\\srv\share`.
+    ///
+    /// The path is returned unchanged if it is not verbatim or if the
+    /// other form would not refer to the same file, such as when it is too
+    /// long or has a segment ending with a dot or a space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// assert_eq!(FlexPath::new(r"C:\a", windows), FlexPath::new(r"\\?\C:\a", windows).strip_verbatim());
+    /// assert!(FlexPath::new(r"\\?\C:\a.", windows).strip_verbatim().is_verbatim());
+    /// ```
+    pub fn strip_verbatim(&self) -> FlexPath {
+        FlexPath::from_resolved(flexible::simplify_verbatim(&self.0, self.1), self.1)
+    }
+
+    /// Converts an absolute drive or UNC path of the `Windows` variant into
+    /// the verbatim form, without making it absolute or changing its case
+    /// as [`normalize_path`] does.
+    ///
+    /// Verbatim paths are returned unchanged; paths of other variants,
+    /// relative paths and paths rooted without a drive return `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// assert_eq!(r"\\?\C:\Program Files", FlexPath::new("c:/Program Files", windows).to_verbatim().unwrap().to_string());
+    /// assert_eq!(r"\\?\UNC\srv\share\a", FlexPath::new(r"\\srv\share\a", windows).to_verbatim().unwrap().to_string());
+    /// assert_eq!(None, FlexPath::new(r"\a", windows).to_verbatim());
+    /// ```
+    pub fn to_verbatim(&self) -> Option<FlexPath> {
+        if self.1 != FlexPathVariant::Windows {
+            return None;
+        }
+        if self.is_verbatim() {
+            return Some(self.clone());
+        }
+        if let Some((_, drive, rest)) = regex_captures!(r"^([A-Za-z]):(.*)$", &self.0) {
+            return Some(FlexPath::new(&format!(r"\\?\{}:/{}", drive.to_uppercase(), rest), self.1));
+        }
+        if regex_is_match!(r"^[\\/][\\/][^\\/]", &self.0) {
+            return Some(FlexPath::new(&(r"\\?\UNC".to_owned() + &self.0[1..]), self.1));
+        }
+        None
+    }
+
+    /// Indicates whether any segment looks like a generated 8.3 short
+    /// name, such as `PROGRA~1` or `LONGNA~2.TXT`, in which case the path
+    /// may be expanded into its long form through the file system.
+    ///
+    /// This is a heuristic on the text only: a long name may happen to
+    /// match the pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// assert!(FlexPath::new(r"C:\PROGRA~1\app", FlexPathVariant::Windows).looks_like_short_name());
+    /// assert!(!FlexPath::new(r"C:\backup~\app", FlexPathVariant::Windows).looks_like_short_name());
+    /// ```
+    pub fn looks_like_short_name(&self) -> bool {
+        self.0[flexible::root_len(&self.0, self.1)..].split('/').any(is_short_name)
+    }
+
+    /// Indicates whether any segment contains a character with a special
+    /// meaning in [`FlexGlob`] patterns: `*`, `?`, `[` or `{`. The `?` of
+    /// a verbatim (`\\?\`) prefix is not considered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// assert!(FlexPath::new_common("src/*.rs").contains_wildcard());
+    /// assert!(!FlexPath::new(r"\\?\C:\src", FlexPathVariant::Windows).contains_wildcard());
+    /// ```
+    pub fn contains_wildcard(&self) -> bool {
+        self.0[flexible::root_len(&self.0, self.1)..].contains(['*', '?', '[', '{'])
+    }
+
+    /// Returns the base name of a file path.
+    ///
+    /// # Example
+    /// 
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("qux.html", FlexPath::new_common("foo/qux.html").base_name());
+    /// ```
+    pub fn base_name(&self) -> String {
+        base_name(&self.0)
+    }
+
+    /// Returns the last segment of the path, or `None` if the path is
+    /// empty or consists solely of a root, such as `/` or `C:\`.
+    ///
+    /// Unlike [`.base_name`], this does not allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// assert_eq!(Some("qux.html"), FlexPath::new_common("foo/qux.html").file_name());
+    /// assert_eq!(None, FlexPath::new_common("/").file_name());
+    /// assert_eq!(None, FlexPath::new(r"\\server\share\", FlexPathVariant::Windows).file_name());
+    /// ```
+    pub fn file_name(&self) -> Option<&str> {
+        let rest = &self.0[flexible::root_len(&self.0, self.1)..];
+        (!rest.is_empty()).then(|| file_name_str(rest))
+    }
+
+    /// Returns the base name of a file path, removing any of the specified extensions.
+    /// This method adds any lacking dot (`.`) prefix automatically to each
+    /// extension argument.
+    ///
+    /// # Example
+    /// 
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("qux", FlexPath::new_common("foo/qux.html").base_name_without_ext([".html"]));
+    /// ```
+    pub fn base_name_without_ext<'a, T>(&self, extensions: T) -> String
+        where T: IntoIterator<Item = &'a str>
+    {
+        base_name_without_ext(&self.0, extensions)
+    }
+
+    /// Splits the file name into its stem and the longest of the candidate
+    /// `extensions` that it ends with, returning the candidate as given,
+    /// or `None` with the whole file name if none matches. A path without
+    /// a file name, such as a root, gives an empty stem and `None`.
+    ///
+    /// Candidates are matched like in [`.has_extension`], so that `.gz`
+    /// matches `a.tar.gz` and `.tar.gz` is preferred over it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let path = FlexPath::new_common("dist/app.tar.GZ");
+    /// assert_eq!(("app", Some(".tar.gz")), path.split_known_extension([".gz", ".tar.gz", ".zip"]));
+    /// assert_eq!(("app.tar", Some("gz")), path.split_known_extension(["gz"]));
+    /// assert_eq!(("app.tar.GZ", None), path.split_known_extension([".zip"]));
+    /// ```
+    pub fn split_known_extension<'a, T>(&self, extensions: T) -> (&str, Option<&'a str>)
+        where T: IntoIterator<Item = &'a str>
+    {
+        let Some(name) = self.file_name() else {
+            return ("", None);
+        };
+        let Some(full) = full_extension(name, ExtensionOptions::default()) else {
+            return (name, None);
+        };
+        let candidates = extensions.into_iter().map(|ext| (ext, case::fold(&extension_arg(ext)))).collect::<Vec<_>>();
+        // Compare at each dot so that the split falls where the matched
+        // suffix starts in the name, whose length may differ once folded.
+        let start = name.len() - full.len() - 1;
+        for (dot, _) in name[start..].match_indices('.') {
+            let suffix = case::fold(&name[start + dot..]);
+            if let Some((ext, _)) = candidates.iter().find(|(_, folded)| *folded == suffix) {
+                return (&name[..start + dot], Some(ext));
+            }
+        }
+        (name, None)
+    }
+
+    /// Returns the compound extension of the file name, without the
+    /// leading dot, matching what [`.change_extension`] replaces.
+    ///
+    /// A file name starting with a dot, such as `.gitignore`, has no
+    /// extension unless [`ExtensionOptions::leading_dot_is_extension`] is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!(Some("tar.gz"), FlexPath::new_common("foo/a.tar.gz").full_extension());
+    /// assert_eq!(None, FlexPath::new_common("foo/a").full_extension());
+    /// ```
+    pub fn full_extension(&self) -> Option<&str> {
+        self.full_extension_with(ExtensionOptions::default())
+    }
+
+    /// Returns the compound extension of the file name like [`.full_extension`],
+    /// recognizing extensions according to `options`.
+    pub fn full_extension_with(&self, options: ExtensionOptions) -> Option<&str> {
+        self.file_name()?;
+        full_extension(&self.0, options)
+    }
+
+    /// Iterates over each extension of the file name in order, without
+    /// leading dots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let path = FlexPath::new_common("app.min.js");
+    /// assert_eq!(vec!["min", "js"], path.extensions().collect::<Vec<_>>());
+    /// ```
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.full_extension().into_iter().flat_map(|ext| ext.split('.'))
+    }
+
+    /// Returns the file name without its compound extension, or an empty
+    /// string for a path without a file name, such as `\\srv\share`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("a", FlexPath::new_common("foo/a.tar.gz").stem_without_full_extension());
+    /// ```
+    pub fn stem_without_full_extension(&self) -> &str {
+        if self.file_name().is_none() {
+            return "";
+        }
+        stem_without_full_extension(&self.0)
+    }
+
+    /// Splits the path into its directory, file stem and compound extension.
+    ///
+    /// The directory is borrowed from the internal representation, which
+    /// delimits segments with forward slashes (`/`) regardless of variant.
+    /// It keeps its trailing separator only when it is a root, such as `/` or `C:/`.
+    /// A path without a file name, such as `\\srv\share`, has an empty stem.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!(("foo", "a", Some("tar.gz")), FlexPath::new_common("foo/a.tar.gz").split_file_name());
+    /// assert_eq!(("/", "a", None), FlexPath::new_common("/a").split_file_name());
+    /// assert_eq!(("", "a", Some("txt")), FlexPath::new_common("a.txt").split_file_name());
+    /// ```
+    pub fn split_file_name(&self) -> (&str, &str, Option<&str>) {
+        (self.dir_str(), self.stem_without_full_extension(), self.full_extension())
+    }
+
+    /// Returns the directory part of the internal representation, as in
+    /// [`.split_file_name`].
+    pub(crate) fn dir_str(&self) -> &str {
+        let root_len = flexible::root_len(&self.0, self.1);
+        match self.0.rfind('/') {
+            Some(i) if i < root_len => &self.0[..root_len],
+            Some(i) => &self.0[..i],
+            None => "",
+        }
+    }
+
+    /// Numbers the file name with `n` according to `pattern`, keeping
+    /// the directory and the compound extension intact.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{DedupPattern, FlexPath};
+    /// let path = FlexPath::new_common("foo/a.tar.gz");
+    /// assert_eq!("foo/a (2).tar.gz", path.with_dedup_suffix(2, DedupPattern::Parenthesized).to_string());
+    /// assert_eq!("foo/a-2.tar.gz", path.with_dedup_suffix(2, DedupPattern::Hyphenated).to_string());
+    /// ```
+    pub fn with_dedup_suffix(&self, n: usize, pattern: DedupPattern) -> FlexPath {
+        let (_, stem, extension) = self.split_file_name();
+        let suffix = match pattern {
+            DedupPattern::Parenthesized => format!(" ({n})"),
+            DedupPattern::Hyphenated => format!("-{n}"),
+        };
+        let stem_end = self.0.len() - extension.map_or(0, |ext| ext.len() + 1);
+        let mut r = self.0[..stem_end].to_owned();
+        if stem.is_empty() && extension.is_none() {
+            return Self::from_resolved(r, self.1);
+        }
+        r.push_str(&suffix);
+        r.push_str(&self.0[stem_end..]);
+        Self::from_resolved(r, self.1)
+    }
+
+    /// Returns the first path among this path and its numbered variants, as produced
+    /// by [`.with_dedup_suffix`] starting from 1, for which `exists` returns `false`.
+    ///
+    /// Returns `None` if the path exists and has no file name to number,
+    /// such as the empty path, `/` or `C:\`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{DedupPattern, FlexPath};
+    /// let taken = ["a.txt", "a (1).txt"];
+    /// let path = FlexPath::new_common("a.txt").find_free_name(DedupPattern::Parenthesized, |p| taken.contains(&p.to_string().as_str()));
+    /// assert_eq!("a (2).txt", path.unwrap().to_string());
+    /// assert_eq!(None, FlexPath::new_common("/").find_free_name(DedupPattern::Parenthesized, |_| true));
+    /// ```
+    pub fn find_free_name<F>(&self, pattern: DedupPattern, mut exists: F) -> Option<FlexPath>
+        where F: FnMut(&FlexPath) -> bool
+    {
+        if !exists(self) {
+            return Some(self.clone());
+        }
+        self.file_name()?;
+        (1..).map(|n| self.with_dedup_suffix(n, pattern)).find(|candidate| !exists(candidate))
+    }
+
+    /// Shortens the stem of the file name so that the file name fits
+    /// in `max_bytes`, cutting on a character boundary and keeping
+    /// the directory and the compound extension intact.
+    ///
+    /// If the extension leaves no room for at least one character of the
+    /// stem, the file name as a whole is cut instead, so that a name is
+    /// never cut down to a bare extension such as `.txt`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!("foo/abc.txt", FlexPath::new_common("foo/abcdef.txt").truncate_file_name(7).to_string());
+    /// assert_eq!("foo/abcd", FlexPath::new_common("foo/abcdef.txt").truncate_file_name(4).to_string());
+    /// ```
+    pub fn truncate_file_name(&self, max_bytes: usize) -> FlexPath {
+        self.truncate_file_name_by(max_bytes, |s| s.char_indices().map(|(i, _)| i).collect())
+    }
+
+    /// Shortens the stem of the file name like [`.truncate_file_name`],
+    /// but never splits a grapheme cluster such as an emoji sequence.
+    #[cfg(feature = "unicode")]
+    pub fn truncate_file_name_graphemes(&self, max_bytes: usize) -> FlexPath {
+        use unicode_segmentation::UnicodeSegmentation;
+        self.truncate_file_name_by(max_bytes, |s| s.grapheme_indices(true).map(|(i, _)| i).collect())
+    }
+
+    fn truncate_file_name_by<F>(&self, max_bytes: usize, boundaries: F) -> FlexPath
+        where F: Fn(&str) -> Vec<usize>
+    {
+        let name = file_name_str(&self.0);
+        if name.len() <= max_bytes {
+            return self.clone();
+        }
+        let name_start = self.0.len() - name.len();
+        let (_, stem, extension) = self.split_file_name();
+        let ext_len = extension.map_or(0, |ext| ext.len() + 1);
+        let cut = |s: &str, keep: usize| boundaries(s).into_iter().rev().find(|i| *i <= keep).unwrap_or(0);
+        let stem_len = if ext_len < max_bytes { cut(stem, max_bytes - ext_len) } else { 0 };
+        let name = if stem_len != 0 {
+            stem[..stem_len].to_owned() + &name[stem.len()..]
+        } else {
+            name[..cut(name, max_bytes)].to_owned()
+        };
+        Self::from_resolved(self.0[..name_start].to_owned() + &name, self.1)
+    }
+
+    /// Indicates whether the file name is hidden by convention, that is,
+    /// whether it starts with a dot (`.`). The `.` and `..` segments, which
+    /// a path may keep with [`ResolveOptions`], are not hidden.
+    ///
+    /// In the Windows operating system, being hidden is a file attribute
+    /// rather than a naming convention; this method only inspects the text.
+    /// Use `is_hidden_on_disk()` with the `fs` feature to check the attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert!(FlexPath::new_common("a/.git").is_hidden());
+    /// assert!(!FlexPath::new_common(".git/a").is_hidden());
+    /// ```
+    pub fn is_hidden(&self) -> bool {
+        is_hidden_name(file_name_str(&self.0))
+    }
+
+    /// Indicates whether any segment of the path is hidden by convention,
+    /// as in [`.is_hidden`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert!(FlexPath::new_common(".git/a").has_hidden_segment());
+    /// ```
+    pub fn has_hidden_segment(&self) -> bool {
+        self.0[flexible::root_len(&self.0, self.1)..].split('/').any(is_hidden_name)
+    }
+
+    /// Returns the path as text in a form that is the same regardless of
+    /// the platform and of how the prefix was written, meant as a stable
+    /// interchange format for manifests and lock files.
+    ///
+    /// Every separator is a forward slash, the drive letter and the
+    /// verbatim `UNC` marker are uppercase and the verbatim prefix is
+    /// written as `//?/`, as in `//?/C:/a` and `//srv/share/a`. The
+    /// variant is not encoded; passing the text to [`FlexPath::new`] with
+    /// the same variant yields an equivalent path with the same canonical text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// assert_eq!("//?/C:/a/b", FlexPath::new(r"\\?\c:\a\b", windows).canonical_text());
+    /// assert_eq!("//?/UNC/srv/share", FlexPath::new(r"\\?\unc\srv\share", windows).canonical_text());
+    /// assert_eq!("//srv/share/a", FlexPath::new(r"\\srv\share\a", windows).canonical_text());
+    /// assert_eq!("/a/b", FlexPath::new_common("/a/b").canonical_text());
+    /// ```
+    pub fn canonical_text(&self) -> String {
+        flexible::canonical(&self.0, self.1)
+    }
+
+    /// Returns the key by which the platform of the path's variant tells
+    /// files apart: the [canonical text](Self::canonical_text), case-folded
+    /// for the `Windows` variant. The path itself preserves case, so the key
+    /// can be stored alongside its display form, such as in a database or an
+    /// on-disk index, to find duplicates.
+    ///
+    /// Paths of different variants may have the same key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// assert_eq!("c:/users/me", FlexPath::new(r"c:\Users\ME", windows).normal_key());
+    /// assert_eq!("//srv/share/a", FlexPath::new(r"\\SRV\Share\A", windows).normal_key());
+    /// assert_eq!("/Users/me", FlexPath::new_common("/Users/me").normal_key());
+    /// ```
+    pub fn normal_key(&self) -> String {
+        let text = self.canonical_text();
+        if self.1 == FlexPathVariant::Windows { case::fold(&text) } else { text }
+    }
+
+    /// Returns the key of [`.normal_key`](Self::normal_key) in Unicode
+    /// normalization form C, so that precomposed and decomposed spellings
+    /// of a name, as written by different file systems, have the same key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!(FlexPath::new_common("/caf\u{e9}").normal_key_nfc(), FlexPath::new_common("/cafe\u{301}").normal_key_nfc());
+    /// ```
+    #[cfg(feature = "unicode")]
+    pub fn normal_key_nfc(&self) -> String {
+        use unicode_normalization::UnicodeNormalization;
+        let text: String = self.canonical_text().nfc().collect();
+        if self.1 == FlexPathVariant::Windows { case::fold(&text).nfc().collect() } else { text }
+    }
+
+    /// Returns a key whose byte-wise order sorts paths segment by segment,
+    /// so that large lists can be sorted with `sort_unstable_by_key`
+    /// without re-splitting paths in every comparison.
+    ///
+    /// Segments are delimited by a zero byte, which sorts below any byte
+    /// of a file name, so that `a/b` sorts before `a-b`. The root is
+    /// canonicalized with forward slashes and an uppercase drive letter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let mut paths = ["a-b", "a/c", "a", "a/b"].map(FlexPath::new_common);
+    /// paths.sort_unstable_by_key(|p| p.sort_key());
+    /// assert_eq!(["a", "a/b", "a/c", "a-b"].map(FlexPath::new_common), paths);
+    /// ```
+    pub fn sort_key(&self) -> Vec<u8> {
+        let mut r = vec![self.1 as u8];
+        for (i, key) in trie::keys(self).iter().enumerate() {
+            if i != 0 {
+                r.push(0);
+            }
+            if i == 0 && flexible::root_len(&self.0, self.1) != 0 {
+                r.extend(flexible::canonical_root(key).bytes());
+            } else {
+                r.extend(key.bytes());
+            }
+        }
+        r
+    }
+
+    /// Replaces the root of the path, such as `C:\` with `D:\` or
+    /// `\\old\share` with `\\new\share`, keeping its segments. A relative
+    /// path is anchored at the new root.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidRoot`] if `new_root` is not solely a
+    /// root of the path's variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// let path = FlexPath::new(r"\\old\share\a\b", windows);
+    /// assert_eq!(r"\\new\share\a\b", path.with_root(r"\\new\share").unwrap().to_string());
+    /// assert_eq!(r"D:\a", FlexPath::new(r"C:\a", windows).with_root("D:").unwrap().to_string());
+    /// assert!(FlexPath::new(r"C:\a", windows).with_root(r"D:\x").is_err());
+    /// ```
+    pub fn with_root(&self, new_root: &str) -> Result<FlexPath, FlexPathError> {
+        let root = FlexPath::new(new_root, self.1);
+        let root_len = flexible::root_len(&root.0, root.1);
+        if root_len == 0 || root_len != root.0.len() {
+            return Err(FlexPathError::InvalidRoot { root: new_root.to_owned(), variant: self.1 });
+        }
+        let keys = trie::keys(self);
+        let skip = usize::from(flexible::root_len(&self.0, self.1) != 0);
+        Ok(root.join_segments(&keys[skip..]))
+    }
+
+    /// Returns the path on each of the given Windows drives, as for a
+    /// launcher probing several drives for `\tools\x.exe`. The path must be
+    /// relative or rooted without a drive, as in `\tools\x.exe`; either
+    /// way it is placed at the root of each drive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if the path is not of the
+    /// `Windows` variant, [`FlexPathError::AbsolutePath`] if it has a
+    /// prefix such as a drive or a UNC share, and
+    /// [`FlexPathError::InvalidRoot`] if a drive is not an ASCII letter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new(r"\tools\x.exe", FlexPathVariant::Windows);
+    /// let candidates: Vec<String> = path.with_each_drive(['C', 'd']).unwrap().iter().map(|p| p.to_string()).collect();
+    /// assert_eq!(vec![r"C:\tools\x.exe", r"D:\tools\x.exe"], candidates);
+    /// ```
+    pub fn with_each_drive<T: IntoIterator<Item = char>>(&self, drives: T) -> Result<Vec<FlexPath>, FlexPathError> {
+        if self.1 != FlexPathVariant::Windows {
+            return Err(FlexPathError::VariantMismatch { expected: FlexPathVariant::Windows, found: self.1 });
+        }
+        if flexible::prefix_len(&self.0, self.1) != 0 {
+            return Err(FlexPathError::AbsolutePath { path: self.to_string() });
+        }
+        drives.into_iter().map(|drive| {
+            let root = format!("{}:\\", drive.to_ascii_uppercase());
+            if !drive.is_ascii_alphabetic() {
+                return Err(FlexPathError::InvalidRoot { root, variant: self.1 });
+            }
+            self.with_root(&root)
+        }).collect()
+    }
+
+    /// Uppercases the drive letter of a `Windows` path, including in the
+    /// verbatim form, leaving the rest of the path alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new(r"c:\Foo", FlexPathVariant::Windows);
+    /// assert_eq!(r"C:\Foo", path.normalize_drive_letter().to_string());
+    /// ```
+    pub fn normalize_drive_letter(&self) -> FlexPath {
+        if self.1 != FlexPathVariant::Windows {
+            return self.clone();
+        }
+        match regex_find!(r"^(?:\\\\\?\\)?[a-z]:", &self.0) {
+            Some(prefix) => FlexPath::from_resolved(prefix.to_uppercase() + &self.0[prefix.len()..], self.1),
+            None => self.clone(),
+        }
+    }
+
+    /// Lowercases the host name of a `Windows` UNC path, including in the
+    /// verbatim form, leaving the share and the rest of the path alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new(r"\\SERVER\Share\A", FlexPathVariant::Windows);
+    /// assert_eq!(r"\\server\Share\A", path.normalize_unc_host().to_string());
+    /// ```
+    pub fn normalize_unc_host(&self) -> FlexPath {
+        if self.1 != FlexPathVariant::Windows {
+            return self.clone();
+        }
+        let prefix_len = if let Some(prefix) = regex_find!(r"(?i)^\\\\\?\\unc/", &self.0) {
+            prefix.len()
+        } else if regex_is_match!(r"^[\\/][\\/]([^?]|$)", &self.0) {
+            2
+        } else {
+            return self.clone();
+        };
+        let host_len = self.0[prefix_len..].find('/').unwrap_or(self.0.len() - prefix_len);
+        let host = &self.0[prefix_len..prefix_len + host_len];
+        FlexPath::from_resolved(self.0[..prefix_len].to_owned() + &host.to_lowercase() + &self.0[prefix_len + host_len..], self.1)
+    }
+
+    /// Normalizes the letter case of the prefix of a `Windows` path,
+    /// uppercasing the drive letter and the verbatim `UNC` marker and
+    /// lowercasing the UNC host name, without the other changes
+    /// [`normalize_path`] performs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new(r"\\?\unc\SRV\Share", FlexPathVariant::Windows);
+    /// assert_eq!(r"\\?\UNC\srv\Share", path.normalize_prefix_case().to_string());
+    /// ```
+    pub fn normalize_prefix_case(&self) -> FlexPath {
+        let r = self.normalize_drive_letter().normalize_unc_host();
+        if r.is_verbatim() && r.0[4..].get(..4).is_some_and(|marker| marker.eq_ignore_ascii_case("unc/")) {
+            return FlexPath::from_resolved(r"\\?\UNC".to_owned() + &r.0[7..], r.1);
+        }
+        r
     }
 
-    /// Changes the extension of a path and returns a new string.
-    /// This method adds any lacking dot (`.`) prefix automatically to the
-    /// `extension` argument.
+    /// Returns a key identifying the volume of an absolute path: its
+    /// drive letter, UNC host and share, or `/`, case-folded for the
+    /// `Windows` variant, so that files can be grouped by volume.
     ///
-    /// This method allows multiple dots per extension. If that is not
-    /// desired, use [`.change_last_extension`].
+    /// Verbatim paths have the same key as their drive and UNC forms.
+    /// Returns `None` for relative paths and for `Windows` paths rooted
+    /// without a drive, whose volume depends on the current drive.
     ///
     /// # Example
-    /// 
+    ///
     /// ```
-    /// use hydroperx_path::FlexPath;
-    /// assert_eq!("a.y", FlexPath::new_common("a.x").change_extension(".y").to_string());
-    /// assert_eq!("a.z", FlexPath::new_common("a.x.y").change_extension(".z").to_string());
-    /// assert_eq!("a.z.w", FlexPath::new_common("a.x.y").change_extension(".z.w").to_string());
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let windows = FlexPathVariant::Windows;
+    /// let key = |path| FlexPath::new(path, windows).volume_key();
+    /// assert_eq!(key(r"C:\a"), key(r"\\?\c:\b"));
+    /// assert_eq!(key(r"\\SRV\Share\a"), key(r"\\?\UNC\srv\share"));
+    /// assert_ne!(key(r"\\srv\share"), key(r"\\srv\other"));
+    /// assert_eq!(None, key(r"\a"));
     /// ```
-    ///
-    pub fn change_extension(&self, extension: &str) -> FlexPath {
-        Self(change_extension(&self.0, extension), self.1)
+    pub fn volume_key(&self) -> Option<String> {
+        let root_len = flexible::root_len(&self.0, self.1);
+        if root_len == 0 {
+            return None;
+        }
+        if self.1 == FlexPathVariant::Common {
+            return Some("/".to_owned());
+        }
+        let root = flexible::canonical_root(&self.0[..root_len]);
+        let root = root.trim_end_matches('/');
+        let key = if let Some(drive) = root.strip_prefix("//?/").filter(|r| regex_is_match!(r"^[A-Za-z]:$", r)) {
+            drive.to_owned()
+        } else if let Some(unc) = regex_find!(r"(?i)^//\?/unc/", root).map(|prefix| &root[prefix.len()..]) {
+            "//".to_owned() + unc
+        } else if root == "//?" {
+            // other verbatim paths, such as `\\?\Volume{...}`, name the volume in their first segment.
+            let volume = self.0[root_len..].split('/').next().unwrap_or("");
+            "//?/".to_owned() + volume
+        } else if root.is_empty() {
+            return None;
+        } else {
+            root.to_owned()
+        };
+        Some(case::fold(&key))
     }
 
-    /// Changes only the last extension of a path and returns a new string.
-    /// This method adds any lacking dot (`.`) prefix automatically to the
-    /// `extension` argument.
+    /// Iterates over the directories strictly between `base` and this
+    /// path, from the shallowest to the deepest, such as the directories
+    /// to create before writing this path under `base`.
     ///
-    /// # Panics
+    /// Nothing is yielded if this path is not under `base`.
     ///
-    /// Panics if the extension contains more than one dot.
+    /// # Example
     ///
-    pub fn change_last_extension(&self, extension: &str) -> FlexPath {
-        Self(change_last_extension(&self.0, extension), self.1)
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let file = FlexPath::new_common("/out/a/b/c.txt");
+    /// let dirs: Vec<String> = file.iter_dirs(&FlexPath::new_common("/out")).map(|p| p.to_string()).collect();
+    /// assert_eq!(vec!["/out/a", "/out/a/b"], dirs);
+    /// ```
+    pub fn iter_dirs(&self, base: &FlexPath) -> impl Iterator<Item = FlexPath> {
+        let keys = trie::keys(self);
+        let base_keys = trie::keys(base);
+        let count = if self.1 == base.1 && keys.starts_with(&base_keys) { keys.len().saturating_sub(base_keys.len() + 1) } else { 0 };
+        let base = base.clone();
+        (1..=count).map(move |n| base.join_segments(&keys[base_keys.len()..base_keys.len() + n]))
     }
 
-    /// Checks if a file path has a specific extension.
-    /// This method adds any lacking dot (`.`) prefix automatically to the
-    /// `extension` argument.
-    pub fn has_extension(&self, extension: &str) -> bool {
-        has_extension(&self.0, extension)
+    /// Appends a single literal segment, such as an untrusted user name,
+    /// which can never traverse to a parent directory or add more segments.
+    ///
+    /// A segment is not literal if it is empty, `.` or `..`, contains a
+    /// separator or, for the `Windows` variant, would form a drive prefix
+    /// when appended to an empty path. Such a segment is handled
+    /// according to `policy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, SegmentPolicy};
+    /// let mut path = FlexPath::new_common("/home");
+    /// assert!(path.push_segment("../etc", SegmentPolicy::Reject).is_err());
+    /// path.push_segment("../etc", SegmentPolicy::Escape).unwrap();
+    /// assert_eq!("/home/..%2Fetc", path.to_string());
+    /// ```
+    pub fn push_segment(&mut self, segment: &str, policy: SegmentPolicy) -> Result<(), FlexPathError> {
+        let drive_like = self.1 == FlexPathVariant::Windows && self.0.is_empty() && segment.contains(':');
+        let reason = if segment.is_empty() {
+            Some("the segment is empty")
+        } else if segment == "." || segment == ".." {
+            Some("the segment refers to a directory by itself")
+        } else if segment.contains(['/', '\\']) {
+            Some("the segment contains a separator")
+        } else if drive_like {
+            Some("the segment would form a drive prefix")
+        } else {
+            None
+        };
+        let segment = match (reason, policy) {
+            (None, SegmentPolicy::Reject) => std::borrow::Cow::Borrowed(segment),
+            (Some(reason), SegmentPolicy::Reject) => {
+                return Err(FlexPathError::InvalidSegment { segment: segment.to_owned(), reason });
+            },
+            (_, SegmentPolicy::Escape) => std::borrow::Cow::Owned(escape_segment(segment, drive_like)),
+        };
+        self.push_literal(&segment);
+        Ok(())
     }
 
-    /// Checks if a file path has any of multiple specific extensions.
-    /// This method adds any lacking dot (`.`) prefix automatically to each
-    /// extension argument.
-    pub fn has_extensions<'a, T: IntoIterator<Item = &'a str>>(&self, extensions: T) -> bool {
-        has_extensions(&self.0, extensions)
+    /// Removes the last `n` segments, as resolving `..` `n` times would,
+    /// without going above the root, returning the result and the number of
+    /// segments actually removed. For a relative path, the empty path is
+    /// the limit.
+    ///
+    /// # Errors
+    ///
+    /// With [`AscendPolicy::Reject`], returns [`FlexPathError::AboveRoot`] if
+    /// the path has fewer than `n` segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{AscendPolicy, FlexPath};
+    /// let path = FlexPath::new_common("/a/b/c");
+    /// assert_eq!((FlexPath::new_common("/a"), 2), path.ascend(2, AscendPolicy::Clamp).unwrap());
+    /// assert_eq!((FlexPath::new_common("/"), 3), path.ascend(5, AscendPolicy::Clamp).unwrap());
+    /// assert!(path.ascend(5, AscendPolicy::Reject).is_err());
+    /// ```
+    pub fn ascend(&self, n: usize, policy: AscendPolicy) -> Result<(FlexPath, usize), FlexPathError> {
+        let root_len = flexible::root_len(&self.0, self.1);
+        let mut rest = &self.0[root_len..];
+        let mut levels = 0;
+        while levels < n && !rest.is_empty() {
+            rest = &rest[..rest.rfind('/').unwrap_or(0)];
+            levels += 1;
+        }
+        if levels < n && policy == AscendPolicy::Reject {
+            return Err(FlexPathError::AboveRoot { path: self.to_string(), levels: n });
+        }
+        let mut r = &self.0[..root_len + rest.len()];
+        // a UNC share is resolved without a trailing separator.
+        if rest.is_empty() && self.is_unc() {
+            r = r.trim_end_matches('/');
+        }
+        Ok((FlexPath::from_resolved(r, self.1), levels))
     }
 
-    /// Returns the base name of a file path.
+    /// Appends relative paths, which may only descend: every `..` segment
+    /// and every absolute path is rejected rather than resolved. Empty and
+    /// `.` segments are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::AbsolutePath`] for an absolute path, including
+    /// one with a drive prefix for the `Windows` variant, and
+    /// [`FlexPathError::InvalidSegment`] for a path with a `..` segment.
     ///
     /// # Example
-    /// 
+    ///
     /// ```
-    /// use hydroperx_path::FlexPath;
-    /// assert_eq!("qux.html", FlexPath::new_common("foo/qux.html").base_name());
+    /// use hydroperx_path::{FlexPath, FlexPathError};
+    /// let root = FlexPath::new_common("/srv/sandbox");
+    /// assert_eq!(FlexPath::new_common("/srv/sandbox/a/b/c"), root.descend(["a/./b", "c"]).unwrap());
+    /// assert!(matches!(root.descend(["a/../../etc"]), Err(FlexPathError::InvalidSegment { .. })));
+    /// assert!(matches!(root.descend(["/etc"]), Err(FlexPathError::AbsolutePath { .. })));
     /// ```
-    pub fn base_name(&self) -> String {
-        base_name(&self.0)
+    pub fn descend<T: IntoIterator<Item = S>, S: AsRef<str>>(&self, paths: T) -> Result<FlexPath, FlexPathError> {
+        let mut r = self.clone();
+        for path in paths {
+            let path = path.as_ref();
+            if flexible::is_absolute(path, self.1) {
+                return Err(FlexPathError::AbsolutePath { path: path.to_owned() });
+            }
+            for segment in common::split_separators(path).filter(|s| !s.is_empty() && *s != ".") {
+                if segment == ".." {
+                    return Err(FlexPathError::InvalidSegment { segment: path.to_owned(), reason: "the path refers to a parent directory" });
+                }
+                r.push_literal(segment);
+            }
+        }
+        Ok(r)
     }
 
-    /// Returns the base name of a file path, removing any of the specified extensions.
-    /// This method adds any lacking dot (`.`) prefix automatically to each
-    /// extension argument.
+    /// Appends literal segments, which must not contain separators.
+    pub(crate) fn join_segments<S: AsRef<str>>(&self, segments: &[S]) -> FlexPath {
+        let mut r = self.clone();
+        for segment in segments {
+            r.push_literal(segment.as_ref());
+        }
+        r
+    }
+
+    fn push_literal(&mut self, segment: &str) {
+        if !(self.0.is_empty() || self.0.ends_with('/')) {
+            self.0.push('/');
+        }
+        self.0.push_str(segment);
+    }
+
+    /// Returns the capacity of the buffer holding the path, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes, so that
+    /// appending segments with [`.push_segment`] does not reallocate.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the buffer to the length of the path.
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    /// Empties the path, keeping its variant and the capacity of its
+    /// buffer, so that a single `FlexPath` can be reused in a loop.
     ///
     /// # Example
-    /// 
+    ///
     /// ```
-    /// use hydroperx_path::FlexPath;
-    /// assert_eq!("qux", FlexPath::new_common("foo/qux.html").base_name_without_ext([".html"]));
+    /// use hydroperx_path::{FlexPath, SegmentPolicy};
+    /// let mut path = FlexPath::new_common("");
+    /// path.reserve(64);
+    /// let capacity = path.capacity();
+    /// for name in ["a.txt", "b.txt"] {
+    ///     path.clear();
+    ///     path.push_segment("out", SegmentPolicy::Reject).unwrap();
+    ///     path.push_segment(name, SegmentPolicy::Reject).unwrap();
+    ///     assert_eq!(format!("out/{name}"), path.to_string());
+    /// }
+    /// assert_eq!(capacity, path.capacity());
     /// ```
-    pub fn base_name_without_ext<'a, T>(&self, extensions: T) -> String
-        where T: IntoIterator<Item = &'a str>
-    {
-        base_name_without_ext(&self.0, extensions)
+    pub fn clear(&mut self) {
+        self.0.clear();
     }
 
+    /// Converts the path into a `PathBuf` using the separators of its
+    /// variant, regardless of the host platform.
+    ///
+    /// On a host of another platform, the result may name a single file
+    /// with backslashes in its name; use [`.try_to_native_path`] to
+    /// detect such mismatches.
     pub fn to_path_buf(&self) -> PathBuf {
         PathBuf::from_str(&self.to_string()).unwrap_or(PathBuf::new())
     }
+
+    /// Converts the path into a `PathBuf` for the host platform, failing
+    /// with [`FlexPathError::VariantMismatch`] if its variant is not the
+    /// native variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new_native("a/b");
+    /// assert_eq!(path.to_path_buf(), path.try_to_native_path().unwrap());
+    /// let foreign = if FlexPathVariant::native() == FlexPathVariant::Windows { FlexPathVariant::Common } else { FlexPathVariant::Windows };
+    /// assert!(FlexPath::new("a/b", foreign).try_to_native_path().is_err());
+    /// ```
+    pub fn try_to_native_path(&self) -> Result<PathBuf, FlexPathError> {
+        if self.1 != FlexPathVariant::NATIVE {
+            return Err(FlexPathError::VariantMismatch { expected: FlexPathVariant::NATIVE, found: self.1 });
+        }
+        Ok(self.to_path_buf())
+    }
+}
+
+/// Constructs an empty `FlexPath` based on [`FlexPath::default_variant`].
+impl Default for FlexPath {
+    fn default() -> Self {
+        Self::from_resolved(String::new(), Self::default_variant())
+    }
+}
+
+/// Resolves a path based on [`FlexPath::default_variant`].
+impl std::str::FromStr for FlexPath {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s, Self::default_variant()))
+    }
 }
 
-impl ToString for FlexPath {
-    /// Returns a string representation of the path,
-    /// delimiting segments with either a forward slash (`/`) or backward slash (`\`)
-    /// depending on the path's `FlexPathVariant`.
-    fn to_string(&self) -> String {
+impl std::fmt::Display for FlexPath {
+    /// Formats the path, delimiting segments with either a forward slash (`/`)
+    /// or backward slash (`\`) depending on the path's `FlexPathVariant`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.variant() == FlexPathVariant::Windows {
-            self.0.replace('/', "\\")
+            f.write_str(&self.0.replace('/', "\\"))
         } else {
-            self.0.clone()
+            f.write_str(&self.0)
         }
     }
 }
 
-static STARTS_WITH_PATH_SEPARATOR: Lazy<Regex> = lazy_regex!(r"^[/\\]");
+/// A resolved path borrowed from a [`FlexPath`] or from a [`PathArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlexPathRef<'a>(&'a str, FlexPathVariant);
+
+impl FlexPathRef<'_> {
+    /// Returns the variant this path is based on.
+    pub fn variant(&self) -> FlexPathVariant {
+        self.1
+    }
+
+    /// Indicates whether the path is absolute or not.
+    pub fn is_absolute(&self) -> bool {
+        flexible::is_absolute(self.0, self.1)
+    }
+
+    /// Copies the path into an owned `FlexPath`.
+    pub fn to_flex_path(&self) -> FlexPath {
+        FlexPath::from_resolved(self.0.to_owned(), self.1)
+    }
+}
+
+impl<'a> From<&'a FlexPath> for FlexPathRef<'a> {
+    fn from(path: &'a FlexPath) -> Self {
+        Self(&path.0, path.1)
+    }
+}
 
-fn change_extension(path: &str, extension: &str) -> String {
-    let extension = (if extension.starts_with('.') { "" } else { "." }).to_owned() + extension;
-    if regex_find!(r"(\.[^\.]+)+$", path).is_none() {
-        return path.to_owned() + &extension;
+impl std::fmt::Display for FlexPathRef<'_> {
+    /// Formats the path like the `Display` implementation of `FlexPath`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.variant() == FlexPathVariant::Windows {
+            f.write_str(&self.0.replace('/', "\\"))
+        } else {
+            f.write_str(self.0)
+        }
     }
-    regex_replace!(r"(\.[^\.]+)+$", path, |_, _| &extension).into_owned()
+}
+
+static STARTS_WITH_PATH_SEPARATOR: Lazy<Regex> = lazy_regex!(r"^[/\\]");
+
+fn change_extension(path: &str, extension: &str, options: ExtensionOptions) -> String {
+    let extension = extension_arg(extension);
+    let ext_start = regex_find!(r"(\.[^\.]+)+$", &mask_leading_dots(path, options)).map_or(path.len(), |ext| path.len() - ext.len());
+    path[..ext_start].to_owned() + &extension
 }
 
 fn change_last_extension(path: &str, extension: &str) -> String {
-    let extension = (if extension.starts_with('.') { "" } else { "." }).to_owned() + extension;
+    let extension = extension_arg(extension);
     assert!(
         extension[1..].find('.').is_none(),
         "The argument to hydroperx_path::change_last_extension() must only contain one extension; got {}",
         extension
     );
-    if regex_find!(r"(\..+)$", path).is_none() {
-        return path.to_owned() + &extension;
-    }
-    regex_replace!(r"(\..+)$", path, |_, _| &extension).into_owned()
+    let ext_start = regex_find!(r"(\..+)$", &mask_leading_dots(path, ExtensionOptions::default())).map_or(path.len(), |ext| path.len() - ext.len());
+    path[..ext_start].to_owned() + &extension
+}
+
+/// Replaces the leading dots of the file name with NUL characters, unless
+/// they are considered extensions, so that the extension patterns of
+/// [`change_extension`] and [`change_last_extension`] cannot start there
+/// while byte offsets stay the same.
+fn mask_leading_dots(path: &str, options: ExtensionOptions) -> String {
+    let name = file_name_str(path);
+    let candidate = extension_candidate(path, options);
+    let name_start = path.len() - name.len();
+    let dots = name.len() - candidate.len();
+    path[..name_start].to_owned() + &"\0".repeat(dots) + candidate
+}
+
+fn add_extension(path: &str, extension: &str) -> String {
+    let extension = extension_arg(extension);
+    assert!(
+        extension.len() > 1 && !extension.contains(['/', '\\']),
+        "The argument to hydroperx_path::add_extension() must be a non-empty extension without path separators; got {}",
+        extension
+    );
+    path.to_owned() + &extension
+}
+
+/// Indicates whether a segment looks like an 8.3 short name, as described
+/// for [`FlexPath::looks_like_short_name`].
+fn is_short_name(segment: &str) -> bool {
+    regex_captures!(r"^([^~.]+)~([1-9][0-9]{0,5})(?:\.[^.]{1,3})?$", segment)
+        .is_some_and(|(_, base, number)| base.len() + 1 + number.len() <= 8)
 }
 
 /// Adds prefix dot to extension if missing.
@@ -283,29 +1910,87 @@ fn extension_arg(extension: &str) -> String {
     (if extension.starts_with('.') { "" } else { "." }).to_owned() + extension
 }
 
-fn has_extension(path: &str, extension: &str) -> bool {
-    let extension = extension.to_lowercase();
-    let extension = (if extension.starts_with('.') { "" } else { "." }).to_owned() + &extension;
-    path.to_lowercase().ends_with(&extension_arg(&extension))
+fn has_extension(path: &str, extension: &str, options: ExtensionOptions) -> bool {
+    let fold = |s: &str| if options.case_sensitive { s.to_owned() } else { case::fold(s) };
+    let extension = fold(&extension_arg(extension));
+    full_extension(path, options).is_some_and(|ext| fold(&(".".to_owned() + ext)).ends_with(&extension))
 }
 
 fn has_extensions<'a, T: IntoIterator<Item = &'a str>>(path: &str, extensions: T) -> bool {
-    extensions.into_iter().any(|ext| has_extension(path, ext))
+    let Some(ext) = full_extension(path, ExtensionOptions::default()) else {
+        return false;
+    };
+    let ext = case::fold(&(".".to_owned() + ext));
+    extensions.into_iter().any(|extension| ext.ends_with(&case::fold(&extension_arg(extension))))
+}
+
+fn escape_segment(segment: &str, escape_colon: bool) -> String {
+    match segment {
+        "" => "%00".to_owned(),
+        "." => "%2E".to_owned(),
+        ".." => "%2E%2E".to_owned(),
+        _ => segment.chars().fold(String::new(), |mut r, ch| {
+            match ch {
+                '%' => r.push_str("%25"),
+                '/' => r.push_str("%2F"),
+                '\\' => r.push_str("%5C"),
+                ':' if escape_colon => r.push_str("%3A"),
+                _ => r.push(ch),
+            }
+            r
+        }),
+    }
+}
+
+/// Indicates whether an unresolved path starts with exactly two separators.
+fn starts_with_double_root(path: &str) -> bool {
+    let mut chars = path.chars().map(|ch| ch == '/' || ch == '\\');
+    chars.next() == Some(true) && chars.next() == Some(true) && chars.next() != Some(true)
+}
+
+fn file_name_str(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or("")
+}
+
+fn is_hidden_name(name: &str) -> bool {
+    name.starts_with('.') && name != "." && name != ".."
+}
+
+/// Returns the part of the file name that may contain extensions,
+/// skipping leading dots unless they are considered extensions.
+fn extension_candidate(path: &str, options: ExtensionOptions) -> &str {
+    let name = file_name_str(path);
+    if options.leading_dot_is_extension { name } else { name.trim_start_matches('.') }
+}
+
+fn full_extension(path: &str, options: ExtensionOptions) -> Option<&str> {
+    regex_find!(r"(\.[^\.]+)+$", extension_candidate(path, options)).map(|ext| &ext[1..])
+}
+
+fn last_extension(path: &str, options: ExtensionOptions) -> Option<&str> {
+    regex_find!(r"\.[^\.]+$", extension_candidate(path, options)).map(|ext| &ext[1..])
+}
+
+fn stem_without_full_extension(path: &str) -> &str {
+    let name = file_name_str(path);
+    full_extension(path, ExtensionOptions::default()).map_or(name, |ext| &name[..name.len() - ext.len() - 1])
 }
 
 fn base_name(path: &str) -> String {
-    path.split('/').last().map_or("", |s| s).to_owned()
+    path.split('/').next_back().map_or("", |s| s).to_owned()
 }
 
 fn base_name_without_ext<'a, T>(path: &str, extensions: T) -> String
     where T: IntoIterator<Item = &'a str>
 {
     let extensions = extensions.into_iter().map(extension_arg).collect::<Vec<String>>();
-    path.split('/').last().map_or("".to_owned(), |base| {
-        regex_replace!(r"(\.[^\.]+)+$", base, |_, prev_ext: &str| {
-            (if extensions.iter().any(|ext| ext == prev_ext) { "" } else { prev_ext }).to_owned()
-        }).into_owned()
-    })
+    let base = file_name_str(path);
+    match full_extension(path, ExtensionOptions::default()) {
+        Some(prev_ext) if extensions.iter().any(|ext| ext[1..] == *prev_ext) => {
+            base[..base.len() - prev_ext.len() - 1].to_owned()
+        },
+        _ => base.to_owned(),
+    }
 }
 
 /// Normalizes a path by resolving relative components and performing some changes.
@@ -322,8 +2007,63 @@ fn base_name_without_ext<'a, T>(path: &str, extensions: T) -> String
 /// assert_eq!(PathBuf::from_str(r"\\?\C:").unwrap(), normalize_path(r"\\?\C:"));
 /// ```
 pub fn normalize_path(p: impl AsRef<Path>) -> PathBuf {
+    normalize_path_with(p, NormalizeOptions::default()).0
+}
+
+/// Normalizes a path like [`normalize_path`], also finding segments that
+/// end with dots or spaces, which Win32 APIs strip from paths that are not
+/// in the verbatim form, and stripping them according to `options`.
+///
+/// Only Windows paths are affected. Segments of a path already in the
+/// verbatim form are left unchanged and not reported, as are segments made
+/// only of dots and spaces. On other platforms, the report is always empty.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{normalize_path_with, FlexPathVariant, NormalizeOptions};
+/// let options = NormalizeOptions { strip_trailing_dots_and_spaces: true };
+/// if FlexPathVariant::native() == FlexPathVariant::Windows {
+///     let (normalized, report) = normalize_path_with(r"C:\tmp\a. \b..", options);
+///     assert!(normalized.ends_with(r"tmp\a\b"));
+///     assert_eq!(vec!["a. ", "b.."], report.trailing_segments.iter().map(|s| s.segment.as_str()).collect::<Vec<_>>());
+/// } else {
+///     let (normalized, report) = normalize_path_with("/tmp/a. /b..", options);
+///     assert!(normalized.ends_with("a. /b.."));
+///     assert!(report.trailing_segments.is_empty());
+/// }
+/// ```
+pub fn normalize_path_with(p: impl AsRef<Path>, options: NormalizeOptions) -> (PathBuf, NormalizeReport) {
     let cwd = std::env::current_dir().unwrap_or(PathBuf::from_str("/").unwrap());
-    let p = FlexPath::from_n_native([cwd.to_str().unwrap(), &p.as_ref().to_string_lossy().to_owned()]).to_string();
+    let mut path = FlexPath::from_n([cwd.to_str().unwrap(), &p.as_ref().to_string_lossy()], FlexPathVariant::NATIVE);
+    let report = trailing_dots_and_spaces(&mut path, options);
+    (normalize_text(path.to_string()), report)
+}
+
+fn trailing_dots_and_spaces(path: &mut FlexPath, options: NormalizeOptions) -> NormalizeReport {
+    let mut report = NormalizeReport::default();
+    if path.1 != FlexPathVariant::Windows || path.is_verbatim() {
+        return report;
+    }
+    let root_len = flexible::root_len(&path.0, path.1);
+    let mut r = path.0[..root_len].to_owned();
+    for (index, segment) in path.0[root_len..].split('/').enumerate() {
+        let stripped = segment.trim_end_matches(['.', ' ']);
+        if index != 0 {
+            r.push('/');
+        }
+        if stripped.len() == segment.len() || stripped.is_empty() {
+            r.push_str(segment);
+            continue;
+        }
+        report.trailing_segments.push(TrailingCharsSegment { index, segment: segment.to_owned() });
+        r.push_str(if options.strip_trailing_dots_and_spaces { stripped } else { segment });
+    }
+    *path = FlexPath::from_resolved(r, path.1);
+    report
+}
+
+fn normalize_text(p: String) -> PathBuf {
     let p = regex_replace!(r"[^\\/][\\/]+$", &p, |a: &str| {
         a.chars().collect::<Vec<_>>()[0].to_string()
     }).into_owned();
@@ -361,6 +2101,107 @@ mod test {
 
         assert_eq!("qux.html", FlexPath::new_common("foo/qux.html").base_name());
         assert_eq!("qux", FlexPath::new_common("foo/qux.html").base_name_without_ext([".html"]));
+
+        assert_eq!(Some("tar.gz"), FlexPath::new_common("a/b.tar.gz").full_extension());
+        assert_eq!(None, FlexPath::new_common("a.x/b").full_extension());
+        assert_eq!("b", FlexPath::new_common("a/b.tar.gz").stem_without_full_extension());
+        assert_eq!("b", FlexPath::new_common("a.x/b").stem_without_full_extension());
+        assert_eq!(0, FlexPath::new_common("a").extensions().count());
+        assert_eq!("a.sql.gz", FlexPath::new_common("a.sql").add_extension(".gz").to_string());
+        assert_eq!("a.x/b", FlexPath::new_common("a.x/b").without_extension().to_string());
+        assert_eq!("a.x/b", FlexPath::new_common("a.x/b.y.z").remove_extension().to_string());
+        for path in [r"\\srv\share.x", r"\\?\UNC\srv\share.x", r"C:\"] {
+            let path = FlexPath::new(path, FlexPathVariant::Windows);
+            assert_eq!(path, path.without_extension());
+            assert_eq!(path, path.remove_extension());
+        }
+        assert_eq!("a.z", FlexPath::new_common("a.x.y").change_last_extension("z").to_string());
+        assert_eq!(".bashrc.z", FlexPath::new_common(".bashrc.x.y").change_last_extension("z").to_string());
+    }
+
+    #[test]
+    fn dotfile_extensions() {
+        let legacy = ExtensionOptions { leading_dot_is_extension: true, ..Default::default() };
+        assert_eq!(None, FlexPath::new_common("a/.gitignore").full_extension());
+        assert_eq!(Some("gitignore"), FlexPath::new_common("a/.gitignore").full_extension_with(legacy));
+        assert_eq!(Some("bak"), FlexPath::new_common(".bashrc.bak").full_extension());
+        assert_eq!("a/.gitignore.x", FlexPath::new_common("a/.gitignore").change_extension(".x").to_string());
+        assert_eq!("a/.x", FlexPath::new_common("a/.gitignore").change_extension_with(".x", legacy).to_string());
+        assert!(!FlexPath::new_common(".gitignore").has_extension("gitignore"));
+        assert!(FlexPath::new_common(".gitignore").has_extension_with("gitignore", legacy));
+        assert_eq!(".gitignore", FlexPath::new_common(".gitignore").base_name_without_ext(["gitignore"]));
+    }
+
+    #[test]
+    fn extension_case() {
+        let case_sensitive = ExtensionOptions { case_sensitive: true, ..Default::default() };
+        assert!(FlexPath::new_common("Makefile.C").has_extension(".c"));
+        assert!(!FlexPath::new_common("Makefile.C").has_extension_with(".c", case_sensitive));
+        assert!(FlexPath::new_common("Makefile.C").has_extension_with(".C", case_sensitive));
+        assert!(FlexPath::new_common("a.STRASSE").has_extension("straße"));
+        assert!(FlexPath::new("a.ẞ", FlexPathVariant::Windows).has_extension_with("ß", ExtensionOptions::default()));
+        assert!(FlexPath::new("a.ẞ", FlexPathVariant::Windows).has_extension_with("ss", ExtensionOptions::default()));
+        assert_eq!(("日", Some("\u{212A}")), FlexPath::new_common("日.k").split_known_extension(["\u{212A}"]));
+        assert_eq!(("a", Some("\u{212A}")), FlexPath::new_common("a.k").split_known_extension(["\u{212A}"]));
+        assert_eq!(("a.tar", Some(".K")), FlexPath::new_common("a.tar.\u{212A}").split_known_extension([".K"]));
+        assert_eq!(("a", Some("straße")), FlexPath::new_common("a.STRASSE").split_known_extension(["straße"]));
+        assert_eq!(("", None), FlexPath::new(r"\\srv\share.x", FlexPathVariant::Windows).split_known_extension(["x"]));
+        assert_eq!(("", None), FlexPath::new(r"\\?\UNC\srv\share.x", FlexPathVariant::Windows).split_known_extension(["x"]));
+    }
+
+    #[test]
+    fn file_name_split() {
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(("C:/", "a", Some("txt")), FlexPath::new(r"C:\a.txt", windows).split_file_name());
+        assert_eq!(("C:/x", "a", None), FlexPath::new(r"C:\x\a", windows).split_file_name());
+        assert_eq!((r"\\srv/share/", "a", None), FlexPath::new(r"\\srv\share\a", windows).split_file_name());
+        assert_eq!((r"\\srv/share", "", None), FlexPath::new(r"\\srv\share", windows).split_file_name());
+        assert_eq!((r"\\?\UNC/srv/share.x", "", None), FlexPath::new(r"\\?\UNC\srv\share.x", windows).split_file_name());
+        assert_eq!(("C:/", "", None), FlexPath::new(r"C:\", windows).split_file_name());
+        assert_eq!(("a/b", ".env", Some("local")), FlexPath::new_common("a/b/.env.local").split_file_name());
+    }
+
+    #[test]
+    fn dedup_suffix() {
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(r"C:\a.x\b (3)", FlexPath::new("C:/a.x/b", windows).with_dedup_suffix(3, DedupPattern::Parenthesized).to_string());
+        assert_eq!(".bashrc-1", FlexPath::new_common(".bashrc").with_dedup_suffix(1, DedupPattern::Hyphenated).to_string());
+        assert_eq!("a", FlexPath::new_common("a").find_free_name(DedupPattern::Hyphenated, |_| false).unwrap().to_string());
+        for path in [FlexPath::new_common(""), FlexPath::new_common("/"), FlexPath::new(r"C:\", windows), FlexPath::new(r"\\srv\share", windows)] {
+            assert_eq!(None, path.find_free_name(DedupPattern::Hyphenated, |_| true), "{path}");
+            assert_eq!(Some(path.clone()), path.find_free_name(DedupPattern::Hyphenated, |_| false));
+        }
+    }
+
+    #[test]
+    fn sort_keys() {
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(FlexPath::new(r"c:\a", windows).sort_key(), FlexPath::new(r"C:\a", windows).sort_key());
+        assert!(FlexPath::new_common("/a/b").sort_key() < FlexPath::new_common("/a.b").sort_key());
+    }
+
+    #[test]
+    fn hidden() {
+        assert!(FlexPath::new(r"C:\a\.cache", FlexPathVariant::Windows).is_hidden());
+        assert!(!FlexPath::new_common("/").is_hidden());
+        assert!(!FlexPath::new_common("a/b").has_hidden_segment());
+        let options = ResolveOptions { preserve_current_dir: true, ..Default::default() };
+        let dot = FlexPath::new_with(".", FlexPathVariant::Common, options);
+        assert!(!dot.is_hidden());
+        assert!(!dot.has_hidden_segment());
+        assert!(dot.resolve(".env").is_hidden());
+    }
+
+    #[test]
+    fn file_name_truncation() {
+        assert_eq!("a/\u{e9}.txt", FlexPath::new_common("a/\u{e9}\u{e9}.txt").truncate_file_name(7).to_string());
+        assert_eq!("a/abc", FlexPath::new_common("a/abcdef").truncate_file_name(3).to_string());
+        assert_eq!("a/b.t", FlexPath::new_common("a/b.tar.gz").truncate_file_name(3).to_string());
+        assert_eq!("a/b.c", FlexPath::new_common("a/b.c").truncate_file_name(255).to_string());
+        assert_eq!("a/\u{65e5}\u{672c}", FlexPath::new_common("a/\u{65e5}\u{672c}.txt").truncate_file_name(6).to_string());
+        assert_eq!("a/b.txt", FlexPath::new_common("a/bc.txt").truncate_file_name(5).to_string());
+        #[cfg(feature = "unicode")]
+        assert_eq!("x.txt", FlexPath::new_common("x\u{1F44D}\u{1F3FD}.txt").truncate_file_name_graphemes(10).to_string());
     }
 
     #[test]
@@ -400,16 +2241,234 @@ mod test {
         assert_eq!("", FlexPath::new("C:/", windows).relative("C:/"));
         assert_eq!("", FlexPath::new("C:/foo", windows).relative("C:/foo"));
         assert_eq!(r"\\foo", FlexPath::new("C:/", windows).relative(r"\\foo"));
-        assert_eq!("../../foo", FlexPath::new(r"\\a/b", windows).relative(r"\\foo"));
+        assert_eq!(r"\\foo", FlexPath::new(r"\\a/b", windows).relative(r"\\foo"));
+        assert_eq!(r"\\srv/other/b", FlexPath::new(r"\\srv\share\a", windows).relative(r"\\srv\other\b"));
+        assert_eq!("../b", FlexPath::new(r"\\srv\share\a", windows).relative(r"//srv/share/b"));
+        assert_eq!("b", FlexPath::new(r"\\srv\share", windows).relative(r"\\srv\share\b"));
+        assert_eq!(r"\\?\UNC/srv/b", FlexPath::new(r"\\?\UNC\srv\a", windows).relative(r"\\?\UNC\srv\b"));
+        assert_eq!("../far", FlexPath::new(r"\foo", windows).relative(r"\far"));
         assert_eq!("D:/", FlexPath::new("C:/", windows).relative(r"D:"));
         assert_eq!("../bar", FlexPath::new(r"\\?\C:\foo", windows).relative(r"\\?\C:\bar"));
+        assert_eq!("../bar", FlexPath::new(r"c:\foo", windows).relative(r"C:\bar"));
+    }
+
+    #[test]
+    fn intermediate_dirs() {
+        let windows = FlexPathVariant::Windows;
+        let file = FlexPath::new(r"C:\a\b\c", windows);
+        assert_eq!(vec![FlexPath::new("C:/a", windows), FlexPath::new("C:/a/b", windows)], file.iter_dirs(&FlexPath::new("C:", windows)).collect::<Vec<_>>());
+        assert_eq!(0, file.iter_dirs(&FlexPath::new("C:/a/b", windows)).count());
+        assert_eq!(0, file.iter_dirs(&FlexPath::new("C:/ab", windows)).count());
+        assert_eq!(0, file.iter_dirs(&file).count());
+        assert_eq!(vec![FlexPath::new_common("a")], FlexPath::new_common("a/b").iter_dirs(&FlexPath::new_common("")).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn forward_slash_verbatim() {
+        let windows = FlexPathVariant::Windows;
+        for path in ["//?/C:/foo", r"\\?/C:\foo", r"//?\C:\foo\", r"\\?\C:\bar\..\foo"] {
+            let path = FlexPath::new(path, windows);
+            assert!(path.is_verbatim());
+            assert_eq!(r"\\?\C:\foo", path.to_string());
+            assert_eq!(path, FlexPath::new(&path.to_string(), windows));
+        }
+        let unc = FlexPath::new("//?/UNC/srv/share/a", windows);
+        assert_eq!(r"\\?\UNC\srv\share\a", unc.to_string());
+        assert_eq!(Some("a"), unc.file_name());
+        assert!(!FlexPath::new("//srv/share", windows).is_verbatim());
+    }
+
+    #[test]
+    fn double_root() {
+        let options = ResolveOptions { preserve_double_root: true, ..Default::default() };
+        let path = FlexPath::new_with(r"\\host\a", FlexPathVariant::Common, options);
+        assert_eq!("//host/a", path.to_string());
+        assert_eq!("//host", path.resolve("..").to_string());
+        assert_eq!("//", path.resolve("../../..").to_string());
+        assert_eq!("/x", path.resolve("/x").to_string());
+        assert_eq!("//host/a/b/c", path.resolve_n(["b", "c"]).to_string());
+        assert_eq!(path, FlexPath::new_common("/x").resolve_path(&path));
+        assert_eq!("//host/a/b", path.resolve_path(&FlexPath::new_common("b")).to_string());
+        let mut out = String::new();
+        path.resolve_into("b", &mut out);
+        assert_eq!("//host/a/b", out);
+        assert!(path.is_absolute());
+        assert_ne!(FlexPath::new_common("/host/a"), path);
+        assert_eq!(vec!["//", "host", "a"], trie::keys(&path));
+
+        let options = ResolveOptions { preserve_current_dir: true, ..Default::default() };
+        let dot = FlexPath::new_with(r".\", FlexPathVariant::Windows, options);
+        assert_eq!(".", dot.to_string());
+        assert_eq!(r"a\b", dot.resolve(r".\a\b").to_string());
+        assert!(dot.is_cwd_relative());
+        assert_eq!("", FlexPath::new_with("", FlexPathVariant::Common, options).to_string());
+        assert_eq!("/", FlexPath::new_with("/.", FlexPathVariant::Common, options).to_string());
+    }
+
+    #[test]
+    fn forward_slash_unc() {
+        let windows = FlexPathVariant::Windows;
+        for path in ["//srv/share/x", r"\/srv\share\x\", r"/\srv//share/a/../x"] {
+            let path = FlexPath::new(path, windows);
+            assert!(path.is_unc());
+            assert_eq!(FlexPath::new(r"\\srv\share\x", windows), path);
+        }
+        assert_eq!(FlexPath::new(r"\\srv\share\a", windows), FlexPath::new("//srv/share", windows).resolve("a"));
+        assert!(FlexPath::new(r"\\srv", windows).is_unc());
+        assert!(!FlexPath::new(r"\srv", windows).is_unc());
+        assert!(!FlexPath::new(r"\\.\pipe\x", windows).is_unc());
+        assert_eq!(FlexPath::new_common("/srv/share"), FlexPath::new_common("//srv/share"));
+    }
+
+    #[test]
+    fn short_names() {
+        let windows = FlexPathVariant::Windows;
+        for name in ["PROGRA~1", "LONGNA~2.TXT", "a~123456", "docume~1/x"] {
+            assert!(FlexPath::new(name, windows).looks_like_short_name(), "{name}");
+        }
+        for name in ["PROGRAM~1", "a~0", "~1", "file.txt~1", "a~1.html", "name~"] {
+            assert!(!FlexPath::new(name, windows).looks_like_short_name(), "{name}");
+        }
+    }
+
+    #[test]
+    fn resolve_into_buffer() {
+        let mut out = String::from("prefix:");
+        FlexPath::new_common("/a/b").resolve_into("../../../c//d/.", &mut out);
+        assert_eq!("prefix:/c/d", out);
+        for variant in [FlexPathVariant::Common, FlexPathVariant::Windows] {
+            for (base, path) in [("a/b", ".."), ("", "a/./b"), ("/", ".."), ("C:/x", r"..\y"), (r"\\srv\share", "z")] {
+                let base = FlexPath::new(base, variant);
+                let mut out = String::new();
+                base.resolve_into(path, &mut out);
+                assert_eq!(base.resolve(path).to_string(), out);
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_texts() {
+        let windows = FlexPathVariant::Windows;
+        for path in [r"\\?\c:\a", r"//?/C:/a", r"\\?\UNC\srv\share\x", r"\\srv\share\x", r"c:", r"\a", "a/b"] {
+            let path = FlexPath::new(path, windows);
+            let text = path.canonical_text();
+            assert!(!text.contains('\\'), "{text}");
+            assert_eq!(text, FlexPath::new(&text, windows).canonical_text());
+            assert_eq!(path.normalize_drive_letter().to_string(), FlexPath::new(&text, windows).to_string());
+            assert_eq!(path.normal_key(), FlexPath::new(&text.to_uppercase(), windows).normal_key());
+        }
+    }
+
+    #[test]
+    fn drive_candidates() {
+        let windows = FlexPathVariant::Windows;
+        let path = FlexPath::new("a/b", windows);
+        assert_eq!(vec![FlexPath::new("Z:/a/b", windows)], path.with_each_drive(['z']).unwrap());
+        assert!(path.with_each_drive([]).unwrap().is_empty());
+        assert!(matches!(path.with_each_drive(['1']), Err(FlexPathError::InvalidRoot { .. })));
+        assert!(matches!(FlexPath::new(r"\\srv\share\a", windows).with_each_drive(['C']), Err(FlexPathError::AbsolutePath { .. })));
+        assert!(matches!(FlexPath::new_common("a").with_each_drive(['C']), Err(FlexPathError::VariantMismatch { .. })));
+    }
+
+    #[test]
+    fn replaced_roots() {
+        let windows = FlexPathVariant::Windows;
+        let path = FlexPath::new(r"C:\a\b", windows);
+        assert_eq!(FlexPath::new(r"\\srv\share\a\b", windows), path.with_root(r"\\srv\share\").unwrap());
+        assert_eq!(FlexPath::new(r"\\?\D:\a\b", windows), path.with_root(r"\\?\D:\").unwrap());
+        assert_eq!(FlexPath::new(r"C:\x", windows), FlexPath::new(r"\x", windows).with_root("C:").unwrap());
+        assert_eq!(FlexPath::new(r"\a", windows), FlexPath::new(r"a", windows).with_root("/").unwrap());
+        assert!(path.with_root("").is_err());
+        assert_eq!(FlexPath::new_common("/a"), FlexPath::new_common("a").with_root("/").unwrap());
+        assert!(FlexPath::new_common("/a").with_root("C:").is_err());
+    }
+
+    #[test]
+    fn prefix_case() {
+        let windows = FlexPathVariant::Windows;
+        let normalize = |p: &str| FlexPath::new(p, windows).normalize_prefix_case().to_string();
+        assert_eq!(r"\\?\C:\Foo", normalize(r"\\?\c:\Foo"));
+        assert_eq!(r"\\srv\Share", normalize(r"//SRV/Share"));
+        assert_eq!(r"\\srv", normalize(r"\\Srv"));
+        assert_eq!(r"\Foo", normalize(r"\Foo"));
+        assert_eq!(r"a\B", normalize(r"a\B"));
+        assert_eq!("c:/A", FlexPath::new_common("c:/A").normalize_prefix_case().to_string());
+    }
+
+    #[test]
+    fn ascend_descend() {
+        let windows = FlexPathVariant::Windows;
+        let path = FlexPath::new(r"\\srv\share\a", windows);
+        assert_eq!((FlexPath::new(r"\\srv\share", windows), 1), path.ascend(3, AscendPolicy::Clamp).unwrap());
+        assert_eq!((FlexPath::new("", windows), 2), FlexPath::new("a/b", windows).ascend(2, AscendPolicy::Reject).unwrap());
+        assert_eq!(Err(FlexPathError::AboveRoot { path: "a".to_owned(), levels: 2 }), FlexPath::new_common("a").ascend(2, AscendPolicy::Reject));
+        assert_eq!((path.clone(), 0), path.ascend(0, AscendPolicy::Reject).unwrap());
+
+        let empty = FlexPath::new("", windows);
+        assert!(matches!(empty.descend(["C:x"]), Err(FlexPathError::AbsolutePath { .. })));
+        assert_eq!(FlexPath::new(r"a\C:b", windows), empty.descend([r"a\C:b\"]).unwrap());
+        assert_eq!(path, path.descend(["", "."]).unwrap());
+    }
+
+    #[test]
+    fn literal_segments() {
+        let windows = FlexPathVariant::Windows;
+        let mut path = FlexPath::new("", windows);
+        assert!(matches!(path.push_segment("C:", SegmentPolicy::Reject), Err(FlexPathError::InvalidSegment { .. })));
+        path.push_segment("C:", SegmentPolicy::Escape).unwrap();
+        path.push_segment(r"a\b", SegmentPolicy::Escape).unwrap();
+        path.push_segment("..", SegmentPolicy::Escape).unwrap();
+        path.push_segment("50%", SegmentPolicy::Escape).unwrap();
+        path.push_segment("x:y", SegmentPolicy::Reject).unwrap();
+        assert_eq!(r"C%3A\a%5Cb\%2E%2E\50%25\x:y", path.to_string());
+        assert!(!path.is_absolute());
+        assert!(FlexPath::new_common("a").push_segment("", SegmentPolicy::Reject).is_err());
+    }
+
+    #[test]
+    fn special_characters() {
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(FlexPath::new("C:/x/a/?/b", windows), FlexPath::from_n(["C:/x", r"a\?\b"], windows));
+        assert_eq!(r"C:\x\100%25\b", FlexPath::from_n(["C:/x", "100%25/b"], windows).to_string());
+        assert_eq!(FlexPath::new("C:/x/a:b/c?d*", windows), FlexPath::new(r"C:\x\a:b\c?d*", windows));
+        assert!(!FlexPath::new("a/?/b", windows).is_absolute());
+        assert!(FlexPath::new("a/?/b", windows).contains_wildcard());
+        assert_eq!(FlexPath::new("//?/C:/", windows), FlexPath::new("//?/C:/a/../..", windows));
+        assert_eq!(FlexPath::new(r"\\?\C:\", windows), FlexPath::new(r"\\?\C:\a\..\..", windows));
+        assert_eq!("a?b", FlexPath::new("//?/C:/a?b", windows).file_name().unwrap());
+    }
+
+    #[test]
+    fn trailing_dots_and_spaces_report() {
+        let windows = FlexPathVariant::Windows;
+        let mut path = FlexPath::new(r"C:\a.\b\c \...", windows);
+        let report = trailing_dots_and_spaces(&mut path, NormalizeOptions::default());
+        assert_eq!(FlexPath::new(r"C:\a.\b\c \...", windows), path);
+        assert_eq!(vec![(0, "a."), (2, "c ")], report.trailing_segments.iter().map(|s| (s.index, s.segment.as_str())).collect::<Vec<_>>());
+        trailing_dots_and_spaces(&mut path, NormalizeOptions { strip_trailing_dots_and_spaces: true });
+        assert_eq!(r"C:\a\b\c\...", path.to_string());
+
+        let mut verbatim = FlexPath::new(r"\\?\C:\a.", windows);
+        assert!(trailing_dots_and_spaces(&mut verbatim, NormalizeOptions { strip_trailing_dots_and_spaces: true }).trailing_segments.is_empty());
+        assert_eq!(r"\\?\C:\a.", verbatim.to_string());
+
+        let mut common = FlexPath::new_common("/a./b ");
+        assert!(trailing_dots_and_spaces(&mut common, NormalizeOptions { strip_trailing_dots_and_spaces: true }).trailing_segments.is_empty());
+        assert_eq!("/a./b ", common.to_string());
     }
 
     #[test]
     fn normalization() {
-        assert_eq!(PathBuf::from_str(r"\\?\C:\program files").unwrap(), normalize_path(r"C:/Program Files/"));
-        assert_eq!(PathBuf::from_str(r"\\?\UNC\server\foo").unwrap(), normalize_path(r"\\server\foo\"));
-        assert_eq!(PathBuf::from_str(r"\\?\C:\foo").unwrap(), normalize_path(r"\\?\c:/foo/"));
-        assert_eq!(PathBuf::from_str(r"\\?\UNC\server\foo").unwrap(), normalize_path(r"\\?\unc\server\Foo\"));
+        // normalize_path() resolves with the native variant, so Windows
+        // paths are only normalized as such on Windows.
+        if FlexPathVariant::native() == FlexPathVariant::Windows {
+            assert_eq!(PathBuf::from_str(r"\\?\C:\program files").unwrap(), normalize_path(r"C:/Program Files/"));
+            assert_eq!(PathBuf::from_str(r"\\?\UNC\server\foo").unwrap(), normalize_path(r"\\server\foo\"));
+            assert_eq!(PathBuf::from_str(r"\\?\C:\foo").unwrap(), normalize_path(r"\\?\c:/foo/"));
+            assert_eq!(PathBuf::from_str(r"\\?\UNC\server\foo").unwrap(), normalize_path(r"\\?\unc\server\Foo\"));
+        } else {
+            assert_eq!(PathBuf::from_str("/foo/Bar").unwrap(), normalize_path("/foo/Bar/"));
+            assert_eq!(PathBuf::from_str("/foo/Bar").unwrap(), normalize_path("/foo/./baz/../Bar"));
+        }
     }
 }
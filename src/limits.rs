@@ -0,0 +1,233 @@
+/*!
+This module contains the `PathLimits` validator and the `InputLimits`
+guarding resolution of untrusted input.
+*/
+
+use super::{common, flexible, FlexPath, FlexPathError, FlexPathVariant};
+
+/// Configurable limits on the length of a path and of its segments,
+/// such as those of a file system or an archive format, checked with
+/// [`FlexPath::check_limits`].
+///
+/// The root, such as `/` or `C:\`, is not a segment, but counts
+/// towards the total length.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct PathLimits {
+    /// The maximum length of a segment in UTF-8 bytes. This is 255 by
+    /// default, the limit of most Unix file systems.
+    pub max_segment_bytes: Option<usize>,
+    /// The maximum length of a segment in characters, that is, Unicode
+    /// scalar values. NTFS instead limits names to 255 UTF-16 units, in
+    /// which a character outside the Basic Multilingual Plane counts
+    /// twice. This is unlimited by default.
+    pub max_segment_chars: Option<usize>,
+    /// The maximum length of the whole path in UTF-8 bytes. This is
+    /// unlimited by default.
+    pub max_total_bytes: Option<usize>,
+    /// The maximum number of segments. This is unlimited by default.
+    pub max_segments: Option<usize>,
+}
+
+impl Default for PathLimits {
+    fn default() -> Self {
+        Self { max_segment_bytes: Some(255), max_segment_chars: None, max_total_bytes: None, max_segments: None }
+    }
+}
+
+/// A limit exceeded by a path, reported by [`FlexPath::check_limits`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum LimitViolation {
+    /// A segment is longer than [`PathLimits::max_segment_bytes`].
+    SegmentBytes {
+        /// The index of the segment, counted from zero after the root.
+        index: usize,
+        /// The segment.
+        segment: String,
+        /// The length of the segment in bytes.
+        len: usize,
+    },
+    /// A segment is longer than [`PathLimits::max_segment_chars`].
+    SegmentChars {
+        /// The index of the segment, counted from zero after the root.
+        index: usize,
+        /// The segment.
+        segment: String,
+        /// The length of the segment in characters.
+        len: usize,
+    },
+    /// The path is longer than [`PathLimits::max_total_bytes`].
+    TotalBytes {
+        /// The length of the path in bytes.
+        len: usize,
+    },
+    /// The path has more segments than [`PathLimits::max_segments`].
+    Segments {
+        /// The number of segments.
+        count: usize,
+    },
+}
+
+/// Limits on the size of untrusted input to resolution, checked by
+/// [`FlexPath::new_limited`] and [`FlexPath::from_n_limited`] before any
+/// work is done. Resolution takes time linear in the input, so these
+/// bound the time and memory a single input may use.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct InputLimits {
+    /// The maximum total length of the input in UTF-8 bytes. This is
+    /// 32,767 by default, the longest path the Windows operating system
+    /// accepts.
+    pub max_bytes: Option<usize>,
+    /// The maximum total number of segments in the input, counting each
+    /// separator, including repeated ones, as starting a segment. This is
+    /// 4,096 by default.
+    pub max_segments: Option<usize>,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self { max_bytes: Some(32_767), max_segments: Some(4_096) }
+    }
+}
+
+impl InputLimits {
+    /// Limits that accept any input.
+    pub const UNLIMITED: InputLimits = InputLimits { max_bytes: None, max_segments: None };
+
+    fn check<S: AsRef<str>>(&self, paths: &[S]) -> Result<(), FlexPathError> {
+        let len = paths.iter().map(|path| path.as_ref().len()).sum();
+        if let Some(max) = self.max_bytes.filter(|&max| len > max) {
+            return Err(FlexPathError::InputTooLong { len, max });
+        }
+        if let Some(max) = self.max_segments {
+            let count = paths.iter().map(|path| common::split_separators(path.as_ref()).count()).sum();
+            if count > max {
+                return Err(FlexPathError::TooManySegments { count, max });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FlexPath {
+    /// Resolves untrusted input like [`FlexPath::new`], refusing input
+    /// that exceeds `limits`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InputTooLong`] or
+    /// [`FlexPathError::TooManySegments`] if the input exceeds `limits`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathError, FlexPathVariant, InputLimits};
+    /// let limits = InputLimits::default();
+    /// assert_eq!("a/c", FlexPath::new_limited("a/b/../c", FlexPathVariant::Common, &limits).unwrap().to_string());
+    /// let hostile = "../".repeat(100_000);
+    /// assert!(matches!(FlexPath::new_limited(&hostile, FlexPathVariant::Common, &limits), Err(FlexPathError::InputTooLong { .. })));
+    /// ```
+    pub fn new_limited(path: &str, variant: FlexPathVariant, limits: &InputLimits) -> Result<FlexPath, FlexPathError> {
+        limits.check(&[path])?;
+        Ok(FlexPath::new(path, variant))
+    }
+
+    /// Resolves multiple untrusted inputs like [`FlexPath::from_n`],
+    /// refusing them if their total size exceeds `limits`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InputTooLong`] or
+    /// [`FlexPathError::TooManySegments`] if the inputs exceed `limits`.
+    pub fn from_n_limited<T: IntoIterator<Item = S>, S: AsRef<str>>(paths: T, variant: FlexPathVariant, limits: &InputLimits) -> Result<FlexPath, FlexPathError> {
+        let paths = paths.into_iter().collect::<Vec<S>>();
+        limits.check(&paths)?;
+        Ok(FlexPath::from_n(paths, variant))
+    }
+
+    /// Checks the path against `limits`, returning every exceeded limit
+    /// at once, segments in order, rather than only the first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, LimitViolation, PathLimits};
+    /// let limits = PathLimits { max_segment_bytes: Some(4), max_segments: Some(2), ..Default::default() };
+    /// let violations = FlexPath::new_common("/abcde/b/c").check_limits(&limits).unwrap_err();
+    /// assert_eq!(vec![
+    ///     LimitViolation::SegmentBytes { index: 0, segment: "abcde".into(), len: 5 },
+    ///     LimitViolation::Segments { count: 3 },
+    /// ], violations);
+    /// assert!(FlexPath::new_common("a/b").check_limits(&limits).is_ok());
+    /// ```
+    pub fn check_limits(&self, limits: &PathLimits) -> Result<(), Vec<LimitViolation>> {
+        let mut r = Vec::new();
+        let rest = &self.0[flexible::root_len(&self.0, self.1)..];
+        let segments: Vec<&str> = if rest.is_empty() { Vec::new() } else { rest.split('/').collect() };
+        for (index, segment) in segments.iter().enumerate() {
+            if limits.max_segment_bytes.is_some_and(|max| segment.len() > max) {
+                r.push(LimitViolation::SegmentBytes { index, segment: (*segment).to_owned(), len: segment.len() });
+            }
+            let chars = segment.chars().count();
+            if limits.max_segment_chars.is_some_and(|max| chars > max) {
+                r.push(LimitViolation::SegmentChars { index, segment: (*segment).to_owned(), len: chars });
+            }
+        }
+        if limits.max_total_bytes.is_some_and(|max| self.0.len() > max) {
+            r.push(LimitViolation::TotalBytes { len: self.0.len() });
+        }
+        if limits.max_segments.is_some_and(|max| segments.len() > max) {
+            r.push(LimitViolation::Segments { count: segments.len() });
+        }
+        if r.is_empty() { Ok(()) } else { Err(r) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn multibyte_and_roots() {
+        let limits = PathLimits { max_segment_bytes: Some(3), max_segment_chars: Some(2), max_total_bytes: Some(10), max_segments: None };
+        let violations = FlexPath::new(r"\\srv\share\éé\abc", FlexPathVariant::Windows).check_limits(&limits).unwrap_err();
+        assert_eq!(vec![
+            LimitViolation::SegmentBytes { index: 0, segment: "éé".to_owned(), len: 4 },
+            LimitViolation::SegmentChars { index: 1, segment: "abc".to_owned(), len: 3 },
+            LimitViolation::TotalBytes { len: 20 },
+        ], violations);
+        assert!(FlexPath::new_common("/").check_limits(&PathLimits { max_segments: Some(0), ..Default::default() }).is_ok());
+    }
+
+    #[test]
+    fn pathological_inputs() {
+        let limits = InputLimits { max_bytes: None, max_segments: Some(3) };
+        assert!(FlexPath::new_limited("a//b", FlexPathVariant::Common, &limits).is_ok());
+        assert_eq!(Err(FlexPathError::TooManySegments { count: 4, max: 3 }), FlexPath::from_n_limited(["a/b", "c", "d"], FlexPathVariant::Common, &limits));
+        assert_eq!(Err(FlexPathError::InputTooLong { len: 4, max: 3 }), FlexPath::from_n_limited(["ab", "cd"], FlexPathVariant::Common, &InputLimits { max_bytes: Some(3), ..InputLimits::UNLIMITED }));
+
+        // resolving many paths takes linear time, where folding them took quadratic time.
+        let parts = vec!["a/b"; 200_000];
+        assert_eq!(400_000, FlexPath::from_n_limited(&parts, FlexPathVariant::Windows, &InputLimits::UNLIMITED).unwrap().segments().count());
+        let parts: Vec<&str> = std::iter::repeat_n(["a", ".."], 100_000).flatten().collect();
+        assert_eq!("C:/", FlexPath::from_n(std::iter::once("C:").chain(parts), FlexPathVariant::Windows).0.as_str());
+
+        // the linear resolution agrees with folding pairwise resolution.
+        let inputs = ["a", "..", "/b", r"\\srv\share", "C:x", r"\\?\c:\y", "//?/UNC/h/s", ".", "", "d:", r"\z", "../..", r"\\?\GLOBALROOT"];
+        for variant in [FlexPathVariant::Common, FlexPathVariant::Windows] {
+            for i in 0..inputs.len() {
+                for j in 0..inputs.len() {
+                    for k in 0..inputs.len() {
+                        let paths = [inputs[i], inputs[j], inputs[k]];
+                        let folded = flexible::resolve(&flexible::resolve(paths[0], paths[1], variant), paths[2], variant);
+                        assert_eq!(folded, flexible::resolve_n(paths, variant), "{paths:?} {variant:?}");
+                        let mut into = "x".to_owned();
+                        flexible::resolve_n_into(paths, variant, &mut into);
+                        assert_eq!(folded, into[1..], "{paths:?} {variant:?}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+/*!
+This module contains the reading and writing of delimited path lists,
+such as the output of `git ls-files -z` and `find -print0`, the
+contents of response files and `PATH`-style environment variables.
+*/
+
+use super::{FlexPath, FlexPathError, FlexPathVariant};
+use std::borrow::Borrow;
+use std::io;
+
+/// Indicates how the entries of a path list are delimited.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub enum ListDelimiter {
+    /// Each entry ends with a line feed, optionally preceded by a
+    /// carriage return. Paths containing line breaks cannot be listed.
+    #[default]
+    Newline,
+    /// Each entry ends with a zero byte, as with `find -print0`, so that
+    /// any path can be listed.
+    Nul,
+}
+
+impl ListDelimiter {
+    fn as_char(self) -> char {
+        match self {
+            Self::Newline => '\n',
+            Self::Nul => '\0',
+        }
+    }
+}
+
+/// Parses a path list, resolving each entry as a path of `variant`.
+///
+/// Empty entries are skipped, and the last entry need not be terminated.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{parse_path_list, FlexPath, FlexPathVariant, ListDelimiter};
+/// let paths = parse_path_list("src/a.rs\0line\nbreak.txt\0", ListDelimiter::Nul, FlexPathVariant::Common);
+/// assert_eq!(vec![FlexPath::new_common("src/a.rs"), FlexPath::new_common("line\nbreak.txt")], paths);
+/// ```
+pub fn parse_path_list(text: &str, delimiter: ListDelimiter, variant: FlexPathVariant) -> Vec<FlexPath> {
+    text.split(delimiter.as_char())
+        .map(|entry| if delimiter == ListDelimiter::Newline { entry.strip_suffix('\r').unwrap_or(entry) } else { entry })
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| FlexPath::new(entry, variant))
+        .collect()
+}
+
+/// Formats a path list, terminating each path, formatted with the
+/// separators of its variant, with the delimiter.
+///
+/// # Errors
+///
+/// Returns [`FlexPathError::InvalidListEntry`] if a path contains the
+/// delimiter or, for [`ListDelimiter::Newline`], a carriage return.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{format_path_list, FlexPath, ListDelimiter};
+/// let paths = ["a/b", "c"].map(FlexPath::new_common);
+/// assert_eq!("a/b\nc\n", format_path_list(&paths, ListDelimiter::Newline).unwrap());
+/// assert!(format_path_list([FlexPath::new_common("a\nb")], ListDelimiter::Newline).is_err());
+/// ```
+pub fn format_path_list<I, P>(paths: I, delimiter: ListDelimiter) -> Result<String, FlexPathError>
+where
+    I: IntoIterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    let mut r = String::new();
+    for path in paths {
+        let path = path.borrow().to_string();
+        let reason = match delimiter {
+            ListDelimiter::Newline if path.contains(['\n', '\r']) => Some("the path contains a line break"),
+            ListDelimiter::Nul if path.contains('\0') => Some("the path contains a zero byte"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            return Err(FlexPathError::InvalidListEntry { path, reason });
+        }
+        r.push_str(&path);
+        r.push(delimiter.as_char());
+    }
+    Ok(r)
+}
+
+/// Reads a path list from `reader` as by [`parse_path_list`].
+///
+/// # Errors
+///
+/// Returns an error if reading fails or, with kind `InvalidData`, if
+/// the list is not valid UTF-8.
+pub fn read_path_list<R: io::Read>(mut reader: R, delimiter: ListDelimiter, variant: FlexPathVariant) -> io::Result<Vec<FlexPath>> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    Ok(parse_path_list(&text, delimiter, variant))
+}
+
+/// Writes a path list to `writer` as by [`format_path_list`].
+///
+/// # Errors
+///
+/// Returns an error if writing fails or, with kind `InvalidInput`, if
+/// a path cannot be listed, in which case nothing is written.
+pub fn write_path_list<W, I, P>(mut writer: W, paths: I, delimiter: ListDelimiter) -> io::Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    let text = format_path_list(paths, delimiter).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    writer.write_all(text.as_bytes())
+}
+
+/// Returns the separator of `PATH`-style lists for `variant`: a
+/// semicolon (`;`) for `Windows` and a colon (`:`) otherwise.
+pub const fn path_list_separator(variant: FlexPathVariant) -> char {
+    match variant {
+        FlexPathVariant::Common => ':',
+        FlexPathVariant::Windows => ';',
+    }
+}
+
+/// Splits a `PATH`-style list, such as the value of `PATH` or `PYTHONPATH`,
+/// into paths of `variant`, separated as by [`path_list_separator`].
+///
+/// For `Windows`, double quotes (`"`) are removed, and separators between
+/// them are part of the path, as in `"C:\a;b"`; empty entries are
+/// skipped. For `Common`, an empty entry refers to the current
+/// directory and is kept as an empty path, as POSIX specifies.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{split_path_list, FlexPath, FlexPathVariant};
+/// let windows = FlexPathVariant::Windows;
+/// let paths = split_path_list(r#"C:\bin;;"D:\a;b";\\srv\tools"#, windows);
+/// assert_eq!(vec![r"C:\bin", r"D:\a;b", r"\\srv\tools"], paths.iter().map(|p| p.to_string()).collect::<Vec<_>>());
+/// assert_eq!(3, split_path_list("/bin::/usr/bin", FlexPathVariant::Common).len());
+/// ```
+pub fn split_path_list(list: &str, variant: FlexPathVariant) -> Vec<FlexPath> {
+    match variant {
+        FlexPathVariant::Common => list.split(':').map(|entry| FlexPath::new(entry, variant)).collect(),
+        FlexPathVariant::Windows => {
+            let mut r = Vec::new();
+            let mut entry = String::new();
+            let mut quoted = false;
+            for ch in list.chars().chain(std::iter::once(';')) {
+                match ch {
+                    '"' => quoted = !quoted,
+                    ';' if !quoted => {
+                        if !entry.is_empty() {
+                            r.push(FlexPath::new(&entry, variant));
+                        }
+                        entry.clear();
+                    },
+                    _ => entry.push(ch),
+                }
+            }
+            r
+        },
+    }
+}
+
+/// Joins paths into a `PATH`-style list, the inverse of [`split_path_list`].
+///
+/// For `Windows`, paths containing the separator are enclosed in
+/// double quotes.
+///
+/// # Errors
+///
+/// Returns [`FlexPathError::InvalidListEntry`] if a `Common` path
+/// contains a colon or if a `Windows` path contains a double quote or
+/// is empty, and [`FlexPathError::VariantMismatch`] if a path's variant
+/// differs from `variant`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{join_path_list, FlexPath, FlexPathVariant};
+/// let windows = FlexPathVariant::Windows;
+/// let paths = [r"C:\bin", r"D:\a;b"].map(|p| FlexPath::new(p, windows));
+/// assert_eq!(r#"C:\bin;"D:\a;b""#, join_path_list(&paths, windows).unwrap());
+/// assert!(join_path_list([FlexPath::new_common("/a:b")], FlexPathVariant::Common).is_err());
+/// ```
+pub fn join_path_list<I, P>(paths: I, variant: FlexPathVariant) -> Result<String, FlexPathError>
+where
+    I: IntoIterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    let separator = path_list_separator(variant);
+    let mut r = String::new();
+    for (i, path) in paths.into_iter().enumerate() {
+        let path = path.borrow();
+        if path.1 != variant {
+            return Err(FlexPathError::VariantMismatch { expected: variant, found: path.1 });
+        }
+        let text = path.to_string();
+        let reason = match variant {
+            FlexPathVariant::Common if text.contains(':') => Some("the path contains the list separator"),
+            FlexPathVariant::Windows if text.contains('"') => Some("the path contains a double quote"),
+            FlexPathVariant::Windows if text.is_empty() => Some("empty entries are ignored"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            return Err(FlexPathError::InvalidListEntry { path: text, reason });
+        }
+        if i != 0 {
+            r.push(separator);
+        }
+        if text.contains(separator) {
+            r.push('"');
+            r.push_str(&text);
+            r.push('"');
+        } else {
+            r.push_str(&text);
+        }
+    }
+    Ok(r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let windows = FlexPathVariant::Windows;
+        let paths = parse_path_list("C:\\a\r\n\r\nb/c", ListDelimiter::Newline, windows);
+        assert_eq!(vec![FlexPath::new(r"C:\a", windows), FlexPath::new(r"b\c", windows)], paths);
+        let mut out = Vec::new();
+        write_path_list(&mut out, &paths, ListDelimiter::Nul).unwrap();
+        assert_eq!(b"C:\\a\0b\\c\0", out.as_slice());
+        assert_eq!(paths, read_path_list(out.as_slice(), ListDelimiter::Nul, windows).unwrap());
+        assert_eq!(io::ErrorKind::InvalidData, read_path_list(&b"\xff"[..], ListDelimiter::Nul, windows).unwrap_err().kind());
+        assert!(matches!(format_path_list([FlexPath::new_common("a\0")], ListDelimiter::Nul), Err(FlexPathError::InvalidListEntry { .. })));
+    }
+
+    #[test]
+    fn path_variables() {
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(vec![FlexPath::new("C:/", windows), FlexPath::new("a", windows)], split_path_list(r#"C:\;"a""#, windows));
+        assert!(split_path_list(";;", windows).is_empty());
+        let common = split_path_list(":C:/a", FlexPathVariant::Common);
+        assert_eq!(vec![FlexPath::new_common(""), FlexPath::new_common("C"), FlexPath::new_common("/a")], common);
+        assert_eq!(":C:/a", join_path_list(&common, FlexPathVariant::Common).unwrap());
+        assert!(matches!(join_path_list(&common, windows), Err(FlexPathError::VariantMismatch { .. })));
+        assert_eq!(":/a", join_path_list([FlexPath::new_common(""), FlexPath::new_common("/a")], FlexPathVariant::Common).unwrap());
+    }
+}
@@ -0,0 +1,99 @@
+/*!
+This module contains the `PathMap` collection.
+*/
+
+use super::{cmp, FlexPath, FlexPathVariant, PathCmpOptions};
+use std::collections::HashMap;
+
+/// A map from paths to values whose key comparison follows
+/// a chosen [`PathCmpOptions`].
+///
+/// Each entry keeps the path it was first inserted with, so that
+/// the original letter case remains available for display.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant, PathCmpOptions, PathMap};
+/// let windows = FlexPathVariant::Windows;
+/// let mut map = PathMap::new(PathCmpOptions::for_variant(windows));
+/// map.insert(FlexPath::new(r"C:\Foo.txt", windows), 1);
+/// map.insert(FlexPath::new(r"c:\foo.TXT", windows), 2);
+/// assert_eq!(1, map.len());
+/// assert_eq!(r"C:\Foo.txt", map.get_key_value(&FlexPath::new(r"C:\FOO.txt", windows)).unwrap().0.to_string());
+/// ```
+pub struct PathMap<V> {
+    entries: HashMap<(FlexPathVariant, String), (FlexPath, V)>,
+    options: PathCmpOptions,
+}
+
+impl<V> PathMap<V> {
+    /// Constructs an empty map comparing keys according to `options`.
+    pub fn new(options: PathCmpOptions) -> Self {
+        Self { entries: HashMap::new(), options }
+    }
+
+    /// Returns the comparison options of the map.
+    pub fn options(&self) -> PathCmpOptions {
+        self.options
+    }
+
+    fn key(&self, path: &FlexPath) -> (FlexPathVariant, String) {
+        (path.variant(), cmp::key(path, self.options))
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indicates whether the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts a value, returning the previous value for an equivalent path.
+    /// The previously stored path is kept.
+    pub fn insert(&mut self, path: FlexPath, value: V) -> Option<V> {
+        let key = self.key(&path);
+        match self.entries.get_mut(&key) {
+            Some(entry) => Some(std::mem::replace(&mut entry.1, value)),
+            None => {
+                self.entries.insert(key, (path, value));
+                None
+            },
+        }
+    }
+
+    /// Indicates whether the map contains a path equivalent to `path`.
+    pub fn contains_key(&self, path: &FlexPath) -> bool {
+        self.entries.contains_key(&self.key(path))
+    }
+
+    /// Returns the value for a path equivalent to `path`.
+    pub fn get(&self, path: &FlexPath) -> Option<&V> {
+        self.get_key_value(path).map(|(_, v)| v)
+    }
+
+    /// Returns the stored path and value for a path equivalent to `path`.
+    pub fn get_key_value(&self, path: &FlexPath) -> Option<(&FlexPath, &V)> {
+        self.entries.get(&self.key(path)).map(|(p, v)| (p, v))
+    }
+
+    /// Returns a mutable reference to the value for a path equivalent to `path`.
+    pub fn get_mut(&mut self, path: &FlexPath) -> Option<&mut V> {
+        let key = self.key(path);
+        self.entries.get_mut(&key).map(|(_, v)| v)
+    }
+
+    /// Removes the entry for a path equivalent to `path`, returning its value.
+    pub fn remove(&mut self, path: &FlexPath) -> Option<V> {
+        let key = self.key(path);
+        self.entries.remove(&key).map(|(_, v)| v)
+    }
+
+    /// Iterates over the entries in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&FlexPath, &V)> {
+        self.entries.values().map(|(p, v)| (p, v))
+    }
+}
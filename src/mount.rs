@@ -0,0 +1,311 @@
+/*!
+This module contains the `MountTable` path mapper.
+
+With the `json` or `toml` feature, a table can be saved and loaded as a
+list of mounts, each having the following fields:
+
+- `virtual`: the virtual prefix.
+- `real`: the real prefix.
+- `virtual_variant` and `real_variant`: the variant of each prefix,
+  either `"common"` or `"windows"`. These are optional and default to
+  `"common"`.
+
+In JSON, the table is an object whose `mount` field is an array of
+mount objects:
+
+```json
+{ "mount": [{ "virtual": "/assets", "real": "C:\\game\\data", "real_variant": "windows" }] }
+```
+
+In TOML, the table is an array of tables named `mount`:
+
+```toml
+[[mount]]
+virtual = "/assets"
+real = 'C:\game\data'
+real_variant = "windows"
+```
+*/
+
+#[cfg(any(feature = "json", feature = "toml"))]
+use super::{FlexPathError, FlexPathVariant};
+use super::{trie, FlexPath, PathTrie};
+
+/// Maps virtual path prefixes to real path prefixes and back,
+/// using longest-prefix matching over whole segments.
+///
+/// The virtual and real sides may use different variants. When several
+/// virtual prefixes share a real prefix, [`to_virtual`](Self::to_virtual)
+/// maps back through the earliest of them that is still mounted.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant, MountTable};
+/// let mut table = MountTable::new();
+/// table.mount(FlexPath::new_common("/assets/textures"), FlexPath::new(r"C:\game\data\textures", FlexPathVariant::Windows));
+/// let real = table.to_real(&FlexPath::new_common("/assets/textures/grass.png")).unwrap();
+/// assert_eq!(r"C:\game\data\textures\grass.png", real.to_string());
+/// assert_eq!("/assets/textures/grass.png", table.to_virtual(&real).unwrap().to_string());
+/// ```
+#[derive(Default)]
+pub struct MountTable {
+    virtual_to_real: PathTrie<FlexPath>,
+    real_to_virtual: PathTrie<Vec<FlexPath>>,
+}
+
+impl MountTable {
+    /// Constructs an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts the `real` prefix at the `virtual_prefix`, replacing any
+    /// mount at the same virtual prefix.
+    pub fn mount(&mut self, virtual_prefix: FlexPath, real: FlexPath) {
+        if let Some(previous) = self.virtual_to_real.insert(virtual_prefix.clone(), real.clone()) {
+            self.forget_reverse(&previous, &virtual_prefix);
+        }
+        match self.real_to_virtual.get_mut(&real) {
+            Some(virtual_prefixes) => virtual_prefixes.push(virtual_prefix),
+            None => { self.real_to_virtual.insert(real, vec![virtual_prefix]); },
+        }
+    }
+
+    /// Removes the mount at `virtual_prefix`, returning its real prefix.
+    pub fn unmount(&mut self, virtual_prefix: &FlexPath) -> Option<FlexPath> {
+        let real = self.virtual_to_real.remove(virtual_prefix)?;
+        self.forget_reverse(&real, virtual_prefix);
+        Some(real)
+    }
+
+    /// Removes `virtual_prefix` from the reverse mapping of `real`.
+    fn forget_reverse(&mut self, real: &FlexPath, virtual_prefix: &FlexPath) {
+        let Some(virtual_prefixes) = self.real_to_virtual.get_mut(real) else {
+            return;
+        };
+        virtual_prefixes.retain(|other| trie::node_keys(other) != trie::node_keys(virtual_prefix));
+        if virtual_prefixes.is_empty() {
+            self.real_to_virtual.remove(real);
+        }
+    }
+
+    /// Iterates over the mounts as `(virtual, real)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&FlexPath, &FlexPath)> {
+        self.virtual_to_real.iter()
+    }
+
+    /// Maps a virtual path to its real path through the mount with
+    /// the longest matching virtual prefix.
+    pub fn to_real(&self, virtual_path: &FlexPath) -> Option<FlexPath> {
+        map_prefix(&self.virtual_to_real, virtual_path, |real| real)
+    }
+
+    /// Maps a real path back to its virtual path through the mount with
+    /// the longest matching real prefix.
+    pub fn to_virtual(&self, real_path: &FlexPath) -> Option<FlexPath> {
+        map_prefix(&self.real_to_virtual, real_path, |virtual_prefixes| &virtual_prefixes[0])
+    }
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+impl MountTable {
+    /// Returns the saved form of the mounts as `(field, value)` pairs.
+    fn saved_mounts(&self) -> Vec<[(&'static str, String); 4]> {
+        self.iter().map(|(virtual_prefix, real)| [
+            ("virtual", virtual_prefix.to_string()),
+            ("real", real.to_string()),
+            ("virtual_variant", variant_name(virtual_prefix.1).to_owned()),
+            ("real_variant", variant_name(real.1).to_owned()),
+        ]).collect()
+    }
+
+    /// Adds a saved mount, whose fields are looked up by `field`, failing
+    /// if a field is missing, unknown or not a string.
+    fn load_mount<'a>(&mut self, index: usize, keys: impl Iterator<Item = &'a str>, field: impl Fn(&str) -> Option<Option<&'a str>>) -> Result<(), FlexPathError> {
+        let invalid = |reason: String| FlexPathError::InvalidMountTable { reason: format!("mount {index}: {reason}") };
+        if let Some(key) = keys.into_iter().find(|key| !["virtual", "real", "virtual_variant", "real_variant"].contains(key)) {
+            return Err(invalid(format!("unknown field `{key}`")));
+        }
+        let string = |name: &str| field(name).map(|value| value.ok_or_else(|| invalid(format!("field `{name}` is not a string")))).transpose();
+        let variant = |name: &str| match string(name)? {
+            None | Some("common") => Ok(FlexPathVariant::Common),
+            Some("windows") => Ok(FlexPathVariant::Windows),
+            Some(other) => Err(invalid(format!("unknown variant {other:?}"))),
+        };
+        let path = |name: &str, variant_field: &str| {
+            let path = string(name)?.ok_or_else(|| invalid(format!("missing field `{name}`")))?;
+            Ok::<_, FlexPathError>(FlexPath::new(path, variant(variant_field)?))
+        };
+        self.mount(path("virtual", "virtual_variant")?, path("real", "real_variant")?);
+        Ok(())
+    }
+
+    /// Loads a table saved as JSON, in the format described in the
+    /// [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidMountTable`] if the text is not
+    /// JSON or does not follow the format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, MountTable};
+    /// let table = MountTable::from_json(r#"{ "mount": [{ "virtual": "/app", "real": "/srv/app" }] }"#).unwrap();
+    /// assert_eq!("/srv/app/a", table.to_real(&FlexPath::new_common("/app/a")).unwrap().to_string());
+    /// assert_eq!(1, MountTable::from_json(&table.to_json()).unwrap().iter().count());
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<Self, FlexPathError> {
+        let invalid = |reason: String| FlexPathError::InvalidMountTable { reason };
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|error| invalid(error.to_string()))?;
+        let mounts = match value.get("mount") {
+            None => return Ok(Self::new()),
+            Some(serde_json::Value::Array(mounts)) => mounts,
+            Some(_) => return Err(invalid("field `mount` is not an array".to_owned())),
+        };
+        let mut table = Self::new();
+        for (index, mount) in mounts.iter().enumerate() {
+            let mount = mount.as_object().ok_or_else(|| invalid(format!("mount {index}: not an object")))?;
+            table.load_mount(index, mount.keys().map(|key| key.as_str()), |name| mount.get(name).map(|value| value.as_str()))?;
+        }
+        Ok(table)
+    }
+
+    /// Saves the table as JSON, in the format described in the
+    /// [module documentation](self).
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let mounts = self.saved_mounts().into_iter()
+            .map(|fields| serde_json::Value::Object(fields.into_iter().map(|(key, value)| (key.to_owned(), value.into())).collect()))
+            .collect::<Vec<_>>();
+        let mut table = serde_json::Map::new();
+        table.insert("mount".to_owned(), mounts.into());
+        serde_json::to_string_pretty(&table).unwrap()
+    }
+
+    /// Loads a table saved as TOML, in the format described in the
+    /// [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidMountTable`] if the text is not
+    /// TOML or does not follow the format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, MountTable};
+    /// let table = MountTable::from_toml("[[mount]]\nvirtual = '/data'\nreal = 'D:\\data'\nreal_variant = 'windows'\n").unwrap();
+    /// let real = table.to_real(&FlexPath::new_common("/data/a")).unwrap();
+    /// assert_eq!(FlexPath::new(r"D:\data\a", FlexPathVariant::Windows), real);
+    /// assert_eq!(1, MountTable::from_toml(&table.to_toml()).unwrap().iter().count());
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, FlexPathError> {
+        let invalid = |reason: String| FlexPathError::InvalidMountTable { reason };
+        let value: toml::Table = text.parse().map_err(|error: toml::de::Error| invalid(error.message().to_owned()))?;
+        let mounts = match value.get("mount") {
+            None => return Ok(Self::new()),
+            Some(toml::Value::Array(mounts)) => mounts,
+            Some(_) => return Err(invalid("field `mount` is not an array".to_owned())),
+        };
+        let mut table = Self::new();
+        for (index, mount) in mounts.iter().enumerate() {
+            let mount = mount.as_table().ok_or_else(|| invalid(format!("mount {index}: not a table")))?;
+            table.load_mount(index, mount.keys().map(|key| key.as_str()), |name| mount.get(name).map(|value| value.as_str()))?;
+        }
+        Ok(table)
+    }
+
+    /// Saves the table as TOML, in the format described in the
+    /// [module documentation](self).
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> String {
+        let mounts = self.saved_mounts().into_iter()
+            .map(|fields| toml::Value::Table(fields.into_iter().map(|(key, value)| (key.to_owned(), value.into())).collect()))
+            .collect::<Vec<_>>();
+        let mut table = toml::Table::new();
+        table.insert("mount".to_owned(), mounts.into());
+        toml::to_string(&table).unwrap()
+    }
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+fn variant_name(variant: FlexPathVariant) -> &'static str {
+    match variant {
+        FlexPathVariant::Common => "common",
+        FlexPathVariant::Windows => "windows",
+    }
+}
+
+fn map_prefix<V>(trie: &PathTrie<V>, path: &FlexPath, target: impl Fn(&V) -> &FlexPath) -> Option<FlexPath> {
+    let (prefix, value) = trie.best_match(path)?;
+    let rest = &trie::keys(path)[trie::keys(prefix).len()..];
+    Some(target(value).join_segments(rest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn nested_mounts() {
+        let mut table = MountTable::new();
+        table.mount(FlexPath::new_common("assets://"), FlexPath::new_common("/data"));
+        table.mount(FlexPath::new_common("assets://music"), FlexPath::new_common("/mnt/music"));
+        assert_eq!("/mnt/music/a.ogg", table.to_real(&FlexPath::new_common("assets://music/a.ogg")).unwrap().to_string());
+        assert_eq!("/data/musical", table.to_real(&FlexPath::new_common("assets://musical")).unwrap().to_string());
+        assert_eq!(None, table.to_real(&FlexPath::new_common("other://x")));
+        assert_eq!(Some(FlexPath::new_common("/mnt/music")), table.unmount(&FlexPath::new_common("assets://music")));
+        assert_eq!(None, table.to_virtual(&FlexPath::new_common("/mnt/music/a.ogg")));
+    }
+
+    #[test]
+    fn shared_and_remounted_prefixes() {
+        let mut table = MountTable::new();
+        table.mount(FlexPath::new_common("/a"), FlexPath::new_common("/r"));
+        table.mount(FlexPath::new_common("/b"), FlexPath::new_common("/r"));
+        assert_eq!("/a/x", table.to_virtual(&FlexPath::new_common("/r/x")).unwrap().to_string());
+        table.unmount(&FlexPath::new_common("/a"));
+        assert_eq!("/b/x", table.to_virtual(&FlexPath::new_common("/r/x")).unwrap().to_string());
+
+        table.mount(FlexPath::new_common("/b"), FlexPath::new_common("/s"));
+        assert_eq!(None, table.to_virtual(&FlexPath::new_common("/r/x")));
+        assert_eq!("/b/x", table.to_virtual(&FlexPath::new_common("/s/x")).unwrap().to_string());
+        table.mount(FlexPath::new_common("/b"), FlexPath::new_common("/s"));
+        table.unmount(&FlexPath::new_common("/b"));
+        assert_eq!(None, table.to_virtual(&FlexPath::new_common("/s/x")));
+        assert_eq!(0, table.iter().count());
+
+        table.mount(FlexPath::new_common("/v"), FlexPath::new_common("/r"));
+        table.mount(FlexPath::new("/w", FlexPathVariant::Windows), FlexPath::new("/r", FlexPathVariant::Windows));
+        assert_eq!("/v/x", table.to_virtual(&FlexPath::new_common("/r/x")).unwrap().to_string());
+        assert_eq!(r"\w\x", table.to_virtual(&FlexPath::new("/r/x", FlexPathVariant::Windows)).unwrap().to_string());
+        assert_eq!(None, table.to_real(&FlexPath::new_common("/w")));
+    }
+
+    #[cfg(any(feature = "json", feature = "toml"))]
+    #[test]
+    fn saved_tables() {
+        let mut table = MountTable::new();
+        table.mount(FlexPath::new_common("/c"), FlexPath::new(r"\\srv\share\c", FlexPathVariant::Windows));
+        table.mount(FlexPath::new(r"C:\x", FlexPathVariant::Windows), FlexPath::new_common("/x"));
+        let pairs = |table: &MountTable| table.iter().map(|(v, r)| (v.clone(), r.clone())).collect::<Vec<_>>();
+
+        #[cfg(feature = "json")] {
+            assert_eq!(pairs(&table), pairs(&MountTable::from_json(&table.to_json()).unwrap()));
+            assert!(MountTable::from_json("{}").unwrap().iter().next().is_none());
+            for text in ["[", r#"{"mount": 1}"#, r#"{"mount": [{"virtual": "/a"}]}"#, r#"{"mount": [{"virtual": "/a", "real": "/b", "reel": 1}]}"#, r#"{"mount": [{"virtual": "/a", "real": "/b", "real_variant": "mac"}]}"#] {
+                assert!(matches!(MountTable::from_json(text), Err(FlexPathError::InvalidMountTable { .. })), "{text}");
+            }
+        }
+        #[cfg(feature = "toml")] {
+            assert_eq!(pairs(&table), pairs(&MountTable::from_toml(&table.to_toml()).unwrap()));
+            assert!(matches!(MountTable::from_toml("[[mount]]\nvirtual = 1\nreal = '/b'"), Err(FlexPathError::InvalidMountTable { .. })));
+        }
+    }
+}
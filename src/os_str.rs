@@ -0,0 +1,109 @@
+/*!
+This module contains conversions between `FlexPath` and `OsStr`,
+available with the `os-str` feature.
+
+A `FlexPath` holds UTF-8 text, so a path from the operating system that
+is not valid UTF-8, such as a Unix file name in a legacy encoding, is
+refused with [`FlexPathError::NonUtf8Path`] carrying its original bytes,
+rather than being replaced lossily.
+*/
+
+use super::{FlexPath, FlexPathError, FlexPathVariant};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+impl FlexPath {
+    /// Resolves a path from the operating system with the native variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::NonUtf8Path`] if the path is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathError};
+    /// use std::ffi::OsStr;
+    /// assert_eq!(FlexPath::new_native("a/b"), FlexPath::try_from_os_str(OsStr::new("a/./b")).unwrap());
+    ///
+    /// #[cfg(unix)]
+    /// {
+    ///     use std::os::unix::ffi::OsStrExt;
+    ///     let name = OsStr::from_bytes(b"caf\xe9.txt");
+    ///     let Err(FlexPathError::NonUtf8Path { bytes, valid_up_to }) = FlexPath::try_from_os_str(name) else { panic!() };
+    ///     assert_eq!((name.as_bytes(), 3), (&bytes[..], valid_up_to));
+    /// }
+    /// ```
+    pub fn try_from_os_str(path: &OsStr) -> Result<FlexPath, FlexPathError> {
+        Self::try_from_os_str_with(path, FlexPathVariant::NATIVE)
+    }
+
+    /// Resolves a path from the operating system with a specific variant,
+    /// as in [`FlexPath::try_from_os_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::NonUtf8Path`] if the path is not valid UTF-8.
+    pub fn try_from_os_str_with(path: &OsStr, variant: FlexPathVariant) -> Result<FlexPath, FlexPathError> {
+        match path.to_str() {
+            Some(path) => Ok(FlexPath::new(path, variant)),
+            None => Err(FlexPathError::non_utf8(path)),
+        }
+    }
+
+    /// Converts the path into an `OsString` using the separators of its
+    /// variant, as in [`.to_path_buf`]. This conversion never loses data.
+    ///
+    /// [`.to_path_buf`]: FlexPath::to_path_buf
+    pub fn to_os_string(&self) -> OsString {
+        OsString::from(self.to_string())
+    }
+}
+
+/// Resolves a path with the native variant, refusing non-UTF-8 paths.
+impl TryFrom<&OsStr> for FlexPath {
+    type Error = FlexPathError;
+
+    fn try_from(path: &OsStr) -> Result<Self, Self::Error> {
+        Self::try_from_os_str(path)
+    }
+}
+
+/// Resolves a path with the native variant, refusing non-UTF-8 paths.
+impl TryFrom<&Path> for FlexPath {
+    type Error = FlexPathError;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        Self::try_from_os_str(path.as_os_str())
+    }
+}
+
+/// Resolves a path with the native variant, refusing non-UTF-8 paths.
+impl TryFrom<PathBuf> for FlexPath {
+    type Error = FlexPathError;
+
+    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+        Self::try_from_os_str(path.as_os_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn os_str_round_trip() {
+        let path = FlexPath::new_native("a/b c/é.txt");
+        assert_eq!(path, FlexPath::try_from_os_str(&path.to_os_string()).unwrap());
+        assert_eq!(path, FlexPath::try_from(path.to_path_buf()).unwrap());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let bytes = b"dir/\xff\xfename";
+            let error = FlexPath::try_from(Path::new(OsStr::from_bytes(bytes))).unwrap_err();
+            assert_eq!(FlexPathError::NonUtf8Path { bytes: bytes.to_vec(), valid_up_to: 4 }, error);
+            assert_eq!("path \"dir/\u{fffd}\u{fffd}name\" is not valid UTF-8 after 4 bytes", error.to_string());
+        }
+    }
+}
@@ -0,0 +1,197 @@
+/*!
+This module contains utilities that organize paths by their parent directory.
+*/
+
+use super::{case, FlexPath};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Splits each path of a stream into its parent directory and file name,
+/// as by [`dir_file_pairs`].
+pub struct DirFilePairs<I> {
+    paths: I,
+    dir: Option<Arc<FlexPath>>,
+}
+
+/// Splits each path of a stream into its parent directory and file name.
+///
+/// Consecutive paths under the same directory share the same `Arc`, so that
+/// a change of directory, such as when a tar writer must emit a directory
+/// entry, is detected with `Arc::ptr_eq` in constant time. Paths consisting
+/// solely of a root yield the root with an empty file name.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{dir_file_pairs, FlexPath};
+/// use std::sync::Arc;
+/// let paths = ["a/x", "a/y", "b/z"].map(FlexPath::new_common);
+/// let pairs: Vec<_> = dir_file_pairs(&paths).collect();
+/// assert_eq!("a", pairs[0].0.to_string());
+/// assert_eq!("y", pairs[1].1);
+/// assert!(Arc::ptr_eq(&pairs[0].0, &pairs[1].0));
+/// assert!(!Arc::ptr_eq(&pairs[1].0, &pairs[2].0));
+/// ```
+pub fn dir_file_pairs<I, P>(paths: I) -> DirFilePairs<I::IntoIter>
+where
+    I: IntoIterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    DirFilePairs { paths: paths.into_iter(), dir: None }
+}
+
+impl<I, P> Iterator for DirFilePairs<I>
+where
+    I: Iterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    type Item = (Arc<FlexPath>, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.paths.next()?;
+        let path = path.borrow();
+        let dir = path.dir_str();
+        let same = self.dir.as_ref().is_some_and(|current| current.1 == path.1 && *current.0 == *dir);
+        if !same {
+            self.dir = Some(Arc::new(FlexPath::from_resolved(dir, path.1)));
+        }
+        let name = path.file_name().unwrap_or("").to_owned();
+        Some((self.dir.clone().unwrap(), name))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.paths.size_hint()
+    }
+}
+
+/// Options controlling how [`group_by_parent`] orders file names.
+///
+/// Names that compare equal under these options, such as `a` and `A`
+/// when case-insensitive, are ordered by their bytes, so that the
+/// order never depends on the input order or on the locale.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct NameOrder {
+    /// Indicates whether letter case is significant. When `false`,
+    /// names are compared with Unicode case folding.
+    pub case_sensitive: bool,
+    /// Indicates whether runs of ASCII digits are compared by their
+    /// numeric value, so that `file2` sorts before `file10`.
+    pub natural: bool,
+}
+
+impl Default for NameOrder {
+    fn default() -> Self {
+        Self { case_sensitive: true, natural: false }
+    }
+}
+
+impl NameOrder {
+    /// Compares two file names according to these options.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let primary = if self.case_sensitive {
+            self.compare_folded(a, b)
+        } else {
+            self.compare_folded(&case::fold(a), &case::fold(b))
+        };
+        primary.then_with(|| a.cmp(b))
+    }
+
+    fn compare_folded(&self, a: &str, b: &str) -> Ordering {
+        if self.natural { natural_cmp(a, b) } else { a.cmp(b) }
+    }
+}
+
+fn natural_cmp(mut a: &str, mut b: &str) -> Ordering {
+    // equal numbers with fewer leading zeros sort first, unless the rest differs.
+    let mut zeros = Ordering::Equal;
+    while !a.is_empty() && !b.is_empty() {
+        let (x, rest_a) = split_digit_run(a);
+        let (y, rest_b) = split_digit_run(b);
+        let is_number = |s: &str| s.starts_with(|c: char| c.is_ascii_digit());
+        let r = if is_number(x) && is_number(y) {
+            let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            zeros = zeros.then(x.len().cmp(&y.len()));
+            x_value.len().cmp(&y_value.len()).then_with(|| x_value.cmp(y_value))
+        } else {
+            x.cmp(y)
+        };
+        if r != Ordering::Equal {
+            return r;
+        }
+        (a, b) = (rest_a, rest_b);
+    }
+    a.len().cmp(&b.len()).then(zeros)
+}
+
+/// Splits the leading run of either ASCII digits or other characters.
+fn split_digit_run(s: &str) -> (&str, &str) {
+    let digits = s.starts_with(|c: char| c.is_ascii_digit());
+    s.split_at(s.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(s.len()))
+}
+
+/// Groups paths by their parent directory, each group sorted by file
+/// name according to `order`, producing the same listing regardless of
+/// the input order, as required for reproducible archives.
+///
+/// Paths consisting solely of a root are grouped under themselves, as in
+/// [`dir_file_pairs`]. Duplicate paths are kept.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{group_by_parent, FlexPath, NameOrder};
+/// let paths = ["a/file10", "b/x", "a/File2", "a/file1"].map(FlexPath::new_common);
+/// let groups = group_by_parent(paths, NameOrder { case_sensitive: false, natural: true });
+/// let names: Vec<_> = groups[&FlexPath::new_common("a")].iter().map(|p| p.base_name()).collect();
+/// assert_eq!(vec!["file1", "File2", "file10"], names);
+/// assert_eq!(2, groups.len());
+/// ```
+pub fn group_by_parent<I, P>(paths: I, order: NameOrder) -> BTreeMap<FlexPath, Vec<FlexPath>>
+where
+    I: IntoIterator<Item = P>,
+    P: Borrow<FlexPath>,
+{
+    let mut r = BTreeMap::<FlexPath, Vec<FlexPath>>::new();
+    for path in paths {
+        let path = path.borrow();
+        r.entry(FlexPath::from_resolved(path.dir_str(), path.1)).or_default().push(path.clone());
+    }
+    for children in r.values_mut() {
+        children.sort_by(|a, b| order.compare(a.file_name().unwrap_or(""), b.file_name().unwrap_or("")));
+    }
+    r
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn roots() {
+        let windows = FlexPathVariant::Windows;
+        let paths = [r"C:\", r"C:\a", r"\\srv\share\b", "c"].map(|p| FlexPath::new(p, windows));
+        let pairs: Vec<(String, String)> = dir_file_pairs(paths).map(|(dir, name)| (dir.to_string(), name)).collect();
+        let expected = [(r"C:\", ""), (r"C:\", "a"), (r"\\srv\share\", "b"), ("", "c")];
+        assert_eq!(expected.map(|(d, n)| (d.to_owned(), n.to_owned())).to_vec(), pairs);
+    }
+
+    #[test]
+    fn name_orders() {
+        let natural = NameOrder { natural: true, ..Default::default() };
+        let folded = NameOrder { case_sensitive: false, natural: false };
+        assert_eq!(Ordering::Less, natural.compare("a2", "a10"));
+        assert_eq!(Ordering::Less, natural.compare("a02b", "a2c"));
+        assert_eq!(Ordering::Less, natural.compare("a2", "a02"));
+        assert_eq!(Ordering::Less, natural.compare("a", "a1"));
+        assert_eq!(Ordering::Greater, NameOrder::default().compare("a2", "a10"));
+        assert_eq!(Ordering::Less, folded.compare("B", "c"));
+        assert_eq!(Ordering::Less, folded.compare("A", "a"));
+
+        let windows = FlexPathVariant::Windows;
+        let groups = group_by_parent([r"C:\b", r"C:\a", r"C:\"].map(|p| FlexPath::new(p, windows)), NameOrder::default());
+        assert_eq!(vec![r"C:\", r"C:\a", r"C:\b"], groups[&FlexPath::new("C:/", windows)].iter().map(|p| p.to_string()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,106 @@
+/*!
+This module contains conversions between `FlexPath` segments and
+JSON Pointers as specified by RFC 6901.
+*/
+
+use super::{flexible, FlexPath, FlexPathError, FlexPathVariant, SegmentPolicy};
+
+impl FlexPath {
+    /// Converts the segments of the path into a JSON Pointer, escaping
+    /// `~` as `~0` in each segment. The root of an
+    /// absolute path is not represented, and the empty path is the empty
+    /// pointer, referring to a whole document.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// assert_eq!("/a/b~0c", FlexPath::new_common("a/b~c").to_json_pointer());
+    /// assert_eq!("/Users/me", FlexPath::new(r"C:\Users\me", FlexPathVariant::Windows).to_json_pointer());
+    /// assert_eq!("", FlexPath::new_common("/").to_json_pointer());
+    /// ```
+    pub fn to_json_pointer(&self) -> String {
+        let root_len = flexible::root_len(&self.0, self.1);
+        let mut r = String::new();
+        // segments never contain `/`, so only `~` needs escaping.
+        for segment in self.0[root_len..].split('/').filter(|s| !s.is_empty()) {
+            r.push('/');
+            r.push_str(&segment.replace('~', "~0"));
+        }
+        r
+    }
+
+    /// Converts a JSON Pointer into a relative path of the given variant,
+    /// appending each reference token as a literal segment as in
+    /// [`.push_segment`](Self::push_segment), handling tokens that are not
+    /// literal segments, such as `..` or ones containing `/`, according
+    /// to `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidEncoding`] if the pointer is not
+    /// empty and does not start with `/`, or contains a `~` not followed
+    /// by `0` or `1`, and [`FlexPathError::InvalidSegment`] for a token
+    /// that is not a literal segment under [`SegmentPolicy::Reject`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, SegmentPolicy};
+    /// let common = FlexPathVariant::Common;
+    /// assert_eq!("a/b~c", FlexPath::from_json_pointer("/a/b~0c", common, SegmentPolicy::Reject).unwrap().to_string());
+    /// assert!(FlexPath::from_json_pointer("/a~1b", common, SegmentPolicy::Reject).is_err());
+    /// assert_eq!("a%2Fb", FlexPath::from_json_pointer("/a~1b", common, SegmentPolicy::Escape).unwrap().to_string());
+    /// ```
+    pub fn from_json_pointer(pointer: &str, variant: FlexPathVariant, policy: SegmentPolicy) -> Result<FlexPath, FlexPathError> {
+        let invalid = |reason| FlexPathError::InvalidEncoding { reason };
+        let mut r = FlexPath::new("", variant);
+        if pointer.is_empty() {
+            return Ok(r);
+        }
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            return Err(invalid("JSON Pointer does not start with '/'"));
+        };
+        for token in tokens.split('/') {
+            let mut segment = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(ch) = chars.next() {
+                if ch != '~' {
+                    segment.push(ch);
+                    continue;
+                }
+                segment.push(match chars.next() {
+                    Some('0') => '~',
+                    Some('1') => '/',
+                    _ => return Err(invalid("JSON Pointer contains an invalid '~' escape")),
+                });
+            }
+            r.push_segment(&segment, policy)?;
+        }
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_pointer_round_trip() {
+        let common = FlexPathVariant::Common;
+        for path in ["", "a", "a/b", "~/x~1y", "a%2Fb/c"] {
+            let path = FlexPath::new_common(path);
+            let pointer = path.to_json_pointer();
+            assert_eq!(path, FlexPath::from_json_pointer(&pointer, common, SegmentPolicy::Reject).unwrap(), "{pointer}");
+        }
+        assert_eq!("/~01/~0", FlexPath::new_common("~1/~").to_json_pointer());
+        assert!(FlexPath::from_json_pointer("a", common, SegmentPolicy::Reject).is_err());
+        assert!(FlexPath::from_json_pointer("/a~2", common, SegmentPolicy::Reject).is_err());
+        assert!(FlexPath::from_json_pointer("/a~", common, SegmentPolicy::Reject).is_err());
+        assert!(matches!(FlexPath::from_json_pointer("/..", common, SegmentPolicy::Reject), Err(FlexPathError::InvalidSegment { .. })));
+        assert!(FlexPath::from_json_pointer("//", common, SegmentPolicy::Reject).is_err());
+        assert_eq!("%00/%2E%2E", FlexPath::from_json_pointer("//..", common, SegmentPolicy::Escape).unwrap().to_string());
+        let windows = FlexPathVariant::Windows;
+        assert!(FlexPath::from_json_pointer("/C:", windows, SegmentPolicy::Reject).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+/*!
+This module exposes the low-level text algorithms underlying `FlexPath`,
+operating on `&str` without constructing a path, for embedding them in
+custom path types.
+
+Functions taking a _resolved_ path expect the output of [`resolve`] or
+[`remove_dot_segments`], which delimits segments with forward slashes
+after the root.
+
+# Example
+
+```
+use hydroperx_path::{raw, FlexPathVariant};
+let path = raw::remove_dot_segments(r"C:\a\..\b\.\c", FlexPathVariant::Windows);
+assert_eq!("C:/b/c", path);
+let root_len = raw::root_len(&path, FlexPathVariant::Windows);
+assert_eq!(vec!["b", "c"], raw::split_separators(&path[root_len..]).collect::<Vec<_>>());
+```
+*/
+
+use super::{common, flexible, FlexPathVariant};
+
+/// Splits a path at every forward and backward slash, yielding empty
+/// segments between adjacent separators.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::raw;
+/// assert_eq!(vec!["", "a", "", "b"], raw::split_separators(r"/a\\b").collect::<Vec<_>>());
+/// ```
+pub fn split_separators(path: &str) -> impl Iterator<Item = &str> {
+    common::split_separators(path)
+}
+
+/// Returns the length of the Windows prefix of an unresolved path, such
+/// as `C:`, `\\` or `\\?\C:`, or zero if it has none or `variant` is
+/// `Common`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{raw, FlexPathVariant};
+/// assert_eq!(6, raw::prefix_len(r"\\?\C:\a", FlexPathVariant::Windows));
+/// assert_eq!(2, raw::prefix_len("//srv/share", FlexPathVariant::Windows));
+/// assert_eq!(0, raw::prefix_len("C:/a", FlexPathVariant::Common));
+/// ```
+pub fn prefix_len(path: &str, variant: FlexPathVariant) -> usize {
+    flexible::prefix_len(path, variant)
+}
+
+/// Canonicalizes a prefix measured by [`prefix_len`] the way resolution
+/// does, writing verbatim and UNC prefixes with backward slashes.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::raw;
+/// assert_eq!(r"\\?\C:", raw::canonical_prefix("//?/C:"));
+/// ```
+pub fn canonical_prefix(prefix: &str) -> String {
+    flexible::canonical_prefix(prefix)
+}
+
+/// Indicates whether an unresolved path is absolute for `variant`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{raw, FlexPathVariant};
+/// assert!(raw::is_absolute("C:a", FlexPathVariant::Windows));
+/// assert!(!raw::is_absolute("C:a", FlexPathVariant::Common));
+/// ```
+pub fn is_absolute(path: &str, variant: FlexPathVariant) -> bool {
+    flexible::is_absolute(path, variant)
+}
+
+/// Resolves `path2` relative to `path1`, removing dot segments and
+/// redundant separators as [`FlexPath::resolve`](crate::FlexPath::resolve) does.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{raw, FlexPathVariant};
+/// assert_eq!("/a/c", raw::resolve("/a/b", "../c", FlexPathVariant::Common));
+/// assert_eq!("D:/c", raw::resolve("C:/a", r"D:\c", FlexPathVariant::Windows));
+/// ```
+pub fn resolve(path1: &str, path2: &str, variant: FlexPathVariant) -> String {
+    flexible::resolve(path1, path2, variant)
+}
+
+/// Appends the resolution of `path2` relative to `path1` to `out`,
+/// treating Windows prefixes as ordinary segments and allocating only to
+/// grow `out`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::raw;
+/// let mut out = String::from("path: ");
+/// raw::resolve_into("a/b", "../c", &mut out);
+/// assert_eq!("path: a/c", out);
+/// ```
+pub fn resolve_into(path1: &str, path2: &str, out: &mut String) {
+    common::resolve_into(path1, path2, out)
+}
+
+/// Removes `.` and `..` segments and redundant separators from a path,
+/// dropping `..` segments that would ascend above its start.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{raw, FlexPathVariant};
+/// assert_eq!("/b", raw::remove_dot_segments("/a/./../../b/", FlexPathVariant::Common));
+/// assert_eq!(r"\\srv/share/b", raw::remove_dot_segments(r"\\srv\share\a\..\b", FlexPathVariant::Windows));
+/// ```
+pub fn remove_dot_segments(path: &str, variant: FlexPathVariant) -> String {
+    flexible::resolve_one(path, variant)
+}
+
+/// Returns the length of the root of a resolved path, including its
+/// trailing separator if any, or zero for a relative path.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{raw, FlexPathVariant};
+/// assert_eq!(3, raw::root_len("C:/a", FlexPathVariant::Windows));
+/// assert_eq!(12, raw::root_len(r"\\srv/share/a", FlexPathVariant::Windows));
+/// assert_eq!(0, raw::root_len("a/b", FlexPathVariant::Common));
+/// ```
+pub fn root_len(path: &str, variant: FlexPathVariant) -> usize {
+    flexible::root_len(path, variant)
+}
+
+/// Canonicalizes a root measured by [`root_len`], using forward slashes,
+/// an uppercase drive letter and an uppercase verbatim `UNC` marker.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::raw;
+/// assert_eq!("//?/C:/", raw::canonical_root(r"\\?\c:/"));
+/// ```
+pub fn canonical_root(root: &str) -> String {
+    flexible::canonical_root(root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPath;
+
+    #[test]
+    fn agrees_with_flex_path() {
+        for (path, variant) in [
+            ("/a/../b/./c//", FlexPathVariant::Common),
+            ("a/../../b", FlexPathVariant::Common),
+            (r"c:\a\..\b", FlexPathVariant::Windows),
+            (r"\\srv\share\..\a", FlexPathVariant::Windows),
+            (r"\\?\C:\a\.\b", FlexPathVariant::Windows),
+        ] {
+            let resolved = remove_dot_segments(path, variant);
+            let flex = FlexPath::new(path, variant);
+            assert_eq!(flex.to_string().replace('\\', "/"), resolved.replace('\\', "/"), "{path}");
+            assert_eq!(flex.is_absolute(), is_absolute(path, variant), "{path}");
+            let root_len = root_len(&resolved, variant);
+            let segments: Vec<&str> = split_separators(&resolved[root_len..]).filter(|s| !s.is_empty()).collect();
+            assert_eq!(flex.segments().skip(usize::from(root_len != 0)).collect::<Vec<_>>(), segments, "{path}");
+        }
+    }
+}
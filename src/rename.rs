@@ -0,0 +1,96 @@
+/*!
+This module contains the `plan_renames` bulk rename planner.
+*/
+
+use super::{rewrite, FlexGlob, FlexPath, FlexPathError};
+use std::collections::HashMap;
+
+/// Plans renaming every path matching `from` to the `to` template, where
+/// `$N` or `${N}` is substituted by the `N`-th wildcard capture (`$0`
+/// being the whole path) and `$$` is a literal dollar sign, as in
+/// [`RewriteRules`](crate::RewriteRules). Targets are resolved as paths
+/// of the glob's variant.
+///
+/// Returns the `(old, new)` pairs in input order, omitting paths that do
+/// not match or whose target is the same text. Paths that are not renamed
+/// keep their names, so renaming onto one of them is a conflict too.
+///
+/// # Errors
+///
+/// Returns [`FlexPathError::RenameConflict`] if two different paths would
+/// end up with the same name, comparing names as [`FlexPath::normal_key`]
+/// does.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{plan_renames, FlexGlob, FlexPath, FlexPathError, FlexPathVariant};
+/// let glob = FlexGlob::new("photos/IMG_*.jpeg", FlexPathVariant::Common).unwrap();
+/// let paths = ["photos/IMG_1.jpeg", "photos/IMG_2.jpeg", "photos/notes.txt"].map(FlexPath::new_common);
+/// let plan = plan_renames(&paths, &glob, "photos/$1.jpg").unwrap();
+/// assert_eq!(vec![("photos/IMG_1.jpeg".into(), "photos/1.jpg".into()), ("photos/IMG_2.jpeg".into(), "photos/2.jpg".into())],
+///     plan.iter().map(|(old, new)| (old.to_string(), new.to_string())).collect::<Vec<_>>());
+///
+/// let glob = FlexGlob::new("*.jpeg", FlexPathVariant::Common).unwrap();
+/// let paths = ["a.jpeg", "b.jpeg"].map(FlexPath::new_common);
+/// assert!(matches!(plan_renames(&paths, &glob, "x.jpg"), Err(FlexPathError::RenameConflict { .. })));
+/// ```
+pub fn plan_renames<'a, T>(paths: T, from: &FlexGlob, to: &str) -> Result<Vec<(FlexPath, FlexPath)>, FlexPathError>
+    where T: IntoIterator<Item = &'a FlexPath>
+{
+    let mut plan = Vec::new();
+    let mut targets = HashMap::<String, &FlexPath>::new();
+    for path in paths {
+        let target = match from.captures(path) {
+            Some(captures) => FlexPath::new(&rewrite::substitute(to, &captures), from.variant()),
+            None => path.clone(),
+        };
+        if let Some(source) = targets.insert(target.normal_key(), path) {
+            if source.normal_key() == path.normal_key() {
+                // the same path listed twice.
+                continue;
+            }
+            return Err(FlexPathError::RenameConflict {
+                target: target.to_string(),
+                sources: [source.to_string(), path.to_string()],
+            });
+        }
+        if target.0 != path.0 {
+            plan.push((path.clone(), target));
+        }
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn conflicts() {
+        let windows = FlexPathVariant::Windows;
+        let glob = FlexGlob::new(r"C:\logs\*-*.log", windows).unwrap();
+        let paths = [r"C:\logs\app-1.log", r"C:\logs\app-2.log", r"C:\logs\web-1.log", r"C:\logs\app-1.log"].map(|p| FlexPath::new(p, windows));
+        let plan = plan_renames(&paths, &glob, r"C:\logs\$2\$1.log").unwrap();
+        assert_eq!(3, plan.len());
+        assert_eq!(FlexPath::new(r"C:\logs\1\web.log", windows), plan[2].1);
+
+        let braces = FlexGlob::new(r"C:\{logs,old}\*.log", windows).unwrap();
+        let plan = plan_renames(&[FlexPath::new(r"C:\old\app.log", windows)], &braces, r"C:\archive\$1.txt").unwrap();
+        assert_eq!(FlexPath::new(r"C:\archive\app.txt", windows), plan[0].1);
+
+        // renaming onto a path that keeps its name, compared case-insensitively.
+        let paths = [r"C:\logs\1\APP.log", r"C:\logs\app-1.log"].map(|p| FlexPath::new(p, windows));
+        let error = plan_renames(&paths, &glob, r"C:\logs\$2\$1.log").unwrap_err();
+        assert_eq!(
+            FlexPathError::RenameConflict { target: r"C:\logs\1\app.log".to_owned(), sources: [r"C:\logs\1\APP.log".to_owned(), r"C:\logs\app-1.log".to_owned()] },
+            error
+        );
+
+        // a case-only rename is kept.
+        let glob = FlexGlob::new(r"C:\*.TXT", windows).unwrap();
+        let plan = plan_renames(&[FlexPath::new(r"C:\A.TXT", windows)], &glob, r"C:\$1.txt").unwrap();
+        assert_eq!(r"C:\A.txt", plan[0].1.to_string());
+    }
+}
@@ -0,0 +1,512 @@
+/*!
+This module contains the reporting of the changes resolution makes
+to its input, for tools that warn about untidy paths rather than
+silently fixing them.
+*/
+
+use super::{flexible, FlexPath, FlexPathVariant};
+use std::ops::Range;
+
+/// A change resolution made to its input, reported by
+/// [`FlexPath::resolve_with_report`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ResolveEdit {
+    /// The byte range of the input that was changed or removed.
+    pub span: Range<usize>,
+    /// What was done to the input.
+    pub kind: ResolveEditKind,
+}
+
+/// Describes a [`ResolveEdit`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ResolveEditKind {
+    /// A repeated separator, as in `a//b`, was removed.
+    EmptySegment,
+    /// A `.` segment was removed.
+    CurrentDir,
+    /// A `..` segment was collapsed with the preceding segment, or
+    /// dropped if there was none, as in `/..` or `../a`.
+    ParentDir {
+        /// The segment that `..` removed along with itself, which may come
+        /// from the base path, or `None` if `..` was dropped.
+        removed: Option<String>,
+    },
+    /// Separators after the last segment were removed.
+    TrailingSeparator,
+    /// A segment consisting only of whitespace, as in `a/ /b`, was removed
+    /// by [`FlexPath::repair_separators`].
+    BlankSegment,
+    /// A backslash was taken as a separator by the `Common` variant and
+    /// replaced with a forward slash.
+    Backslash,
+    /// A Windows prefix was rewritten, as `//?/C:` into `\\?\C:` or `//`
+    /// into `\\`.
+    PrefixRewrite {
+        /// The canonical prefix.
+        to: String,
+    },
+}
+
+/// The changes resolution made to its input, in input order.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ResolveReport {
+    /// The changes.
+    pub edits: Vec<ResolveEdit>,
+}
+
+impl ResolveReport {
+    /// Indicates whether the input was already in resolved form.
+    pub fn is_clean(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    fn push(&mut self, span: Range<usize>, kind: ResolveEditKind) {
+        self.edits.push(ResolveEdit { span, kind });
+    }
+}
+
+/// A segment of a path parsed by [`FlexPath::parse_with_spans`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SpannedSegment<'a> {
+    /// The byte range of the segment in the input.
+    pub span: Range<usize>,
+    /// The segment.
+    pub text: &'a str,
+}
+
+/// A path parsed by [`FlexPath::parse_with_spans`], mapping each
+/// segment of the resolved path back to the input.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SpannedPath<'a> {
+    /// The resolved path.
+    pub path: FlexPath,
+    /// The byte range of the root in the input, such as `/`, `C:\` or
+    /// `\\`, or `None` for a relative path.
+    pub root: Option<Range<usize>>,
+    /// The segments of the resolved path after the root, in order. For a
+    /// UNC path, the host and share are the first segments.
+    pub segments: Vec<SpannedSegment<'a>>,
+    /// The changes resolution made to the input.
+    pub report: ResolveReport,
+}
+
+/// A text edit to the input of a [`SpannedPath`], replacing a byte range
+/// with new text, as reported by an editor.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct InputEdit<'e> {
+    /// The byte range of the old input that was replaced.
+    pub range: Range<usize>,
+    /// The text that replaced it.
+    pub text: &'e str,
+}
+
+impl InputEdit<'_> {
+    /// Applies the edit to `input`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of the edit is out of bounds of `input` or not on
+    /// character boundaries.
+    pub fn apply(&self, input: &str) -> String {
+        let mut edited = input.to_owned();
+        edited.replace_range(self.range.clone(), self.text);
+        edited
+    }
+}
+
+impl SpannedPath<'_> {
+    /// Produces the parse of `input`, which is the parsed input after
+    /// `edit`, reusing this parse when the edit stays within one segment of
+    /// the resolved path and leaves it an ordinary segment. Otherwise, as when
+    /// the edit inserts a separator or turns the segment into `..`, `input`
+    /// is parsed from scratch. Either way, the result equals that of
+    /// [`FlexPath::parse_with_spans`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, InputEdit};
+    /// let input = "/src/./lib/mod.rs";
+    /// let parsed = FlexPath::parse_with_spans(input, FlexPathVariant::Common);
+    /// let edit = InputEdit { range: 7..10, text: "core" };
+    /// let edited = edit.apply(input);
+    /// let reparsed = parsed.reparse(&edit, &edited);
+    /// assert_eq!("/src/core/mod.rs", reparsed.path.to_string());
+    /// assert_eq!(12..18, reparsed.segments[2].span);
+    /// assert_eq!(5..6, reparsed.report.edits[0].span);
+    /// ```
+    pub fn reparse<'b>(&self, edit: &InputEdit, input: &'b str) -> SpannedPath<'b> {
+        self.reparse_local(edit, input).unwrap_or_else(|| FlexPath::parse_with_spans(input, self.path.1))
+    }
+
+    fn reparse_local<'b>(&self, edit: &InputEdit, input: &'b str) -> Option<SpannedPath<'b>> {
+        let variant = self.path.1;
+        let index = self.segments.iter().position(|s| s.span.start <= edit.range.start && edit.range.end <= s.span.end)?;
+        let span = &self.segments[index].span;
+        // an edit at the start of the input may form a drive prefix, and
+        // one to the first segment after a `\\` root may form a verbatim
+        // or device prefix.
+        if variant == FlexPathVariant::Windows && (span.start == 0 || (index == 0 && self.path.0.starts_with(r"\\"))) {
+            return None;
+        }
+        let delta = edit.text.len() as isize - edit.range.len() as isize;
+        let shift = |offset: usize| offset.checked_add_signed(delta).unwrap();
+        let new_span = span.start..shift(span.end);
+        let text = input.get(new_span.clone())?;
+        if matches!(text, "" | "." | "..") || text.contains(['/', '\\']) {
+            return None;
+        }
+
+        // the resolved text is the root followed by the joined segments;
+        // a verbatim prefix may have to be added or dropped, so it is left
+        // to a full parse.
+        let resolved = self.path.0.as_str();
+        let joined_len = self.segments.iter().map(|s| s.text.len() + 1).sum::<usize>().saturating_sub(1);
+        let root_len = resolved.len().checked_sub(joined_len)?;
+        if resolved.starts_with(r"\\?") || !resolved[root_len..].starts_with(self.segments.first().map_or("", |s| s.text)) {
+            return None;
+        }
+        let mut path = resolved[..root_len].to_owned();
+        let mut segments = Vec::with_capacity(self.segments.len());
+        for (i, segment) in self.segments.iter().enumerate() {
+            let segment = match i.cmp(&index) {
+                std::cmp::Ordering::Less => SpannedSegment { span: segment.span.clone(), text: &input[segment.span.clone()] },
+                std::cmp::Ordering::Equal => SpannedSegment { span: new_span.clone(), text },
+                std::cmp::Ordering::Greater => {
+                    let span = shift(segment.span.start)..shift(segment.span.end);
+                    SpannedSegment { text: &input[span.clone()], span }
+                },
+            };
+            if i != 0 {
+                path.push('/');
+            }
+            path.push_str(segment.text);
+            segments.push(segment);
+        }
+        let mut report = self.report.clone();
+        for reported in &mut report.edits {
+            if reported.span.start >= edit.range.end {
+                reported.span = shift(reported.span.start)..shift(reported.span.end);
+            }
+        }
+        Some(SpannedPath { path: FlexPath::from_resolved(path, variant), root: self.root.clone(), segments, report })
+    }
+}
+
+/// Scans `input` the way resolution against `base` processes it,
+/// returning the segments of `input` that remain in the result, in
+/// order, and the changes made to the input.
+pub fn scan<'a>(base: Option<&FlexPath>, input: &'a str, variant: FlexPathVariant) -> (Vec<SpannedSegment<'a>>, ResolveReport) {
+    let mut report = ResolveReport::default();
+    let prefix_len = flexible::prefix_len(input, variant);
+    if prefix_len != 0 {
+        let canonical = flexible::canonical_prefix(&input[..prefix_len]);
+        if canonical != input[..prefix_len] {
+            report.push(0..prefix_len, ResolveEditKind::PrefixRewrite { to: canonical });
+        }
+    }
+    let absolute = prefix_len != 0 || input.starts_with(['/', '\\']);
+
+    // segments that a `..` may remove: either from the base or from the input.
+    let mut base_segments: Vec<&str> = Vec::new();
+    if let Some(base) = base.filter(|_| !absolute) {
+        let rest = &base.0[flexible::prefix_len(&base.0, base.1)..];
+        base_segments.extend(rest.split('/').filter(|s| !s.is_empty()));
+    }
+    let mut segments: Vec<SpannedSegment> = Vec::new();
+
+    let mut pieces: Vec<(Range<usize>, &str)> = Vec::new();
+    let mut start = prefix_len;
+    for (i, ch) in input[prefix_len..].char_indices().map(|(i, ch)| (i + prefix_len, ch)) {
+        if ch == '/' || ch == '\\' {
+            if ch == '\\' && variant == FlexPathVariant::Common {
+                report.push(i..i + 1, ResolveEditKind::Backslash);
+            }
+            pieces.push((start..i, &input[start..i]));
+            start = i + 1;
+        }
+    }
+    pieces.push((start..input.len(), &input[start..]));
+
+    let last = pieces.iter().rposition(|(_, s)| !s.is_empty());
+    for (i, (span, text)) in pieces.iter().enumerate() {
+        let root = i == 0 && absolute && text.is_empty();
+        match *text {
+            _ if root => {},
+            "" if last.is_some_and(|last| i > last) => {
+                if i == last.unwrap() + 1 {
+                    report.push(span.start - 1..input.len(), ResolveEditKind::TrailingSeparator);
+                }
+            },
+            "" if i + 1 < pieces.len() => report.push(span.end..span.end + 1, ResolveEditKind::EmptySegment),
+            "" => {},
+            "." => report.push(span.clone(), ResolveEditKind::CurrentDir),
+            ".." => {
+                let removed = match segments.pop() {
+                    Some(segment) => Some(segment.text.to_owned()),
+                    None => base_segments.pop().map(|s| s.to_owned()),
+                };
+                report.push(span.clone(), ResolveEditKind::ParentDir { removed });
+            },
+            _ => segments.push(SpannedSegment { span: span.clone(), text }),
+        }
+    }
+    report.edits.sort_by_key(|edit| edit.span.start);
+    (segments, report)
+}
+
+impl FlexPath {
+    /// Constructs a `FlexPath` like [`FlexPath::new`], reporting every change
+    /// resolution made to `path`, such as removed empty segments and
+    /// collapsed `..` segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveEditKind};
+    /// let (path, report) = FlexPath::new_with_report("a//b/../c/", FlexPathVariant::Common);
+    /// assert_eq!("a/c", path.to_string());
+    /// let kinds: Vec<_> = report.edits.iter().map(|edit| (edit.span.clone(), edit.kind.clone())).collect();
+    /// assert_eq!(vec![
+    ///     (2..3, ResolveEditKind::EmptySegment),
+    ///     (5..7, ResolveEditKind::ParentDir { removed: Some("b".into()) }),
+    ///     (9..10, ResolveEditKind::TrailingSeparator),
+    /// ], kinds);
+    /// assert!(FlexPath::new_with_report("a/c", FlexPathVariant::Common).1.is_clean());
+    /// ```
+    pub fn new_with_report(path: &str, variant: FlexPathVariant) -> (FlexPath, ResolveReport) {
+        (FlexPath::new(path, variant), scan(None, path, variant).1)
+    }
+
+    /// Resolves `path2` relative to this path like [`.resolve`](Self::resolve),
+    /// reporting every change resolution made to `path2`. A `..` may remove a
+    /// segment of this path, which is reported along with it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveEditKind};
+    /// let windows = FlexPathVariant::Windows;
+    /// let (path, report) = FlexPath::new(r"C:\a", windows).resolve_with_report(r"..\.\b");
+    /// assert_eq!(r"C:\b", path.to_string());
+    /// assert_eq!(ResolveEditKind::ParentDir { removed: Some("a".into()) }, report.edits[0].kind);
+    /// assert_eq!(ResolveEditKind::CurrentDir, report.edits[1].kind);
+    /// ```
+    pub fn resolve_with_report(&self, path2: &str) -> (FlexPath, ResolveReport) {
+        (self.resolve(path2), scan(Some(self), path2, self.1).1)
+    }
+
+    /// Resolves `path` like [`FlexPath::new_with_report`], additionally
+    /// mapping the root and each segment of the result back to its byte
+    /// range in `path`, for precise diagnostics and quick fixes in tools
+    /// such as configuration linters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let input = r"C:\src\..\lib\\a.rs";
+    /// let parsed = FlexPath::parse_with_spans(input, FlexPathVariant::Windows);
+    /// assert_eq!(r"C:\lib\a.rs", parsed.path.to_string());
+    /// assert_eq!(Some(0..3), parsed.root);
+    /// let spans: Vec<_> = parsed.segments.iter().map(|s| (s.text, s.span.clone())).collect();
+    /// assert_eq!(vec![("lib", 10..13), ("a.rs", 15..19)], spans);
+    /// assert_eq!(2, parsed.report.edits.len());
+    /// ```
+    pub fn parse_with_spans(path: &str, variant: FlexPathVariant) -> SpannedPath<'_> {
+        let (segments, report) = scan(None, path, variant);
+        let resolved = FlexPath::new(path, variant);
+        let prefix_len = flexible::prefix_len(path, variant);
+        let root = if resolved.is_absolute() {
+            let separator = usize::from(path[prefix_len..].starts_with(['/', '\\']));
+            Some(0..prefix_len + separator)
+        } else {
+            None
+        };
+        SpannedPath { path: resolved, root, segments, report }
+    }
+}
+
+impl FlexPath {
+    /// Indicates whether `input` ends with two or more separators, as in
+    /// `a///`, which often indicates a path pasted or concatenated by hand.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert!(FlexPath::ends_with_separator_chain(r"a/\"));
+    /// assert!(!FlexPath::ends_with_separator_chain("a/"));
+    /// ```
+    pub fn ends_with_separator_chain(input: &str) -> bool {
+        input.bytes().rev().take_while(|&b| b == b'/' || b == b'\\').nth(1).is_some()
+    }
+
+    /// Repairs the separators of `input` without otherwise resolving it,
+    /// for form validation that shows the user what was changed: runs of
+    /// separators collapse into their first separator, separators after the
+    /// last segment are removed, and so are segments consisting only of
+    /// whitespace, as in `a/ /b`. Other segments, including `.` and `..`,
+    /// are kept, and so is the root, such as `/`, `C:\` or `\\`.
+    ///
+    /// Returns the repaired text and a report of every repair, whose spans
+    /// refer to `input`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, ResolveEditKind};
+    /// let (repaired, report) = FlexPath::repair_separators("..//../ /a///", FlexPathVariant::Common);
+    /// assert_eq!("../../a", repaired);
+    /// let kinds: Vec<_> = report.edits.iter().map(|edit| (edit.span.clone(), edit.kind.clone())).collect();
+    /// assert_eq!(vec![
+    ///     (3..4, ResolveEditKind::EmptySegment),
+    ///     (7..8, ResolveEditKind::BlankSegment),
+    ///     (8..9, ResolveEditKind::EmptySegment),
+    ///     (10..13, ResolveEditKind::TrailingSeparator),
+    /// ], kinds);
+    /// ```
+    pub fn repair_separators(input: &str, variant: FlexPathVariant) -> (String, ResolveReport) {
+        let prefix_len = flexible::prefix_len(input, variant);
+        // a UNC or verbatim prefix already ends with a separator.
+        let prefix_has_separator = input[..prefix_len].ends_with(['/', '\\']);
+        let mut report = ResolveReport::default();
+        let mut out = input[..prefix_len].to_owned();
+
+        // split the rest into separator runs and segments.
+        let mut tokens: Vec<(Range<usize>, bool)> = Vec::new();
+        for (i, ch) in input[prefix_len..].char_indices().map(|(i, ch)| (i + prefix_len, ch)) {
+            let separator = ch == '/' || ch == '\\';
+            match tokens.last_mut() {
+                Some((span, last)) if *last == separator => span.end = i + ch.len_utf8(),
+                _ => tokens.push((i..i + ch.len_utf8(), separator)),
+            }
+        }
+        let blank = |span: &Range<usize>| input[span.clone()].trim().is_empty();
+        let last_segment = tokens.iter().rposition(|(span, separator)| !separator && !blank(span));
+
+        let mut pending_separator: Option<char> = None;
+        let mut wrote_segment = false;
+        for (i, (span, separator)) in tokens.iter().enumerate() {
+            let trailing = last_segment.is_none_or(|last| i > last);
+            if !separator {
+                if blank(span) {
+                    report.push(span.clone(), ResolveEditKind::BlankSegment);
+                    continue;
+                }
+                if let Some(separator) = pending_separator.take() {
+                    out.push(separator);
+                }
+                out.push_str(&input[span.clone()]);
+                wrote_segment = true;
+                continue;
+            }
+            let root = i == 0 && !prefix_has_separator;
+            if root {
+                // the root separator is kept even if nothing follows.
+                out.push_str(&input[span.start..span.start + 1]);
+                if span.len() > 1 {
+                    report.push(span.start + 1..span.end, ResolveEditKind::EmptySegment);
+                }
+            } else if trailing {
+                let kind = if wrote_segment { ResolveEditKind::TrailingSeparator } else { ResolveEditKind::EmptySegment };
+                report.push(span.clone(), kind);
+            } else if !wrote_segment || pending_separator.is_some() {
+                report.push(span.clone(), ResolveEditKind::EmptySegment);
+            } else {
+                pending_separator = input[span.clone()].chars().next();
+                if span.len() > 1 {
+                    report.push(span.start + 1..span.end, ResolveEditKind::EmptySegment);
+                }
+            }
+        }
+        (out, report)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(path: &str, variant: FlexPathVariant) -> Vec<(Range<usize>, ResolveEditKind)> {
+        FlexPath::new_with_report(path, variant).1.edits.into_iter().map(|edit| (edit.span, edit.kind)).collect()
+    }
+
+    #[test]
+    fn edits() {
+        let common = FlexPathVariant::Common;
+        let windows = FlexPathVariant::Windows;
+        assert_eq!(vec![(0..2, ResolveEditKind::ParentDir { removed: None }), (4..5, ResolveEditKind::Backslash)], kinds(r"../a\b", common));
+        assert_eq!(vec![(1..2, ResolveEditKind::EmptySegment)], kinds("//a", common));
+        assert_eq!(vec![(1..3, ResolveEditKind::TrailingSeparator)], kinds("a//", common));
+        assert!(kinds("/", common).is_empty());
+        assert_eq!(vec![(0..6, ResolveEditKind::PrefixRewrite { to: r"\\?\C:".to_owned() })], kinds("//?/C:/a", windows));
+        assert_eq!(vec![(0..2, ResolveEditKind::PrefixRewrite { to: r"\\".to_owned() })], kinds("//srv/share", windows));
+        assert!(kinds(r"\\srv\share\a", windows).is_empty());
+        assert!(kinds(r"C:\a/b", windows).is_empty());
+
+        // clean inputs are exactly those that resolve to themselves.
+        for path in ["a/b", "/a", "a/./b", "a/", "", ".", "/..", "a\\b", "a//b"] {
+            let (resolved, report) = FlexPath::new_with_report(path, common);
+            assert_eq!(report.is_clean(), resolved.to_string() == path, "{path}");
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let windows = FlexPathVariant::Windows;
+        let parsed = FlexPath::parse_with_spans(r"//srv/share/./x/", windows);
+        assert_eq!(Some(0..2), parsed.root);
+        assert_eq!(vec!["srv", "share", "x"], parsed.segments.iter().map(|s| s.text).collect::<Vec<_>>());
+        assert_eq!(14..15, parsed.segments[2].span);
+        let parsed = FlexPath::parse_with_spans("a/../../b", FlexPathVariant::Common);
+        assert_eq!(None, parsed.root);
+        assert_eq!(vec![SpannedSegment { span: 8..9, text: "b" }], parsed.segments);
+        assert_eq!(Some(0..2), FlexPath::parse_with_spans("C:a", windows).root);
+    }
+
+    #[test]
+    fn reparse() {
+        let inputs = [
+            ("/a/./bc/../d//e/", FlexPathVariant::Common),
+            (r"a\b\c", FlexPathVariant::Common),
+            (r"C:\x\y\..", FlexPathVariant::Windows),
+            (r"\\srv\share\x", FlexPathVariant::Windows),
+            (r"ab\c.d", FlexPathVariant::Windows),
+            (r"\\?\C:\a\b", FlexPathVariant::Windows),
+        ];
+        let texts = ["", "z", ".", "..", "/", ":", "q/r", "?"];
+        for (input, variant) in inputs {
+            let parsed = FlexPath::parse_with_spans(input, variant);
+            for start in 0..=input.len() {
+                for end in start..=input.len() {
+                    for text in texts {
+                        let edit = InputEdit { range: start..end, text };
+                        let edited = edit.apply(input);
+                        assert_eq!(FlexPath::parse_with_spans(&edited, variant), parsed.reparse(&edit, &edited), "{input:?} {edit:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn repairs() {
+        let common = FlexPathVariant::Common;
+        let windows = FlexPathVariant::Windows;
+        let repair = |input, variant| FlexPath::repair_separators(input, variant).0;
+        assert_eq!("a/b", repair("a////b///", common));
+        assert_eq!("/", repair("///", common));
+        assert_eq!("a", repair(" /a", common));
+        assert_eq!(r"\\srv\share", repair(r"\\\srv\\share\ ", windows));
+        assert_eq!(r"C:\a\b", repair(r"C:\\a\ \b", windows));
+        assert_eq!("C:a", repair("C:a", windows));
+        for input in ["a/b", "/", "../x", r"C:\a", ""] {
+            assert!(FlexPath::repair_separators(input, common).1.is_clean(), "{input}");
+        }
+        let (repaired, report) = FlexPath::repair_separators(r"\\\srv", windows);
+        assert_eq!(r"\\srv", repaired);
+        assert_eq!(vec![ResolveEdit { span: 2..3, kind: ResolveEditKind::EmptySegment }], report.edits);
+    }
+}
@@ -0,0 +1,132 @@
+/*!
+This module contains the `RewriteRules` engine.
+*/
+
+use super::{trie, FlexGlob, FlexPath, FlexPathVariant};
+
+enum Rule {
+    Prefix { from: FlexPath, to: FlexPath },
+    Glob { glob: FlexGlob, replacement: String, variant: FlexPathVariant },
+}
+
+/// An ordered list of rules rewriting paths, such as translating
+/// host paths into container paths.
+///
+/// A rule either replaces an exact prefix, compared by whole segments,
+/// or matches a [`FlexGlob`] and builds the result from a replacement
+/// template where `$N` or `${N}` is substituted by the `N`-th wildcard
+/// capture (`$0` being the whole path) and `$$` is a literal dollar sign.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexGlob, FlexPath, FlexPathVariant, RewriteRules};
+/// let common = FlexPathVariant::Common;
+/// let mut rules = RewriteRules::new();
+/// rules.add_prefix(FlexPath::new_common("/home/me/project"), FlexPath::new_common("/workspace"));
+/// rules.add_glob(FlexGlob::new("/tmp/*/out/**", common).unwrap(), "/cache/$1$2", common);
+/// assert_eq!("/workspace/src/a.rs", rules.rewrite(&FlexPath::new_common("/home/me/project/src/a.rs")).unwrap().to_string());
+/// assert_eq!("/cache/build/x.o", rules.rewrite(&FlexPath::new_common("/tmp/build/out/x.o")).unwrap().to_string());
+/// assert_eq!(None, rules.rewrite(&FlexPath::new_common("/etc/hosts")));
+/// ```
+#[derive(Default)]
+pub struct RewriteRules {
+    rules: Vec<Rule>,
+}
+
+impl RewriteRules {
+    /// Constructs an empty rule list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Indicates whether there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Appends a rule replacing the `from` prefix by `to`.
+    pub fn add_prefix(&mut self, from: FlexPath, to: FlexPath) -> &mut Self {
+        self.rules.push(Rule::Prefix { from, to });
+        self
+    }
+
+    /// Appends a rule replacing paths matching `glob` by the `replacement`
+    /// template, resolved as a path of the given `variant`.
+    pub fn add_glob(&mut self, glob: FlexGlob, replacement: &str, variant: FlexPathVariant) -> &mut Self {
+        self.rules.push(Rule::Glob { glob, replacement: replacement.to_owned(), variant });
+        self
+    }
+
+    /// Rewrites a path with the first matching rule, or returns `None`
+    /// if no rule matches.
+    pub fn rewrite(&self, path: &FlexPath) -> Option<FlexPath> {
+        self.rules.iter().find_map(|rule| match rule {
+            Rule::Prefix { from, to } => {
+                let keys = trie::keys(path);
+                let from_keys = trie::keys(from);
+                keys.starts_with(&from_keys).then(|| to.join_segments(&keys[from_keys.len()..]))
+            },
+            Rule::Glob { glob, replacement, variant } => {
+                glob.captures(path).map(|captures| FlexPath::new(&substitute(replacement, &captures), *variant))
+            },
+        })
+    }
+}
+
+/// Substitutes `$N`, `${N}` and `$$` in a replacement template.
+pub(crate) fn substitute(template: &str, captures: &[String]) -> String {
+    let mut r = String::new();
+    let mut rest = template;
+    while let Some(i) = rest.find('$') {
+        r.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            r.push('$');
+            rest = after;
+            continue;
+        }
+        let (digits, after) = match rest.strip_prefix('{').and_then(|s| s.split_once('}')) {
+            Some((digits, after)) => (digits, after),
+            None => rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len())),
+        };
+        match digits.parse::<usize>() {
+            Ok(n) => r.push_str(captures.get(n).map_or("", |c| c.as_str())),
+            Err(_) => {
+                r.push('$');
+                continue;
+            },
+        }
+        rest = after;
+    }
+    r + rest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn templates() {
+        let captures = ["all".to_owned(), "a".to_owned(), "b".to_owned()];
+        assert_eq!("a-b/all$", substitute("$1-${2}/$0$$", &captures));
+        assert_eq!("x$y", substitute("x$y", &captures));
+        assert_eq!("a1", substitute("${1}1", &captures));
+    }
+
+    #[test]
+    fn rule_order() {
+        let windows = FlexPathVariant::Windows;
+        let mut rules = RewriteRules::new();
+        rules.add_prefix(FlexPath::new_common("/src/vendor"), FlexPath::new(r"D:\vendor", windows));
+        rules.add_prefix(FlexPath::new_common("/src"), FlexPath::new(r"C:\src", windows));
+        assert_eq!(r"D:\vendor\a", rules.rewrite(&FlexPath::new_common("/src/vendor/a")).unwrap().to_string());
+        assert_eq!(r"C:\src\vendors", rules.rewrite(&FlexPath::new_common("/src/vendors")).unwrap().to_string());
+        assert_eq!(r"C:\src", rules.rewrite(&FlexPath::new_common("/src")).unwrap().to_string());
+    }
+}
@@ -0,0 +1,110 @@
+/*!
+This module contains the `Segments` iterator over the parts of a path.
+*/
+
+use super::{flexible, FlexPath};
+use std::{iter::FusedIterator, str::Split};
+
+/// An iterator over the root and segments of a [`FlexPath`], borrowing
+/// from the path. See [`FlexPath::segments`].
+#[derive(Clone, Debug)]
+pub struct Segments<'a> {
+    root: Option<&'a str>,
+    rest: Option<Split<'a, char>>,
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if let Some(root) = self.root.take() {
+            return Some(root);
+        }
+        self.rest.as_mut()?.next()
+    }
+}
+
+impl DoubleEndedIterator for Segments<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rest.as_mut().and_then(|rest| rest.next_back()).or_else(|| self.root.take())
+    }
+}
+
+impl FusedIterator for Segments<'_> {}
+
+impl FlexPath {
+    /// Iterates over the parts of the path without allocating: the root
+    /// first, if the path has one, and then each segment.
+    ///
+    /// The root is yielded as stored, with forward slashes after any
+    /// prefix, as in `/`, `C:/` or `\\srv/share/`, which
+    /// [`.with_root`](Self::with_root) accepts. The segments never include
+    /// empty segments or `.`, as the path is resolved.
+    ///
+    /// For a path of the native variant, the segments are those yielded as
+    /// `Normal` components by [`.std_components`](Self::std_components),
+    /// which splits the root into a prefix and a root directory instead.
+    ///
+    /// `&FlexPath` implements `IntoIterator` with this iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant};
+    /// let path = FlexPath::new(r"C:\Users\me", FlexPathVariant::Windows);
+    /// assert_eq!(vec!["C:/", "Users", "me"], path.segments().collect::<Vec<_>>());
+    /// let path = FlexPath::new_common("a/b");
+    /// let mut names = Vec::new();
+    /// for segment in &path {
+    ///     names.push(segment);
+    /// }
+    /// assert_eq!(vec!["a", "b"], names);
+    /// ```
+    pub fn segments(&self) -> Segments<'_> {
+        let root_len = flexible::root_len(&self.0, self.1);
+        let rest = &self.0[root_len..];
+        Segments {
+            root: (root_len != 0).then(|| &self.0[..root_len]),
+            rest: (!rest.is_empty()).then(|| rest.split('/')),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a FlexPath {
+    type Item = &'a str;
+    type IntoIter = Segments<'a>;
+
+    fn into_iter(self) -> Segments<'a> {
+        self.segments()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn roots_and_segments() {
+        let windows = FlexPathVariant::Windows;
+        let segments = |path: &FlexPath| path.segments().map(str::to_owned).collect::<Vec<_>>();
+        assert!(segments(&FlexPath::new_common("")).is_empty());
+        assert_eq!(vec!["/"], segments(&FlexPath::new_common("/")));
+        assert_eq!(vec![r"\\srv/share/", "a"], segments(&FlexPath::new(r"\\srv\share\a", windows)));
+        assert_eq!(vec!["a", "b"], segments(&FlexPath::new(r"a\b", windows)));
+        assert_eq!(vec!["b", "a", "/"], FlexPath::new_common("/a/b").segments().rev().collect::<Vec<_>>());
+
+        // the root and segments rebuild the path.
+        for path in [FlexPath::new_common("/x/y"), FlexPath::new(r"\\?\C:\x", windows), FlexPath::new(r"\\srv\share\x", windows), FlexPath::new("C:a", windows)] {
+            assert_eq!(path, FlexPath::from_n(&path, path.variant()));
+        }
+
+        let path = FlexPath::new(if FlexPathVariant::NATIVE == windows { r"C:\a\b" } else { "/a/b" }, FlexPathVariant::NATIVE);
+        let components = path.std_components().unwrap();
+        let normal = components.iter().filter_map(|component| match component {
+            std::path::Component::Normal(name) => name.to_str(),
+            _ => None,
+        }).collect::<Vec<_>>();
+        assert_eq!(normal, path.segments().skip(1).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,228 @@
+/*!
+This module contains the `PathSet` collection, containment-aware
+deduplication and case collision detection.
+*/
+
+use super::{case, cmp, flexible, trie, FlexPath, FlexPathVariant, PathCmpOptions, PathTrie};
+use std::collections::HashMap;
+
+/// A set of `FlexPath`s organized by path segments.
+///
+/// Insertion, lookup and subtree queries take time proportional to the
+/// number of segments of the given path, independently of the set size.
+/// Iteration visits paths in segment order, with directories before
+/// their descendants.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, PathSet};
+/// let mut set = PathSet::new();
+/// set.insert(FlexPath::new_common("/a/b"));
+/// set.insert(FlexPath::new_common("/a/c/d"));
+/// assert!(set.contains_descendant_of(&FlexPath::new_common("/a/c")));
+/// assert_eq!(2, set.iter_under(&FlexPath::new_common("/a")).count());
+/// ```
+#[derive(Default)]
+pub struct PathSet {
+    root: trie::Node<()>,
+    len: usize,
+}
+
+impl PathSet {
+    /// Constructs an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of paths in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the set contains no paths.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a path to the set, returning whether it was not already present.
+    pub fn insert(&mut self, path: FlexPath) -> bool {
+        let keys = trie::node_keys(&path);
+        let new = self.root.insert(&keys, path, ()).is_none();
+        if new {
+            self.len += 1;
+        }
+        new
+    }
+
+    /// Indicates whether the set contains `path`.
+    pub fn contains(&self, path: &FlexPath) -> bool {
+        self.root.get(&trie::node_keys(path)).is_some_and(|node| node.entry().is_some())
+    }
+
+    /// Removes `path` from the set, returning whether it was present.
+    pub fn remove(&mut self, path: &FlexPath) -> bool {
+        let removed = self.root.remove(&trie::node_keys(path)).is_some();
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Indicates whether the set contains any path strictly under `dir`.
+    pub fn contains_descendant_of(&self, dir: &FlexPath) -> bool {
+        self.root.get(&trie::node_keys(dir)).is_some_and(|node| node.has_children())
+    }
+
+    /// Iterates over the paths in the set that are `dir` itself or under it.
+    pub fn iter_under(&self, dir: &FlexPath) -> impl Iterator<Item = &FlexPath> {
+        self.root.get(&trie::node_keys(dir)).into_iter().flat_map(|node| node.iter()).map(|(path, _)| path)
+    }
+
+    /// Removes `dir` and every path under it, returning how many paths were removed.
+    pub fn remove_subtree(&mut self, dir: &FlexPath) -> usize {
+        let removed = self.root.remove_subtree(&trie::node_keys(dir)).map_or(0, |node| node.count());
+        self.len -= removed;
+        removed
+    }
+
+    /// Iterates over all paths in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &FlexPath> {
+        self.root.iter().map(|(path, _)| path)
+    }
+}
+
+impl FromIterator<FlexPath> for PathSet {
+    fn from_iter<T: IntoIterator<Item = FlexPath>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<FlexPath> for PathSet {
+    fn extend<T: IntoIterator<Item = FlexPath>>(&mut self, iter: T) {
+        for path in iter {
+            self.insert(path);
+        }
+    }
+}
+
+/// Removes paths that are duplicates or descendants of other paths in
+/// the list, keeping the remaining paths in their first-appearance order.
+///
+/// This is useful for reducing a list of roots to scan, where scanning
+/// a directory already covers everything under it. Paths are compared
+/// according to `options`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{dedup_paths, FlexPath, PathCmpOptions};
+/// let roots = ["/a/b", "/c", "/a", "/c/", "/a/b/c"].map(FlexPath::new_common);
+/// let roots = dedup_paths(roots, PathCmpOptions::default());
+/// assert_eq!(vec![FlexPath::new_common("/c"), FlexPath::new_common("/a")], roots);
+/// ```
+pub fn dedup_paths<I: IntoIterator<Item = FlexPath>>(paths: I, options: PathCmpOptions) -> Vec<FlexPath> {
+    let paths: Vec<FlexPath> = paths.into_iter().collect();
+    let key = |path: &FlexPath| FlexPath::from_resolved(cmp::key(path, options), path.1);
+    let mut first = PathTrie::new();
+    for (i, path) in paths.iter().enumerate().rev() {
+        first.insert(key(path), i);
+    }
+    paths.into_iter().enumerate().filter(|(i, path)| {
+        let key = key(path);
+        first.get(&key) == Some(i) && !first.has_strict_ancestor(&key)
+    }).map(|(_, path)| path).collect()
+}
+
+/// Finds paths that would collide on a case-insensitive file system,
+/// such as `README.md` and `readme.md`, returning each group of
+/// colliding spellings in first-appearance order.
+///
+/// Directories are considered too, so that `Docs/a` and `docs/b`
+/// report the `Docs` and `docs` directories, which would be merged. Names
+/// are compared with the crate's Unicode case folding, and prefixes that
+/// only differ in the case of a drive letter do not collide.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{find_case_collisions, FlexPath};
+/// let paths = ["README.md", "src/a.rs", "readme.md", "Src/b.rs"].map(FlexPath::new_common);
+/// let collisions = find_case_collisions(&paths);
+/// assert_eq!(vec![vec!["README.md", "readme.md"], vec!["src", "Src"]], collisions.iter().map(|g| g.iter().map(|p| p.to_string()).collect::<Vec<_>>()).collect::<Vec<_>>());
+/// ```
+pub fn find_case_collisions<I, P>(paths: I) -> Vec<Vec<FlexPath>>
+where
+    I: IntoIterator<Item = P>,
+    P: std::borrow::Borrow<FlexPath>,
+{
+    let mut groups: Vec<Vec<FlexPath>> = Vec::new();
+    let mut spellings: Vec<Vec<String>> = Vec::new();
+    let mut index = HashMap::<(FlexPathVariant, String), usize>::new();
+    for path in paths {
+        let path = path.borrow();
+        let keys = trie::keys(path);
+        let empty = FlexPath::from_resolved(String::new(), path.1);
+        for n in 1..=keys.len() {
+            let prefix = empty.join_segments(&keys[..n]);
+            let text = flexible::canonical(&prefix.0, prefix.1);
+            let i = *index.entry((prefix.1, case::fold(&text))).or_insert_with(|| {
+                groups.push(Vec::new());
+                spellings.push(Vec::new());
+                groups.len() - 1
+            });
+            if !spellings[i].contains(&text) {
+                spellings[i].push(text);
+                groups[i].push(prefix);
+            }
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn subtrees() {
+        let mut set: PathSet = ["/a", "/a/b", "/a/b/c", "/ab", "a"].into_iter().map(FlexPath::new_common).collect();
+        assert_eq!(5, set.len());
+        assert!(!set.insert(FlexPath::new_common("/a/b/")));
+        assert!(set.contains(&FlexPath::new_common("a")));
+        assert!(!set.contains(&FlexPath::new_common("/b")));
+        assert_eq!(vec!["/a", "/a/b", "/a/b/c"], set.iter_under(&FlexPath::new_common("/a")).map(|p| p.to_string()).collect::<Vec<_>>());
+        assert_eq!(2, set.remove_subtree(&FlexPath::new_common("/a/b")));
+        assert!(!set.contains_descendant_of(&FlexPath::new_common("/a")));
+        assert!(set.remove(&FlexPath::new_common("/a")));
+        assert_eq!(2, set.len());
+
+        let windows = FlexPathVariant::Windows;
+        let set: PathSet = [r"\\srv\share", r"\\srv\share\x"].into_iter().map(|p| FlexPath::new(p, windows)).collect();
+        assert_eq!(2, set.iter_under(&FlexPath::new(r"\\srv\share\", windows)).count());
+    }
+
+    #[test]
+    fn dedup() {
+        let windows = FlexPathVariant::Windows;
+        let roots = [r"C:\Src\a", r"c:\src", r"D:\", r"d:\x"].map(|p| FlexPath::new(p, windows));
+        let roots = dedup_paths(roots, PathCmpOptions::for_variant(windows));
+        assert_eq!(vec![FlexPath::new(r"c:\src", windows), FlexPath::new(r"D:\", windows)], roots);
+        assert_eq!(2, dedup_paths(roots, PathCmpOptions::default()).len());
+        let roots = [r"C:\a", r"c:\a\b", r"c:\A\b"].map(|p| FlexPath::new(p, windows));
+        assert_eq!(vec![FlexPath::new(r"C:\a", windows), FlexPath::new(r"c:\A\b", windows)], dedup_paths(roots, PathCmpOptions::default()));
+    }
+
+    #[test]
+    fn case_collisions() {
+        let windows = FlexPathVariant::Windows;
+        let paths = [r"C:\a\STRASSE", r"c:\a\straße", r"C:\A", r"C:\b"].map(|p| FlexPath::new(p, windows));
+        let collisions = find_case_collisions(paths);
+        assert_eq!(vec![vec![FlexPath::new(r"C:\a", windows), FlexPath::new(r"C:\A", windows)], vec![FlexPath::new(r"C:\a\STRASSE", windows), FlexPath::new(r"c:\a\straße", windows)]], collisions);
+        assert!(find_case_collisions([FlexPath::new_common("a"), FlexPath::new_common("a/")]).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+/*!
+This module contains the buffer in which a `FlexPath` stores its text.
+
+Without the `compact` feature, this is a `String`. With it, paths of up
+to 62 bytes are stored inline, without a heap allocation, and longer
+paths spill onto the heap.
+*/
+
+#[cfg(not(feature = "compact"))]
+pub type Storage = String;
+
+#[cfg(feature = "compact")]
+pub use compact::Storage;
+
+#[cfg(feature = "compact")]
+mod compact {
+    use std::borrow::Borrow;
+    use std::hash::{Hash, Hasher};
+    use std::ops::Deref;
+
+    /// The number of bytes stored without a heap allocation.
+    pub const INLINE_CAPACITY: usize = 62;
+
+    #[derive(Clone)]
+    pub enum Storage {
+        Inline { len: u8, bytes: [u8; INLINE_CAPACITY] },
+        Heap(String),
+    }
+
+    impl Storage {
+        pub const fn new() -> Self {
+            Self::Inline { len: 0, bytes: [0; INLINE_CAPACITY] }
+        }
+
+        pub fn as_str(&self) -> &str {
+            match self {
+                // SAFETY: the inline bytes are only ever written from
+                // whole `&str` values, so they are valid UTF-8.
+                Self::Inline { len, bytes } => unsafe { std::str::from_utf8_unchecked(&bytes[..*len as usize]) },
+                Self::Heap(s) => s,
+            }
+        }
+
+        pub fn push_str(&mut self, s: &str) {
+            match self {
+                Self::Inline { len, bytes } if *len as usize + s.len() <= INLINE_CAPACITY => {
+                    bytes[*len as usize..*len as usize + s.len()].copy_from_slice(s.as_bytes());
+                    *len += s.len() as u8;
+                },
+                Self::Inline { .. } => {
+                    let mut heap = String::with_capacity(self.len() + s.len());
+                    heap.push_str(self.as_str());
+                    heap.push_str(s);
+                    *self = Self::Heap(heap);
+                },
+                Self::Heap(heap) => heap.push_str(s),
+            }
+        }
+
+        pub fn push(&mut self, ch: char) {
+            self.push_str(ch.encode_utf8(&mut [0; 4]));
+        }
+
+        pub fn clear(&mut self) {
+            match self {
+                Self::Inline { len, .. } => *len = 0,
+                Self::Heap(heap) => heap.clear(),
+            }
+        }
+
+        pub fn capacity(&self) -> usize {
+            match self {
+                Self::Inline { .. } => INLINE_CAPACITY,
+                Self::Heap(heap) => heap.capacity(),
+            }
+        }
+
+        pub fn reserve(&mut self, additional: usize) {
+            match self {
+                Self::Inline { len, .. } if *len as usize + additional <= INLINE_CAPACITY => {},
+                Self::Inline { .. } => {
+                    let mut heap = String::with_capacity(self.len() + additional);
+                    heap.push_str(self.as_str());
+                    *self = Self::Heap(heap);
+                },
+                Self::Heap(heap) => heap.reserve(additional),
+            }
+        }
+
+        pub fn shrink_to_fit(&mut self) {
+            match self {
+                Self::Heap(heap) if heap.len() <= INLINE_CAPACITY => *self = Self::from(heap.as_str()),
+                Self::Heap(heap) => heap.shrink_to_fit(),
+                Self::Inline { .. } => {},
+            }
+        }
+    }
+
+    impl Default for Storage {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Deref for Storage {
+        type Target = str;
+
+        fn deref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl Borrow<str> for Storage {
+        fn borrow(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl From<&str> for Storage {
+        fn from(s: &str) -> Self {
+            let mut r = Self::new();
+            r.push_str(s);
+            r
+        }
+    }
+
+    impl From<String> for Storage {
+        fn from(s: String) -> Self {
+            if s.len() <= INLINE_CAPACITY { Self::from(s.as_str()) } else { Self::Heap(s) }
+        }
+    }
+
+    impl From<Storage> for String {
+        fn from(s: Storage) -> Self {
+            match s {
+                Storage::Inline { .. } => s.as_str().to_owned(),
+                Storage::Heap(heap) => heap,
+            }
+        }
+    }
+
+    impl PartialEq for Storage {
+        fn eq(&self, other: &Self) -> bool {
+            self.as_str() == other.as_str()
+        }
+    }
+
+    impl Eq for Storage {}
+
+    impl PartialOrd for Storage {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Storage {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.as_str().cmp(other.as_str())
+        }
+    }
+
+    impl Hash for Storage {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.as_str().hash(state);
+        }
+    }
+
+    impl std::fmt::Debug for Storage {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.as_str().fmt(f)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn spill() {
+            let mut s = Storage::from("a".repeat(INLINE_CAPACITY - 1).as_str());
+            s.push('b');
+            assert!(matches!(s, Storage::Inline { .. }));
+            s.push('é');
+            assert!(matches!(s, Storage::Heap(_)));
+            assert_eq!("a".repeat(INLINE_CAPACITY - 1) + "bé", s.as_str());
+            s.clear();
+            s.push_str("c");
+            s.shrink_to_fit();
+            assert_eq!(Storage::from("c"), s);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+/*!
+This module contains segment-aligned suffix matching and the
+`SuffixIndex` for locating files by a partial path.
+*/
+
+use super::{case, flexible, FlexPath, FlexPathVariant, PathCmpOptions};
+use std::collections::HashMap;
+
+/// Returns the segments of a path after its root, compared under `options`.
+fn segment_keys(path: &FlexPath, options: PathCmpOptions) -> Vec<String> {
+    let root_len = flexible::root_len(&path.0, path.1);
+    path.0[root_len..]
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| if options.case_sensitive { s.to_owned() } else { case::fold(s) })
+        .collect()
+}
+
+impl FlexPath {
+    /// Indicates whether the path ends with the segments of `suffix`,
+    /// resolved with the same variant and compared whole, according to
+    /// the conventions of the variant. Any root of `suffix` is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let path = FlexPath::new_common("/usr/include/foo/bar.h");
+    /// assert!(path.ends_with_path("foo/bar.h"));
+    /// assert!(path.ends_with_path("include/./foo/bar.h"));
+    /// assert!(!path.ends_with_path("oo/bar.h"));
+    /// ```
+    pub fn ends_with_path(&self, suffix: &str) -> bool {
+        self.ends_with_path_with(suffix, PathCmpOptions::for_variant(self.1))
+    }
+
+    /// Indicates whether the path ends with the segments of `suffix` like
+    /// [`.ends_with_path`](Self::ends_with_path), comparing letter case
+    /// according to `options`.
+    pub fn ends_with_path_with(&self, suffix: &str, options: PathCmpOptions) -> bool {
+        let suffix = segment_keys(&FlexPath::new(suffix, self.1), options);
+        segment_keys(self, options).ends_with(&suffix)
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    /// The candidates ending with the segments leading to this node.
+    candidates: Vec<usize>,
+}
+
+/// An index of candidate paths by their reversed segments, finding every
+/// candidate that ends with a partial path, such as an `#include` path or
+/// the query of a "go to file" feature, in time proportional to the
+/// number of segments of the partial path and of the results.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, FlexPathVariant, SuffixIndex};
+/// let mut index = SuffixIndex::new(FlexPathVariant::Common);
+/// for path in ["/src/include/foo/bar.h", "/vendor/foo/bar.h", "/src/bar.h"] {
+///     index.insert(FlexPath::new_common(path));
+/// }
+/// assert_eq!(2, index.find("foo/bar.h").count());
+/// assert_eq!(vec!["/src/include/foo/bar.h"], index.find("include/foo/bar.h").map(|p| p.to_string()).collect::<Vec<_>>());
+/// ```
+pub struct SuffixIndex {
+    root: Node,
+    paths: Vec<FlexPath>,
+    variant: FlexPathVariant,
+    options: PathCmpOptions,
+}
+
+impl SuffixIndex {
+    /// Constructs an empty index resolving partial paths with `variant`
+    /// and comparing segments according to its conventions.
+    pub fn new(variant: FlexPathVariant) -> Self {
+        Self::with_options(variant, PathCmpOptions::for_variant(variant))
+    }
+
+    /// Constructs an empty index resolving partial paths with `variant`
+    /// and comparing segments according to `options`.
+    pub fn with_options(variant: FlexPathVariant, options: PathCmpOptions) -> Self {
+        Self { root: Node::default(), paths: Vec::new(), variant, options }
+    }
+
+    /// Returns the number of candidates in the index.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Indicates whether the index contains no candidates.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Adds a candidate path, returning its index in insertion order.
+    pub fn insert(&mut self, path: FlexPath) -> usize {
+        let index = self.paths.len();
+        let mut node = &mut self.root;
+        node.candidates.push(index);
+        for key in segment_keys(&path, self.options).into_iter().rev() {
+            node = node.children.entry(key).or_default();
+            node.candidates.push(index);
+        }
+        self.paths.push(path);
+        index
+    }
+
+    /// Returns the candidate at an index returned by [`.insert`](Self::insert).
+    pub fn get(&self, index: usize) -> Option<&FlexPath> {
+        self.paths.get(index)
+    }
+
+    /// Iterates over the candidates ending with the segments of `suffix`,
+    /// in insertion order. Any root of `suffix` is ignored.
+    pub fn find(&self, suffix: &str) -> impl Iterator<Item = &FlexPath> {
+        let keys = segment_keys(&FlexPath::new(suffix, self.variant), self.options);
+        let node = keys.iter().rev().try_fold(&self.root, |node, key| node.children.get(key));
+        node.into_iter().flat_map(|node| &node.candidates).map(|&i| &self.paths[i])
+    }
+}
+
+impl FromIterator<FlexPath> for SuffixIndex {
+    /// Indexes paths with the default variant of [`FlexPath::default_variant`].
+    fn from_iter<I: IntoIterator<Item = FlexPath>>(iter: I) -> Self {
+        let mut index = Self::new(FlexPath::default_variant());
+        for path in iter {
+            index.insert(path);
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn suffixes() {
+        let windows = FlexPathVariant::Windows;
+        let path = FlexPath::new(r"C:\Src\Include\Foo\Bar.h", windows);
+        assert!(path.ends_with_path(r"include\foo\bar.h"));
+        assert!(path.ends_with_path(r"C:\src\include\foo\bar.h"));
+        assert!(path.ends_with_path(""));
+        assert!(!path.ends_with_path_with(r"include\foo\bar.h", PathCmpOptions { case_sensitive: true }));
+        assert!(!FlexPath::new_common("a/b").ends_with_path("x/a/b"));
+
+        let mut index = SuffixIndex::new(windows);
+        let a = index.insert(path.clone());
+        let b = index.insert(FlexPath::new(r"\\srv\share\foo\bar.h", windows));
+        assert_eq!(2, index.len());
+        assert_eq!(vec![&path, index.get(b).unwrap()], index.find("FOO/bar.h").collect::<Vec<_>>());
+        assert_eq!(vec![index.get(a).unwrap()], index.find("include/foo/bar.h").collect::<Vec<_>>());
+        assert_eq!(0, index.find("share/foo/bar.h").count());
+        assert_eq!(0, index.find("baz.h").count());
+        assert_eq!(2, index.find("").count());
+    }
+}
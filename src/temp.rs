@@ -0,0 +1,131 @@
+/*!
+This module contains the generation of random child names for
+temporary files and directories.
+*/
+
+use super::{FlexPath, FlexPathError, SegmentPolicy};
+
+/// The characters of the random part of a name, excluding uppercase
+/// letters so that names do not collide on case-insensitive file systems.
+const ALPHABET: &[u8; 36] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// The number of random characters in a name.
+const RANDOM_LEN: usize = 8;
+
+/// The number of names tried before giving up on creating an entry.
+#[cfg(feature = "fs")]
+const ATTEMPTS: usize = 64;
+
+impl FlexPath {
+    /// Returns a child of this path named `prefix`, followed by eight random
+    /// lowercase letters and digits drawn from `rng`, followed by `suffix`,
+    /// such as `build-k3x9q0za.tmp`. The path keeps this path's variant.
+    ///
+    /// `rng` is called once per character and may be any source of random
+    /// numbers, such as a seeded generator in tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidSegment`] if the name is not a
+    /// literal segment as described by [`FlexPath::push_segment`], such as
+    /// when `prefix` or `suffix` contains a separator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let mut state = 0u64;
+    /// let rng = || { state += 1; state };
+    /// let path = FlexPath::new_common("/tmp").random_child("build-", ".tmp", rng).unwrap();
+    /// assert_eq!("/tmp/build-bcdefghi.tmp", path.to_string());
+    /// assert!(FlexPath::new_common("/tmp").random_child("a/", "", || 0).is_err());
+    /// ```
+    pub fn random_child(&self, prefix: &str, suffix: &str, mut rng: impl FnMut() -> u64) -> Result<FlexPath, FlexPathError> {
+        let mut name = String::with_capacity(prefix.len() + RANDOM_LEN + suffix.len());
+        name.push_str(prefix);
+        for _ in 0..RANDOM_LEN {
+            name.push(ALPHABET[(rng() % ALPHABET.len() as u64) as usize] as char);
+        }
+        name.push_str(suffix);
+        let mut r = self.clone();
+        r.push_segment(&name, SegmentPolicy::Reject)?;
+        Ok(r)
+    }
+
+    /// Creates a new file under this directory with a name generated as by
+    /// [`.random_child`](Self::random_child) from system randomness,
+    /// returning its path and the file opened for writing.
+    ///
+    /// The file is created atomically, never opening an existing entry;
+    /// names that are taken are retried with new random characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant, if
+    /// the name is not a literal segment, if the file cannot be created or
+    /// if every attempted name is taken.
+    #[cfg(feature = "fs")]
+    pub fn create_random_file(&self, prefix: &str, suffix: &str) -> std::io::Result<(FlexPath, std::fs::File)> {
+        self.create_random(prefix, suffix, |path| std::fs::OpenOptions::new().write(true).create_new(true).open(path))
+    }
+
+    /// Creates a new directory under this directory with a name generated
+    /// as by [`.random_child`](Self::random_child) from system randomness,
+    /// returning its path.
+    ///
+    /// The directory is created atomically, never reusing an existing entry;
+    /// names that are taken are retried with new random characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path's variant is not the native variant, if
+    /// the name is not a literal segment, if the directory cannot be created
+    /// or if every attempted name is taken.
+    #[cfg(feature = "fs")]
+    pub fn create_random_dir(&self, prefix: &str, suffix: &str) -> std::io::Result<FlexPath> {
+        self.create_random(prefix, suffix, |path| std::fs::create_dir(path)).map(|(path, _)| path)
+    }
+
+    #[cfg(feature = "fs")]
+    fn create_random<T>(&self, prefix: &str, suffix: &str, create: impl Fn(&std::path::Path) -> std::io::Result<T>) -> std::io::Result<(FlexPath, T)> {
+        use std::{hash::{BuildHasher, Hasher}, io};
+        // the hasher keys of a new `RandomState` are seeded by the system.
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        let mut rng = || {
+            hasher.write_u8(0);
+            hasher.finish()
+        };
+        for _ in 0..ATTEMPTS {
+            let path = self.random_child(prefix, suffix, &mut rng).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+            match create(&path.native_path()?) {
+                Ok(created) => return Ok((path, created)),
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {},
+                Err(error) => return Err(error),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::AlreadyExists, "hydroperx_path: could not find an unused random name"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn random_names() {
+        let windows = FlexPathVariant::Windows;
+        let path = FlexPath::new("", windows).random_child("", ".log", || 35).unwrap();
+        assert_eq!("99999999.log", path.to_string());
+        assert!(FlexPath::new("", windows).random_child("C:", "", || 0).is_err());
+        assert_eq!(r"C:\x\aaaaaaaa", FlexPath::new("C:/x", windows).random_child("", "", || 36).unwrap().to_string());
+
+        #[cfg(feature = "fs")] {
+            let dir = FlexPath::new_native(std::env::temp_dir().to_str().unwrap()).create_random_dir("hydroperx-", "").unwrap();
+            let (file, _) = dir.create_random_file("f-", ".txt").unwrap();
+            assert!(file.is_file().unwrap());
+            assert!(file.base_name().starts_with("f-") && file.base_name().len() == 14);
+            std::fs::remove_dir_all(dir.native_path().unwrap()).unwrap();
+        }
+    }
+}
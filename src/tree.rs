@@ -0,0 +1,142 @@
+/*!
+This module contains the `PathTree` hierarchy.
+*/
+
+use super::{trie, FlexPath, FlexPathVariant};
+use std::collections::BTreeMap;
+
+/// A directory hierarchy built from a flat list of paths.
+///
+/// Each node is a segment, or a root such as `/` or `C:\` at the top
+/// level. Directories that are not listed themselves are created
+/// implicitly. The `Display` implementation prints the hierarchy in the
+/// style of the `tree` command.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, PathTree};
+/// let tree: PathTree = ["src/lib.rs", "src/a/b.rs", "Cargo.toml"].into_iter().map(FlexPath::new_common).collect();
+/// assert_eq!(
+///     "Cargo.toml\nsrc\n├── a\n│   └── b.rs\n└── lib.rs\n",
+///     tree.to_string(),
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PathTree {
+    root: PathTreeNode,
+}
+
+/// A node of a [`PathTree`].
+#[derive(Clone, Debug, Default)]
+pub struct PathTreeNode {
+    name: String,
+    path: Option<FlexPath>,
+    children: BTreeMap<String, PathTreeNode>,
+}
+
+impl PathTree {
+    /// Constructs an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a path to the tree, creating its ancestors as needed.
+    pub fn insert(&mut self, path: FlexPath) {
+        let variant = path.variant();
+        let mut node = &mut self.root;
+        for key in trie::keys(&path) {
+            node = node.children.entry(key.clone()).or_insert_with(|| PathTreeNode {
+                name: display_name(&key, variant),
+                ..Default::default()
+            });
+        }
+        node.path = Some(path);
+    }
+
+    /// Returns the top-level nodes in segment order.
+    pub fn roots(&self) -> impl Iterator<Item = &PathTreeNode> {
+        self.root.children()
+    }
+
+    /// Visits every node depth-first in segment order, along with its depth,
+    /// top-level nodes having depth zero.
+    pub fn walk(&self) -> impl Iterator<Item = (usize, &PathTreeNode)> {
+        let mut stack: Vec<(usize, &PathTreeNode)> = self.root.children.values().rev().map(|node| (0, node)).collect();
+        std::iter::from_fn(move || {
+            let (depth, node) = stack.pop()?;
+            stack.extend(node.children.values().rev().map(|child| (depth + 1, child)));
+            Some((depth, node))
+        })
+    }
+}
+
+impl PathTreeNode {
+    /// Returns the segment of the node, or the root for a top-level root node.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the node if it was inserted explicitly.
+    pub fn path(&self) -> Option<&FlexPath> {
+        self.path.as_ref()
+    }
+
+    /// Indicates whether the node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns the children of the node in segment order.
+    pub fn children(&self) -> impl Iterator<Item = &PathTreeNode> {
+        self.children.values()
+    }
+
+    fn fmt_children(&self, f: &mut std::fmt::Formatter<'_>, indent: &str) -> std::fmt::Result {
+        let count = self.children.len();
+        for (i, child) in self.children.values().enumerate() {
+            let last = i + 1 == count;
+            writeln!(f, "{indent}{}{}", if last { "└── " } else { "├── " }, child.name)?;
+            child.fmt_children(f, &(indent.to_owned() + if last { "    " } else { "│   " }))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<FlexPath> for PathTree {
+    fn from_iter<T: IntoIterator<Item = FlexPath>>(iter: T) -> Self {
+        let mut tree = Self::new();
+        for path in iter {
+            tree.insert(path);
+        }
+        tree
+    }
+}
+
+impl std::fmt::Display for PathTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in self.roots() {
+            writeln!(f, "{}", node.name)?;
+            node.fmt_children(f, "")?;
+        }
+        Ok(())
+    }
+}
+
+fn display_name(key: &str, variant: FlexPathVariant) -> String {
+    if variant == FlexPathVariant::Windows { key.replace('/', "\\") } else { key.to_owned() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn roots_and_walk() {
+        let windows = FlexPathVariant::Windows;
+        let tree: PathTree = [r"C:\a\b", r"C:\a", r"D:\c"].into_iter().map(|p| FlexPath::new(p, windows)).collect();
+        assert_eq!("C:\\\n└── a\n    └── b\nD:\\\n└── c\n", tree.to_string());
+        let walked: Vec<_> = tree.walk().map(|(depth, node)| (depth, node.name(), node.path().is_some())).collect();
+        assert_eq!(vec![(0, r"C:\", false), (1, "a", true), (2, "b", true), (0, r"D:\", false), (1, "c", true)], walked);
+    }
+}
@@ -0,0 +1,283 @@
+/*!
+This module contains the segment trie shared by the path collections,
+along with the `PathTrie` map.
+
+Each path is keyed by its variant, its root and its segments, so that
+insertion and lookup take time proportional to the number of segments
+rather than to the number of stored paths, and paths of different
+variants never share a node.
+*/
+
+use super::{flexible, FlexPath};
+use std::collections::BTreeMap;
+
+pub struct Node<V> {
+    children: BTreeMap<String, Node<V>>,
+    entry: Option<(FlexPath, V)>,
+}
+
+impl<V> Default for Node<V> {
+    fn default() -> Self {
+        Self { children: BTreeMap::new(), entry: None }
+    }
+}
+
+/// Returns the trie keys of a path: its root, if any, followed by its segments.
+///
+/// The root key always ends with a separator, so that `\\server\share`
+/// and `\\server\share\a` share the same root key.
+pub fn keys(path: &FlexPath) -> Vec<String> {
+    let root_len = flexible::root_len(&path.0, path.1);
+    let mut r = Vec::new();
+    if root_len != 0 {
+        r.push(path.0[..root_len].strip_suffix('/').unwrap_or(&path.0[..root_len]).to_owned() + "/");
+    }
+    r.extend(path.0[root_len..].split('/').filter(|s| !s.is_empty()).map(|s| s.to_owned()));
+    r
+}
+
+/// Returns the keys under which a path is stored in a [`Node`]: its
+/// variant followed by its [`keys`].
+pub fn node_keys(path: &FlexPath) -> Vec<String> {
+    let mut r = vec![format!("{:?}", path.1)];
+    r.extend(keys(path));
+    r
+}
+
+impl<V> Node<V> {
+    pub fn get(&self, keys: &[String]) -> Option<&Node<V>> {
+        keys.iter().try_fold(self, |node, key| node.children.get(key))
+    }
+
+    pub fn get_mut(&mut self, keys: &[String]) -> Option<&mut Node<V>> {
+        keys.iter().try_fold(self, |node, key| node.children.get_mut(key))
+    }
+
+    /// Returns the entry at the longest prefix of the given keys that has one.
+    pub fn best_match(&self, keys: &[String]) -> Option<&(FlexPath, V)> {
+        let mut node = self;
+        let mut r = node.entry.as_ref();
+        for key in keys {
+            let Some(child) = node.children.get(key) else {
+                break;
+            };
+            node = child;
+            r = node.entry.as_ref().or(r);
+        }
+        r
+    }
+
+    /// Indicates whether any proper, non-empty prefix of the given keys has an entry.
+    pub fn has_strict_ancestor(&self, keys: &[String]) -> bool {
+        let mut node = self;
+        for key in keys.iter().take(keys.len().saturating_sub(1)) {
+            let Some(child) = node.children.get(key) else {
+                return false;
+            };
+            node = child;
+            if node.entry.is_some() {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn entry(&self) -> Option<&(FlexPath, V)> {
+        self.entry.as_ref()
+    }
+
+    pub fn entry_mut(&mut self) -> Option<&mut (FlexPath, V)> {
+        self.entry.as_mut()
+    }
+
+    pub fn has_children(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Inserts an entry, returning the previous one at the same keys.
+    pub fn insert(&mut self, keys: &[String], path: FlexPath, value: V) -> Option<(FlexPath, V)> {
+        let node = keys.iter().fold(self, |node, key| node.children.entry(key.clone()).or_default());
+        node.entry.replace((path, value))
+    }
+
+    /// Removes the entry at the given keys, pruning emptied nodes.
+    pub fn remove(&mut self, keys: &[String]) -> Option<(FlexPath, V)> {
+        let Some((first, rest)) = keys.split_first() else {
+            return self.entry.take();
+        };
+        let child = self.children.get_mut(first)?;
+        let r = child.remove(rest);
+        if child.entry.is_none() && child.children.is_empty() {
+            self.children.remove(first);
+        }
+        r
+    }
+
+    /// Removes the node at the given keys with all of its descendants.
+    pub fn remove_subtree(&mut self, keys: &[String]) -> Option<Node<V>> {
+        let Some((first, rest)) = keys.split_first() else {
+            return Some(std::mem::take(self));
+        };
+        if rest.is_empty() {
+            return self.children.remove(first);
+        }
+        let child = self.children.get_mut(first)?;
+        let r = child.remove_subtree(rest);
+        if child.entry.is_none() && child.children.is_empty() {
+            self.children.remove(first);
+        }
+        r
+    }
+
+    /// Counts the entries in this node and its descendants.
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Iterates over the entries of this node and its descendants in segment order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter { stack: vec![self] }
+    }
+}
+
+pub struct Iter<'a, V> {
+    stack: Vec<&'a Node<V>>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = &'a (FlexPath, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            self.stack.extend(node.children.values().rev());
+            if let Some(entry) = &node.entry {
+                return Some(entry);
+            }
+        }
+        None
+    }
+}
+
+/// A map from paths to values organized by path segments, supporting
+/// longest-prefix-match lookup.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, PathTrie};
+/// let mut mounts = PathTrie::new();
+/// mounts.insert(FlexPath::new_common("/"), "root");
+/// mounts.insert(FlexPath::new_common("/a/b"), "b");
+/// assert_eq!(Some("b"), mounts.best_match(&FlexPath::new_common("/a/b/c")).map(|(_, v)| *v));
+/// assert_eq!(Some("root"), mounts.best_match(&FlexPath::new_common("/a/c")).map(|(_, v)| *v));
+/// ```
+pub struct PathTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for PathTrie<V> {
+    fn default() -> Self {
+        Self { root: Node::default(), len: 0 }
+    }
+}
+
+impl<V> PathTrie<V> {
+    /// Constructs an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of entries in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the trie contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value at `path`, returning the previous value at that path.
+    pub fn insert(&mut self, path: FlexPath, value: V) -> Option<V> {
+        let keys = node_keys(&path);
+        let r = self.root.insert(&keys, path, value).map(|(_, v)| v);
+        if r.is_none() {
+            self.len += 1;
+        }
+        r
+    }
+
+    /// Returns the value at exactly `path`.
+    pub fn get(&self, path: &FlexPath) -> Option<&V> {
+        self.root.get(&node_keys(path)).and_then(|node| node.entry()).map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value at exactly `path`.
+    pub fn get_mut(&mut self, path: &FlexPath) -> Option<&mut V> {
+        self.root.get_mut(&node_keys(path)).and_then(|node| node.entry_mut()).map(|(_, v)| v)
+    }
+
+    /// Removes the value at `path`, returning it.
+    pub fn remove(&mut self, path: &FlexPath) -> Option<V> {
+        let r = self.root.remove(&node_keys(path)).map(|(_, v)| v);
+        if r.is_some() {
+            self.len -= 1;
+        }
+        r
+    }
+
+    /// Returns the entry whose path is the longest prefix of `path`,
+    /// comparing whole segments, including `path` itself.
+    pub fn best_match(&self, path: &FlexPath) -> Option<(&FlexPath, &V)> {
+        self.root.best_match(&node_keys(path)).map(|(p, v)| (p, v))
+    }
+
+    /// Indicates whether the trie has an entry at a path strictly containing `path`.
+    pub fn has_strict_ancestor(&self, path: &FlexPath) -> bool {
+        self.root.has_strict_ancestor(&node_keys(path))
+    }
+
+    /// Iterates over the entries in segment order.
+    pub fn iter(&self) -> impl Iterator<Item = (&FlexPath, &V)> {
+        self.root.iter().map(|(p, v)| (p, v))
+    }
+}
+
+impl<V> FromIterator<(FlexPath, V)> for PathTrie<V> {
+    fn from_iter<T: IntoIterator<Item = (FlexPath, V)>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        for (path, value) in iter {
+            trie.insert(path, value);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn longest_prefix() {
+        let windows = FlexPathVariant::Windows;
+        let mut trie: PathTrie<u32> = [("C:/", 1), ("C:/a", 2), ("C:/ab/c", 3)].into_iter().map(|(p, v)| (FlexPath::new(p, windows), v)).collect();
+        assert_eq!(Some(&2), trie.best_match(&FlexPath::new(r"C:\a\b", windows)).map(|(_, v)| v));
+        assert_eq!(Some(&1), trie.best_match(&FlexPath::new(r"C:\ab", windows)).map(|(_, v)| v));
+        assert_eq!(None, trie.best_match(&FlexPath::new(r"D:\a", windows)));
+        *trie.get_mut(&FlexPath::new("C:/a", windows)).unwrap() = 4;
+        assert_eq!(Some(4), trie.remove(&FlexPath::new("C:/a", windows)));
+        assert_eq!(Some(&1), trie.best_match(&FlexPath::new(r"C:\a\b", windows)).map(|(_, v)| v));
+        assert_eq!(2, trie.len());
+
+        let mut trie = PathTrie::new();
+        trie.insert(FlexPath::new_common("/a"), "common");
+        trie.insert(FlexPath::new("/a", windows), "windows");
+        trie.insert(FlexPath::new_common("C:"), "common drive");
+        assert_eq!(3, trie.len());
+        assert_eq!(Some(&"common"), trie.get(&FlexPath::new_common("/a")));
+        assert_eq!(Some(&"windows"), trie.best_match(&FlexPath::new("/a/b", windows)).map(|(_, v)| v));
+        assert_eq!(None, trie.best_match(&FlexPath::new("C:/x", windows)));
+    }
+}
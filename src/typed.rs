@@ -0,0 +1,166 @@
+/*!
+This module contains the `AbsoluteFlexPath` and `RelativeFlexPath`
+wrappers, which guarantee absoluteness at the type level.
+*/
+
+use super::{FlexPath, FlexPathError};
+use std::ops::Deref;
+
+/// A `FlexPath` that is guaranteed to be absolute.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{AbsoluteFlexPath, FlexPath, RelativeFlexPath};
+/// let base = AbsoluteFlexPath::try_from(FlexPath::new_common("/a/b")).unwrap();
+/// let to = AbsoluteFlexPath::try_from(FlexPath::new_common("/a/c")).unwrap();
+/// assert_eq!("../c", base.relative(&to).unwrap());
+/// let rel = RelativeFlexPath::try_from(FlexPath::new_common("d")).unwrap();
+/// assert_eq!("/a/b/d", base.join(&rel).unwrap().to_string());
+/// assert!(AbsoluteFlexPath::try_from(FlexPath::new_common("a")).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbsoluteFlexPath(FlexPath);
+
+/// A `FlexPath` that is guaranteed to be relative.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelativeFlexPath(FlexPath);
+
+impl AbsoluteFlexPath {
+    /// Returns the wrapped path.
+    pub fn into_inner(self) -> FlexPath {
+        self.0
+    }
+
+    /// Finds the relative path from this path to `to_path`. Unlike
+    /// [`FlexPath::relative`], this does not panic, since both paths are
+    /// absolute.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if `to_path` has another
+    /// variant than this path.
+    pub fn relative(&self, to_path: &AbsoluteFlexPath) -> Result<String, FlexPathError> {
+        check_variant(&self.0, &to_path.0)?;
+        Ok(self.0.relative(&to_path.0.0))
+    }
+
+    /// Resolves a relative path against this path, which remains absolute.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if `path` has another
+    /// variant than this path.
+    pub fn join(&self, path: &RelativeFlexPath) -> Result<AbsoluteFlexPath, FlexPathError> {
+        check_variant(&self.0, &path.0)?;
+        Ok(Self(self.0.resolve(&path.0.0)))
+    }
+}
+
+impl RelativeFlexPath {
+    /// Returns the wrapped path.
+    pub fn into_inner(self) -> FlexPath {
+        self.0
+    }
+
+    /// Resolves another relative path against this path, which remains relative.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::VariantMismatch`] if `path` has another
+    /// variant than this path.
+    pub fn join(&self, path: &RelativeFlexPath) -> Result<RelativeFlexPath, FlexPathError> {
+        check_variant(&self.0, &path.0)?;
+        Ok(Self(self.0.resolve(&path.0.0)))
+    }
+}
+
+fn check_variant(path: &FlexPath, other: &FlexPath) -> Result<(), FlexPathError> {
+    if path.1 != other.1 {
+        return Err(FlexPathError::VariantMismatch { expected: path.1, found: other.1 });
+    }
+    Ok(())
+}
+
+/// Fails with [`FlexPathError::RelativePath`] if the path is relative.
+impl TryFrom<FlexPath> for AbsoluteFlexPath {
+    type Error = FlexPathError;
+
+    fn try_from(path: FlexPath) -> Result<Self, Self::Error> {
+        if path.is_absolute() { Ok(Self(path)) } else { Err(FlexPathError::RelativePath { path: path.to_string() }) }
+    }
+}
+
+/// Fails with [`FlexPathError::AbsolutePath`] if the path is absolute.
+impl TryFrom<FlexPath> for RelativeFlexPath {
+    type Error = FlexPathError;
+
+    fn try_from(path: FlexPath) -> Result<Self, Self::Error> {
+        if path.is_absolute() { Err(FlexPathError::AbsolutePath { path: path.to_string() }) } else { Ok(Self(path)) }
+    }
+}
+
+impl Deref for AbsoluteFlexPath {
+    type Target = FlexPath;
+
+    fn deref(&self) -> &FlexPath {
+        &self.0
+    }
+}
+
+impl Deref for RelativeFlexPath {
+    type Target = FlexPath;
+
+    fn deref(&self) -> &FlexPath {
+        &self.0
+    }
+}
+
+impl From<AbsoluteFlexPath> for FlexPath {
+    fn from(path: AbsoluteFlexPath) -> Self {
+        path.0
+    }
+}
+
+impl From<RelativeFlexPath> for FlexPath {
+    fn from(path: RelativeFlexPath) -> Self {
+        path.0
+    }
+}
+
+impl std::fmt::Display for AbsoluteFlexPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::fmt::Display for RelativeFlexPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn conversions() {
+        let windows = FlexPathVariant::Windows;
+        let abs = AbsoluteFlexPath::try_from(FlexPath::new(r"C:\a", windows)).unwrap();
+        let rel = RelativeFlexPath::try_from(FlexPath::new(r"b\..\c", windows)).unwrap();
+        assert_eq!(r"C:\a\c", abs.join(&rel).unwrap().to_string());
+        assert_eq!(r"c\d", rel.join(&RelativeFlexPath::try_from(FlexPath::new("d", windows)).unwrap()).unwrap().to_string());
+
+        let common_abs = AbsoluteFlexPath::try_from(FlexPath::new_common("/a")).unwrap();
+        let common_rel = RelativeFlexPath::try_from(FlexPath::new_common("b")).unwrap();
+        let mismatch = |found| FlexPathError::VariantMismatch { expected: windows, found };
+        assert_eq!(Err(mismatch(FlexPathVariant::Common)), abs.relative(&common_abs));
+        assert_eq!(Err(mismatch(FlexPathVariant::Common)), abs.join(&common_rel));
+        assert_eq!(Err(mismatch(FlexPathVariant::Common)), rel.join(&common_rel));
+        assert_eq!(Ok("../b".to_owned()), abs.relative(&AbsoluteFlexPath::try_from(FlexPath::new(r"C:\b", windows)).unwrap()));
+        assert!(matches!(RelativeFlexPath::try_from(FlexPath::new(r"\\srv\share", windows)), Err(FlexPathError::AbsolutePath { .. })));
+        assert_eq!(FlexPath::new("c", windows), FlexPath::from(rel));
+    }
+}
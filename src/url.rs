@@ -0,0 +1,133 @@
+/*!
+This module contains the conversion of paths to and from the path
+component of URLs, for serving files over HTTP.
+*/
+
+use super::{flexible, FlexPath, FlexPathError, FlexPathVariant};
+use lazy_regex::*;
+
+/// Options controlling how [`FlexPath::unescape_url_path`] decodes a URL path.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct UrlPathOptions {
+    /// Indicates whether `+` decodes to a space, as in HTML form data,
+    /// rather than to itself. This is `false` by default, following
+    /// RFC 3986.
+    pub plus_as_space: bool,
+}
+
+impl FlexPath {
+    /// Converts the path into the path component of a URL, percent-encoding
+    /// each segment as UTF-8 except for the characters RFC 3986 allows in a
+    /// segment as is (letters, digits, `-._~!$&'()*,;=:@`), and joining
+    /// the segments with `/`. A `+` is encoded too, so that the result
+    /// decodes alike whether or not `+` is taken as a space.
+    ///
+    /// An absolute path starts with `/`. For the `Windows` variant, a
+    /// drive path starts with `/C:/` and a UNC path with `//host/share/`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::{FlexPath, FlexPathVariant, UrlPathOptions};
+    /// let path = FlexPath::new_common("/docs/a b/100%+ü.txt");
+    /// let url_path = path.escape_for_url_path();
+    /// assert_eq!("/docs/a%20b/100%25%2B%C3%BC.txt", url_path);
+    /// assert_eq!(path, FlexPath::unescape_url_path(&url_path, FlexPathVariant::Common, UrlPathOptions::default()).unwrap());
+    /// assert_eq!("/C:/a%23b", FlexPath::new(r"C:\a#b", FlexPathVariant::Windows).escape_for_url_path());
+    /// ```
+    pub fn escape_for_url_path(&self) -> String {
+        let text = flexible::canonical(&self.0, self.1);
+        let mut r = String::with_capacity(text.len() + 1);
+        if self.1 == FlexPathVariant::Windows && regex_is_match!(r"^[A-Za-z]:", &text) {
+            r.push('/');
+        }
+        for byte in text.bytes() {
+            if byte.is_ascii_alphanumeric() || b"/-._~!$&'()*,;=:@".contains(&byte) {
+                r.push(byte as char);
+            } else {
+                r.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        r
+    }
+
+    /// Converts the path component of a URL into a path of the given
+    /// variant, decoding percent-encoded segments and resolving `.` and `..`
+    /// segments, so that `..` never climbs above the root.
+    ///
+    /// For the `Windows` variant, a path starting with a drive, as in
+    /// `/C:/a`, is taken as a drive path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlexPathError::InvalidEncoding`] if a percent sign is not
+    /// followed by two hexadecimal digits or a segment does not decode to
+    /// UTF-8, and [`FlexPathError::InvalidSegment`] if a segment decodes to
+    /// text containing a separator or a NUL character, such as `%2F`, as
+    /// these would not be taken literally.
+    pub fn unescape_url_path(path: &str, variant: FlexPathVariant, options: UrlPathOptions) -> Result<FlexPath, FlexPathError> {
+        let mut r = String::with_capacity(path.len());
+        for (i, segment) in path.split('/').enumerate() {
+            if i != 0 {
+                r.push('/');
+            }
+            let decoded = decode_segment(segment, options)?;
+            if decoded.contains(['/', '\\', '\0']) {
+                return Err(FlexPathError::InvalidSegment { segment: decoded, reason: "the segment contains a separator or a NUL character" });
+            }
+            r.push_str(&decoded);
+        }
+        let drive = variant == FlexPathVariant::Windows && regex_is_match!(r"^/[A-Za-z]:(/|$)", &r);
+        Ok(FlexPath::new(if drive { &r[1..] } else { &r }, variant))
+    }
+}
+
+fn decode_segment(segment: &str, options: UrlPathOptions) -> Result<String, FlexPathError> {
+    let invalid = |reason| FlexPathError::InvalidEncoding { reason };
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut input = segment.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let digits = [input.next(), input.next()];
+                let hex = |digit: Option<u8>| digit.and_then(|d| (d as char).to_digit(16));
+                match digits.map(hex) {
+                    [Some(high), Some(low)] => bytes.push((high * 16 + low) as u8),
+                    _ => return Err(invalid("percent sign not followed by two hexadecimal digits")),
+                }
+            },
+            b'+' if options.plus_as_space => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid("segment is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn url_paths() {
+        let windows = FlexPathVariant::Windows;
+        let common = FlexPathVariant::Common;
+        let plus = UrlPathOptions { plus_as_space: true };
+        for path in ["", "/", "a/b", "/é/[x]?#"] {
+            let path = FlexPath::new_common(path);
+            assert_eq!(path, FlexPath::unescape_url_path(&path.escape_for_url_path(), common, plus).unwrap());
+        }
+        for path in [r"C:\", r"C:\a", r"\\?\C:\a", r"\\srv\share\a b", r"\\srv\share", r"\a"] {
+            let path = FlexPath::new(path, windows);
+            assert_eq!(path, FlexPath::unescape_url_path(&path.escape_for_url_path(), windows, plus).unwrap(), "{path}");
+        }
+        assert_eq!("/", FlexPath::new_common("/").escape_for_url_path());
+        assert_eq!("/C:/", FlexPath::new("C:/", windows).escape_for_url_path());
+        assert_eq!("//srv/share", FlexPath::new(r"\\srv\share", windows).escape_for_url_path());
+        assert_eq!(FlexPath::new_common("/a b"), FlexPath::unescape_url_path("/a+b", common, plus).unwrap());
+        assert_eq!(FlexPath::new_common("/a+b"), FlexPath::unescape_url_path("/a+b", common, UrlPathOptions::default()).unwrap());
+        assert_eq!(FlexPath::new_common("/b"), FlexPath::unescape_url_path("/../%2E%2E/b", common, plus).unwrap());
+        assert!(matches!(FlexPath::unescape_url_path("/a%2Fb", common, plus), Err(FlexPathError::InvalidSegment { .. })));
+        assert!(matches!(FlexPath::unescape_url_path("/a%2", common, plus), Err(FlexPathError::InvalidEncoding { .. })));
+        assert!(matches!(FlexPath::unescape_url_path("/%FF", common, plus), Err(FlexPathError::InvalidEncoding { .. })));
+    }
+}
@@ -0,0 +1,122 @@
+/*!
+This module contains the `WasiPreopens` table, which maps paths
+requested by a WASI guest to its preopened directories.
+*/
+
+use super::{report, trie, FlexPath, FlexPathVariant, ResolveEditKind};
+
+/// The directories preopened for a WASI guest, by which it reaches the
+/// file system, mapping each path the guest requests to a preopen and a
+/// path relative to it.
+///
+/// Guest paths always use the `Common` variant. A path maps to the
+/// preopen whose name is its longest prefix over whole segments, after
+/// resolving `.` and `..` textually, so that a path such as
+/// `/data/../etc` cannot escape into a directory that was not preopened.
+/// Absolute paths only map to absolute preopen names, and relative
+/// paths to relative names such as `.`, which covers every relative path
+/// except one that climbs out of it, such as `../secret`, which maps to
+/// no preopen.
+///
+/// Preopens are identified by their index in the order they were added;
+/// a host typically numbers the descriptor of the preopen at index `i`
+/// as `3 + i`.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, WasiPreopens};
+/// let mut preopens = WasiPreopens::new();
+/// preopens.push("/data");
+/// preopens.push("/data/cache");
+/// preopens.push(".");
+/// assert_eq!(Some((1, FlexPath::new_common("a/b"))), preopens.find("/data/cache/a/./b"));
+/// assert_eq!(Some((0, FlexPath::new_common("cached"))), preopens.find("/data/cached"));
+/// assert_eq!(Some((2, FlexPath::new_common("x"))), preopens.find("x"));
+/// assert_eq!(None, preopens.find("/data/../etc/passwd"));
+/// assert_eq!(None, preopens.find("../secret"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WasiPreopens {
+    names: Vec<FlexPath>,
+}
+
+impl WasiPreopens {
+    /// Constructs an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a preopen by the name the guest sees, such as `/data` or `.`,
+    /// returning its index. A preopen added later takes precedence over
+    /// an earlier one of the same name.
+    pub fn push(&mut self, name: &str) -> usize {
+        self.names.push(FlexPath::new_common(name));
+        self.names.len() - 1
+    }
+
+    /// Returns the name of the preopen at `index`.
+    pub fn get(&self, index: usize) -> Option<&FlexPath> {
+        self.names.get(index)
+    }
+
+    /// Returns the number of preopens.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Indicates whether there are no preopens.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over the preopens as `(index, name)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &FlexPath)> {
+        self.names.iter().enumerate()
+    }
+
+    /// Maps a path requested by the guest to the index of the preopen it
+    /// falls under and the path relative to that preopen, which is empty
+    /// for the preopen itself. Returns `None` if the path falls under no
+    /// preopen.
+    pub fn find(&self, path: &str) -> Option<(usize, FlexPath)> {
+        // resolution drops a `..` that climbs above the start of a relative
+        // path, which must not map into the preopen instead.
+        let (_, report) = report::scan(None, path, FlexPathVariant::Common);
+        let path = FlexPath::new_common(path);
+        if !path.is_absolute() && report.edits.iter().any(|edit| edit.kind == ResolveEditKind::ParentDir { removed: None }) {
+            return None;
+        }
+        let keys = trie::keys(&path);
+        let (index, prefix_len) = self.names.iter().enumerate()
+            .filter(|(_, name)| name.is_absolute() == path.is_absolute())
+            .map(|(i, name)| (i, trie::keys(name)))
+            .filter(|(_, name_keys)| keys.starts_with(name_keys))
+            .map(|(i, name_keys)| (i, name_keys.len()))
+            .max_by_key(|&(_, len)| len)?;
+        Some((index, FlexPath::new_common("").join_segments(&keys[prefix_len..])))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn preopens() {
+        let mut preopens = WasiPreopens::new();
+        assert_eq!(None, preopens.find("/"));
+        preopens.push("/");
+        preopens.push("/tmp/");
+        preopens.push("/tmp");
+        assert_eq!(Some((2, FlexPath::new_common(""))), preopens.find("/tmp/x/.."));
+        assert_eq!(Some((0, FlexPath::new_common("etc"))), preopens.find("/tmp/../etc"));
+        assert_eq!(None, preopens.find("relative"));
+        preopens.push(".");
+        assert_eq!(Some((3, FlexPath::new_common("secret"))), preopens.find("a/../secret"));
+        assert_eq!(None, preopens.find("../secret"));
+        assert_eq!(None, preopens.find("a/../../secret"));
+        assert_eq!(4, preopens.len());
+        assert_eq!(Some(&FlexPath::new_common("/tmp")), preopens.get(1));
+    }
+}
@@ -0,0 +1,127 @@
+/*!
+This module contains the normalization of paths reported by file
+watchers, available with the `fs` feature.
+*/
+
+use super::{case, flexible, trie, FlexPath, FlexPathVariant, PathCmpOptions, VerbatimPolicy};
+use std::path::Path;
+
+/// A watched directory against which raw event paths are normalized.
+///
+/// File watchers report paths inconsistently: in the Windows operating
+/// system they may arrive in the verbatim (`\\?\`) form, with a different
+/// letter case than the watched directory or with 8.3 short names.
+/// [`.normalize`] turns them into paths comparable with the watched directory.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{FlexPath, WatchRoot};
+/// let root = WatchRoot::new(FlexPath::new_native(env!("CARGO_MANIFEST_DIR")));
+/// let event = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("lib.rs");
+/// assert_eq!(Some(FlexPath::new_native("src/lib.rs")), root.relative_to_root(&event));
+/// ```
+#[derive(Clone, Debug)]
+pub struct WatchRoot {
+    root: FlexPath,
+    options: PathCmpOptions,
+}
+
+impl WatchRoot {
+    /// Constructs a watched directory from a native path, comparing
+    /// paths according to the conventions of the native variant.
+    pub fn new(root: FlexPath) -> Self {
+        let options = PathCmpOptions::for_variant(root.1);
+        Self::with_options(root, options)
+    }
+
+    /// Constructs a watched directory, comparing paths according to `options`.
+    pub fn with_options(root: FlexPath, options: PathCmpOptions) -> Self {
+        let root = FlexPath::from_resolved(flexible::simplify_verbatim(&root.0, root.1), root.1);
+        Self { root, options }
+    }
+
+    /// Returns the watched directory.
+    pub fn root(&self) -> &FlexPath {
+        &self.root
+    }
+
+    /// Normalizes a raw event path, returning `None` if it is not valid
+    /// UTF-8 or does not lie under the watched directory.
+    ///
+    /// The verbatim form is simplified, segments that look like 8.3
+    /// short names are expanded through the file system when the path
+    /// still exists, and the watched directory part takes the spelling
+    /// of the watched directory. The expansion is discarded if it changes
+    /// any other segment, as when a symbolic link is followed.
+    pub fn normalize(&self, raw: &Path) -> Option<FlexPath> {
+        let segments = self.segments_under_root(raw)?;
+        Some(self.root.join_segments(&segments))
+    }
+
+    /// Normalizes a raw event path like [`.normalize`], returning it
+    /// relative to the watched directory.
+    pub fn relative_to_root(&self, raw: &Path) -> Option<FlexPath> {
+        let segments = self.segments_under_root(raw)?;
+        Some(FlexPath::from_resolved(segments.join("/"), self.root.1))
+    }
+
+    fn segments_under_root(&self, raw: &Path) -> Option<Vec<String>> {
+        let mut path = FlexPath::new(raw.to_str()?, FlexPathVariant::NATIVE);
+        path = FlexPath::from_resolved(flexible::simplify_verbatim(&path.0, path.1), path.1);
+        if path.looks_like_short_name() {
+            if let Ok(expanded) = path.canonicalize(VerbatimPolicy::Simplify) {
+                if only_short_names_expanded(&path, &expanded) {
+                    path = expanded;
+                }
+            }
+        }
+        let fold = |keys: Vec<String>| -> Vec<String> {
+            if self.options.case_sensitive { keys } else { keys.iter().map(|key| case::fold(key)).collect() }
+        };
+        let root_keys = fold(trie::keys(&self.root));
+        let keys = trie::keys(&path);
+        (fold(keys.clone()).starts_with(&root_keys)).then(|| keys[root_keys.len()..].to_vec())
+    }
+}
+
+/// Indicates whether `expanded` differs from `path` only in segments of
+/// `path` that look like 8.3 short names.
+fn only_short_names_expanded(path: &FlexPath, expanded: &FlexPath) -> bool {
+    let (keys, expanded_keys) = (trie::keys(path), trie::keys(expanded));
+    keys.len() == expanded_keys.len() && keys.iter().zip(&expanded_keys).all(|(key, expanded_key)| {
+        crate::is_short_name(key) || case::eq_folded(key, expanded_key)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn outside_root() {
+        let root = WatchRoot::new(FlexPath::new_native("/watched/dir"));
+        assert_eq!(None, root.normalize(Path::new("/watched/other")));
+        assert_eq!(None, root.normalize(Path::new("/watched/directory")));
+        assert_eq!(Some(FlexPath::new_native("/watched/dir")), root.normalize(Path::new("/watched/dir/")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn short_names_through_links() {
+        let windows = FlexPathVariant::Windows;
+        assert!(only_short_names_expanded(&FlexPath::new(r"C:\PROGRA~1\a", windows), &FlexPath::new(r"c:\Program Files\A", windows)));
+        assert!(!only_short_names_expanded(&FlexPath::new(r"C:\PROGRA~1\a", windows), &FlexPath::new(r"D:\Program Files\a", windows)));
+
+        let temp = FlexPath::new(std::env::temp_dir().to_str().unwrap(), FlexPathVariant::NATIVE);
+        let outside = temp.create_random_dir("hydroperx-", "").unwrap();
+        let watched = temp.create_random_dir("hydroperx-", "").unwrap();
+        std::fs::write(outside.resolve("x").native_path().unwrap(), "").unwrap();
+        std::os::unix::fs::symlink(outside.native_path().unwrap(), watched.resolve("LINK~1").native_path().unwrap()).unwrap();
+        let root = WatchRoot::new(watched.clone());
+        let event = watched.resolve("LINK~1/x").native_path().unwrap();
+        assert_eq!(Some(FlexPath::new_native("LINK~1/x")), root.relative_to_root(&event));
+        std::fs::remove_dir_all(outside.native_path().unwrap()).unwrap();
+        std::fs::remove_dir_all(watched.native_path().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+/*!
+This module contains the lookup of executables in `PATH`-style lists.
+*/
+
+use super::{has_extension, split_path_list, ExtensionOptions, FlexPath, FlexPathVariant};
+
+/// Returns the paths at which a command named `name` may be found
+/// through a `PATH`-style list, in the order a shell tries them, without
+/// accessing the file system.
+///
+/// For `Windows`, `pathext` lists the extensions of executables,
+/// separated by semicolons as in the `PATHEXT` environment variable;
+/// unless `name` already has one of them, each is appended in turn.
+/// `pathext` is ignored for `Common`.
+///
+/// A name containing a separator is not searched for, yielding solely
+/// itself. For `Common`, only `/` is a separator there, and a backslash is
+/// an ordinary character of the name.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{executable_candidates, FlexPathVariant};
+/// let windows = FlexPathVariant::Windows;
+/// let candidates = executable_candidates("git", r"C:\bin;D:\tools", Some(".COM;.EXE"), windows);
+/// let candidates: Vec<String> = candidates.iter().map(|p| p.to_string()).collect();
+/// assert_eq!(vec![r"C:\bin\git.COM", r"C:\bin\git.EXE", r"D:\tools\git.COM", r"D:\tools\git.EXE"], candidates);
+/// assert_eq!(1, executable_candidates("git.exe", r"C:\bin", Some(".COM;.EXE"), windows).len());
+/// ```
+pub fn executable_candidates(name: &str, path_list: &str, pathext: Option<&str>, variant: FlexPathVariant) -> Vec<FlexPath> {
+    let separators: &[char] = if variant == FlexPathVariant::Windows { &['/', '\\'] } else { &['/'] };
+    if name.contains(separators) {
+        return vec![FlexPath::new(name, variant)];
+    }
+    let extensions: Vec<&str> = match (variant, pathext) {
+        (FlexPathVariant::Windows, Some(pathext)) => pathext.split(';').filter(|ext| !ext.is_empty()).collect(),
+        _ => Vec::new(),
+    };
+    let names: Vec<String> = if extensions.is_empty() || extensions.iter().any(|ext| has_extension(name, ext, ExtensionOptions::default())) {
+        vec![name.to_owned()]
+    } else {
+        extensions.iter().map(|ext| name.to_owned() + ext).collect()
+    };
+    split_path_list(path_list, variant).iter()
+        .flat_map(|dir| names.iter().map(|name| match variant {
+            FlexPathVariant::Common if !matches!(name.as_str(), "." | "..") => dir.join_segments(&[name]),
+            _ => dir.resolve(name),
+        }))
+        .collect()
+}
+
+/// Finds the files at which a command named `name` may be found through
+/// a `PATH`-style list of the native variant, as by
+/// [`executable_candidates`], in the order a shell tries them.
+///
+/// Candidates that do not exist or cannot be checked are skipped.
+///
+/// # Example
+///
+/// ```
+/// use hydroperx_path::{find_in_path, FlexPath};
+/// let dir = FlexPath::new_native(env!("CARGO_MANIFEST_DIR"));
+/// let found = find_in_path("Cargo.toml", &dir.to_string(), None);
+/// assert_eq!(vec![dir.resolve("Cargo.toml")], found);
+/// ```
+#[cfg(feature = "fs")]
+pub fn find_in_path(name: &str, path_list: &str, pathext: Option<&str>) -> Vec<FlexPath> {
+    executable_candidates(name, path_list, pathext, FlexPathVariant::NATIVE).into_iter()
+        .filter(|path| path.is_file().unwrap_or(false))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn candidates() {
+        let common = FlexPathVariant::Common;
+        let names = |name, list| executable_candidates(name, list, Some(".exe"), common).iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["/bin/ls", "ls", "/usr/bin/ls"], names("ls", "/bin::/usr/bin"));
+        assert_eq!(vec!["bin/ls"], names("./bin/ls", "/bin"));
+        assert_eq!(vec![r"/bin/a\b"], names(r"a\b", "/bin"));
+
+        let windows = FlexPathVariant::Windows;
+        let candidates = executable_candidates("Setup.Exe", "C:/a", Some(".EXE;;.BAT"), windows);
+        assert_eq!(vec![FlexPath::new(r"C:\a\Setup.Exe", windows)], candidates);
+        assert_eq!(1, executable_candidates("cmd", "C:/a", None, windows).len());
+    }
+}
@@ -0,0 +1,71 @@
+/*!
+This module contains the display width helpers, available with the
+`unicode` feature.
+*/
+
+use super::FlexPath;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+impl FlexPath {
+    /// Returns the number of terminal columns the path occupies when
+    /// formatted, counting wide characters such as CJK ideographs as two
+    /// columns and combining characters as none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// assert_eq!(6, FlexPath::new_common("a/日本").display_width());
+    /// assert_eq!(5, FlexPath::new_common("e\u{301}.txt").display_width());
+    /// ```
+    pub fn display_width(&self) -> usize {
+        self.to_string().width()
+    }
+
+    /// Formats the path to fit in `max_width` terminal columns, replacing
+    /// its beginning by an ellipsis (`…`) if it is wider, so that the file
+    /// name remains visible. Grapheme clusters, such as a letter with
+    /// combining accents or an emoji sequence, are never split.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hydroperx_path::FlexPath;
+    /// let path = FlexPath::new_common("/home/user/日本.txt");
+    /// assert_eq!("…/日本.txt", path.elide_to_width(10));
+    /// assert_eq!("/home/user/日本.txt", path.elide_to_width(40));
+    /// ```
+    pub fn elide_to_width(&self, max_width: usize) -> String {
+        let text = self.to_string();
+        if text.width() <= max_width {
+            return text;
+        }
+        let mut width = 1;
+        let mut start = text.len();
+        for (i, grapheme) in text.grapheme_indices(true).rev() {
+            width += grapheme.width();
+            if width > max_width {
+                break;
+            }
+            start = i;
+        }
+        if max_width == 0 { String::new() } else { "…".to_owned() + &text[start..] }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlexPathVariant;
+
+    #[test]
+    fn graphemes() {
+        let path = FlexPath::new("C:\\x\\e\u{301}e\u{301}", FlexPathVariant::Windows);
+        assert_eq!(7, path.display_width());
+        assert_eq!("…e\u{301}", path.elide_to_width(2));
+        assert_eq!("…", path.elide_to_width(1));
+        assert_eq!("", path.elide_to_width(0));
+        assert_eq!("…\\x\\e\u{301}e\u{301}", path.elide_to_width(6));
+    }
+}